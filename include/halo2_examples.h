@@ -0,0 +1,19 @@
+#ifndef HALO2_EXAMPLES_H
+#define HALO2_EXAMPLES_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* Return codes, see src/ffi.rs. */
+#define HO_OK 0
+#define HO_INVALID_ARGUMENT 1
+#define HO_PROVE_FAILED 2
+#define HO_VERIFY_FAILED 3
+#define HO_INVALID_PROOF 4
+#define HO_PANIC 5
+
+int32_t ho_prove_sort(const uint64_t *values, size_t len, uint8_t **proof, size_t *proof_len);
+int32_t ho_verify_sort(const uint64_t *values, size_t len, const uint8_t *proof, size_t proof_len);
+void ho_free_proof(uint8_t *proof, size_t proof_len);
+
+#endif
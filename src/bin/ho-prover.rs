@@ -0,0 +1,123 @@
+use std::{collections::HashMap, env, fs, process};
+
+use halo2_examples::{bindings, witness::WitnessInput};
+
+const USAGE: &str = "\
+Proves that a list of u64 values sorts to its ascending order.
+
+Usage:
+    ho-prover setup --n <count> [--k <k>] --out <vk.bin> [--keys <dir>]
+    ho-prover prove --witness <witness.json> --out <proof.bin> [--keys <dir>]
+    ho-prover verify --witness <witness.json> --proof <proof.bin> [--vk <vk.bin>] [--keys <dir>]
+    ho-prover mock --witness <witness.json>
+
+The count of values is a power of two from 2 to 64, and the witness is a
+WitnessInput, {\"values\": [\"3\", \"0x01\", ..]}, whose options, if
+given, must be those of the circuit: 8-byte values, duplicates allowed.
+The params are IPA, generated from the count alone with no trusted setup,
+so setup only writes the verifying key, which spares verify --vk the
+keygen of the proving key. The k of the params follows from the count too;
+--k, if given, must be that k.
+With --keys, the keys are read from the directory, or generated and
+written to it the first time.";
+
+/// Returns the --flag value pairs of the arguments.
+fn parse_flags(args: &[String]) -> Result<HashMap<&str, &str>, String> {
+    let pairs = args.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err("expected --flag value pairs".into());
+    }
+    pairs
+        .map(|pair| match pair[0].strip_prefix("--") {
+            Some(flag) => Ok((flag, pair[1].as_str())),
+            None => Err(format!("unexpected argument {}", pair[0])),
+        })
+        .collect()
+}
+
+fn flag<'a>(flags: &HashMap<&str, &'a str>, name: &str) -> Result<&'a str, String> {
+    flags
+        .get(name)
+        .copied()
+        .ok_or_else(|| format!("missing --{}", name))
+}
+
+fn read_witness(path: &str) -> Result<Vec<u64>, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    WitnessInput::from_json(&json)
+        .and_then(|witness| bindings::witness_values(&witness))
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (command, flags) = match args.split_first() {
+        Some((command, flags)) => (command.as_str(), parse_flags(flags)?),
+        None => return Err(USAGE.into()),
+    };
+    if let Some(dir) = flags.get("keys") {
+        bindings::cache_keys_in(dir);
+    }
+    match command {
+        "setup" => {
+            let n = flag(&flags, "n")?
+                .parse()
+                .map_err(|e| format!("--n: {}", e))?;
+            if let Some(k) = flags.get("k") {
+                let k: u32 = k.parse().map_err(|e| format!("--k: {}", e))?;
+                let expected = bindings::sort_k(n).map_err(|e| e.to_string())?;
+                if k != expected {
+                    return Err(format!(
+                        "--k: sorting {} values needs k = {}, not {}",
+                        n, expected, k
+                    ));
+                }
+            }
+            let vk = bindings::sort_vk(n).map_err(|e| e.to_string())?;
+            let out = flag(&flags, "out")?;
+            fs::write(out, vk).map_err(|e| format!("{}: {}", out, e))
+        }
+        "prove" => {
+            let values = read_witness(flag(&flags, "witness")?)?;
+            let proof = bindings::prove_sort(&values).map_err(|e| e.to_string())?;
+            let out = flag(&flags, "out")?;
+            fs::write(out, proof).map_err(|e| format!("{}: {}", out, e))
+        }
+        "verify" => {
+            let values = read_witness(flag(&flags, "witness")?)?;
+            let path = flag(&flags, "proof")?;
+            let proof = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+            let verified = match flags.get("vk") {
+                Some(path) => {
+                    let vk = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+                    bindings::verify_sort_with_vk(&values, &proof, &vk)
+                }
+                None => bindings::verify_sort(&values, &proof),
+            };
+            verified.map_err(|e| format!("invalid proof: {}", e))?;
+            println!("ok");
+            Ok(())
+        }
+        "mock" => {
+            let values = read_witness(flag(&flags, "witness")?)?;
+            let failures = bindings::mock_sort(&values).map_err(|e| e.to_string())?;
+            for failure in &failures {
+                println!("{}", failure);
+            }
+            if failures.is_empty() {
+                println!("ok");
+                Ok(())
+            } else {
+                Err(format!("{} constraints failed", failures.len()))
+            }
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use halo2_examples::{bindings, witness::WitnessInput};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    net::TcpListener,
+    sync::{
+        mpsc::{self, error::TrySendError},
+        Mutex,
+    },
+    task,
+};
+
+/// The state of a proving job.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum Job {
+    Queued,
+    Running,
+    /// The 0x-prefixed hex proof.
+    Done {
+        proof: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// The id and values of a queued job.
+type Queued = (u64, Vec<u64>);
+
+/// The most jobs waiting for a worker, beyond which /prove is refused.
+const MAX_QUEUED: usize = 1024;
+
+/// The most finished jobs kept, beyond which the oldest are dropped.
+const MAX_FINISHED: usize = 4096;
+
+/// The states of the jobs, keeping the MAX_FINISHED latest finished ones.
+#[derive(Default)]
+struct Jobs {
+    states: HashMap<u64, Job>,
+    // the finished jobs, oldest first
+    finished: VecDeque<u64>,
+}
+
+impl Jobs {
+    /// Records the outcome of a job, dropping the oldest finished job if
+    /// there are too many.
+    fn finish(&mut self, id: u64, job: Job) {
+        self.states.insert(id, job);
+        self.finished.push_back(id);
+        if self.finished.len() > MAX_FINISHED {
+            if let Some(oldest) = self.finished.pop_front() {
+                self.states.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// The jobs and the queue of the workers.
+#[derive(Clone)]
+struct Service {
+    jobs: Arc<Mutex<Jobs>>,
+    next_id: Arc<AtomicU64>,
+    queue: mpsc::Sender<Queued>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyRequest {
+    witness: WitnessInput,
+    /// The 0x-prefixed hex proof.
+    proof: String,
+}
+
+type Response<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn bad_request(e: impl ToString) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, e.to_string())
+}
+
+/// Queues the proof of a WitnessInput, returning the id of its job, or 503
+/// if the queue is full.
+async fn prove(
+    State(service): State<Service>,
+    Json(witness): Json<WitnessInput>,
+) -> Response<Value> {
+    let values = bindings::witness_values(&witness).map_err(bad_request)?;
+    let id = service.next_id.fetch_add(1, Ordering::Relaxed);
+    // the job is recorded first, as a worker may pick it up at once
+    service.jobs.lock().await.states.insert(id, Job::Queued);
+    if let Err(e) = service.queue.try_send((id, values)) {
+        service.jobs.lock().await.states.remove(&id);
+        let error = match e {
+            TrySendError::Full(_) => "the queue is full",
+            TrySendError::Closed(_) => "the workers stopped",
+        };
+        return Err((StatusCode::SERVICE_UNAVAILABLE, error.to_string()));
+    }
+    Ok(Json(json!({ "job": id })))
+}
+
+/// Returns the state of a job, with its proof once done.
+async fn job(State(service): State<Service>, Path(id): Path<u64>) -> Response<Job> {
+    match service.jobs.lock().await.states.get(&id) {
+        Some(job) => Ok(Json(job.clone())),
+        None => Err((StatusCode::NOT_FOUND, format!("no job {}", id))),
+    }
+}
+
+/// Verifies a proof of a WitnessInput.
+async fn verify(Json(request): Json<VerifyRequest>) -> Response<Value> {
+    let values = bindings::witness_values(&request.witness).map_err(bad_request)?;
+    let proof = request.proof.strip_prefix("0x").unwrap_or(&request.proof);
+    let proof = hex::decode(proof).map_err(bad_request)?;
+    let valid = task::spawn_blocking(move || bindings::verify_sort(&values, &proof).is_ok())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(json!({ "valid": valid })))
+}
+
+/// Proves the queued jobs one at a time, off the async runtime.
+async fn worker(service: Service, queue: Arc<Mutex<mpsc::Receiver<Queued>>>) {
+    loop {
+        let next = queue.lock().await.recv().await;
+        let (id, values) = match next {
+            Some(next) => next,
+            None => return,
+        };
+        service.jobs.lock().await.states.insert(id, Job::Running);
+        let job = match task::spawn_blocking(move || bindings::prove_sort(&values)).await {
+            Ok(Ok(proof)) => Job::Done {
+                proof: format!("0x{}", hex::encode(proof)),
+            },
+            Ok(Err(e)) => Job::Failed {
+                error: e.to_string(),
+            },
+            Err(e) => Job::Failed {
+                error: e.to_string(),
+            },
+        };
+        service.jobs.lock().await.finish(id, job);
+    }
+}
+
+/// Serves POST /prove, GET /jobs/:id and POST /verify on HO_SERVICE_ADDR,
+/// 127.0.0.1:3000 by default, with HO_SERVICE_WORKERS proving workers, 2 by
+/// default. The keys of every supported number of values are built before
+/// serving, so that no request waits on a keygen.
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let addr = env::var("HO_SERVICE_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+    let workers = match env::var("HO_SERVICE_WORKERS") {
+        Ok(workers) => workers
+            .parse()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+        Err(_) => 2,
+    };
+
+    task::spawn_blocking(|| {
+        bindings::SORT_LENS
+            .iter()
+            .try_for_each(|n| bindings::setup_sort(*n))
+    })
+    .await?
+    .map_err(|e| std::io::Error::other(format!("{:?}", e)))?;
+
+    let (sender, receiver) = mpsc::channel(MAX_QUEUED);
+    let service = Service {
+        jobs: Arc::new(Mutex::new(Jobs::default())),
+        next_id: Arc::new(AtomicU64::new(0)),
+        queue: sender,
+    };
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..workers {
+        tokio::spawn(worker(service.clone(), receiver.clone()));
+    }
+
+    let app = Router::new()
+        .route("/prove", post(prove))
+        .route("/jobs/:id", get(job))
+        .route("/verify", post(verify))
+        .with_state(service);
+    axum::serve(TcpListener::bind(&addr).await?, app).await
+}
@@ -0,0 +1,256 @@
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    pasta::{EqAffine, Fp},
+    plonk::{Error, ProvingKey},
+    poly::commitment::Params,
+};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crate::{
+    gadgets::sort::{SortCircuitBuilder, SortNCircuit},
+    prover::{self, KeyCache},
+    witness::{WitnessError, WitnessInput},
+};
+
+/// The circuit of the language bindings, sorting N u64 values, duplicates
+/// allowed, in ascending order.
+type SortCircuit<const N: usize> = SortNCircuit<Fp, N, 8, true>;
+
+/// The params and proving key of a SortCircuit.
+type Keys = Arc<(Params<EqAffine>, ProvingKey<EqAffine>)>;
+
+/// The keys of every number of values built so far in the process.
+static KEYS: Mutex<Vec<(usize, Keys)>> = Mutex::new(Vec::new());
+
+/// The directory the keys are cached in across processes, if any.
+static KEY_CACHE: Mutex<Option<KeyCache>> = Mutex::new(None);
+
+/// The numbers of values the bindings sort, as the circuit shape is fixed at
+/// compile time.
+pub const SORT_LENS: [usize; 6] = [2, 4, 8, 16, 32, 64];
+
+/// Calls f with the const N of the number of values, which must be one of
+/// SORT_LENS.
+macro_rules! with_len {
+    ($len:expr, $f:ident($($arg:expr),*)) => {
+        match $len {
+            2 => $f::<2>($($arg),*),
+            4 => $f::<4>($($arg),*),
+            8 => $f::<8>($($arg),*),
+            16 => $f::<16>($($arg),*),
+            32 => $f::<32>($($arg),*),
+            64 => $f::<64>($($arg),*),
+            _ => Err(Error::Synthesis),
+        }
+    };
+}
+
+/// Returns the proof that the values sort to their ascending order, the
+/// public inputs being the values followed by the sorted values.
+///
+/// The params and keys are generated on the first call for the number of
+/// values and reused afterwards. Keygen is deterministic for IPA, so the
+/// verifier regenerates the same ones.
+pub fn prove_sort(values: &[u64]) -> Result<Vec<u8>, Error> {
+    with_len!(values.len(), prove(values))
+}
+
+/// Verifies a proof of prove_sort for the values.
+pub fn verify_sort(values: &[u64], proof: &[u8]) -> Result<(), Error> {
+    with_len!(values.len(), verify(values, proof))
+}
+
+/// Returns the verifying key of the circuit sorting n values, for
+/// verify_sort_with_vk.
+pub fn sort_vk(n: usize) -> Result<Vec<u8>, Error> {
+    with_len!(n, vk())
+}
+
+/// Returns the k of the params of the circuit sorting n values, the least
+/// one its rows fit in.
+pub fn sort_k(n: usize) -> Result<u32, Error> {
+    with_len!(n, k())
+}
+
+/// Verifies a proof of prove_sort for the values against a verifying key of
+/// sort_vk, which is rebuilt with keygen_vk, skipping the keygen_pk of
+/// verify_sort.
+pub fn verify_sort_with_vk(values: &[u64], proof: &[u8], vk: &[u8]) -> Result<(), Error> {
+    with_len!(values.len(), verify_with_vk(values, proof, vk))
+}
+
+/// Builds the keys of the circuit sorting n values ahead of the first proof,
+/// e.g. when a service starts.
+pub fn setup_sort(n: usize) -> Result<(), Error> {
+    with_len!(n, keys()).map(|_| ())
+}
+
+/// Caches the keys in the directory, so that they are read back rather than
+/// generated again by the next process.
+pub fn cache_keys_in(dir: impl Into<PathBuf>) {
+    *KEY_CACHE.lock().unwrap_or_else(PoisonError::into_inner) = Some(KeyCache::new(dir));
+}
+
+/// Returns the constraints the values fail in the MockProver, none if they
+/// would prove, to debug the circuit.
+pub fn mock_sort(values: &[u64]) -> Result<Vec<VerifyFailure>, Error> {
+    with_len!(values.len(), mock(values))
+}
+
+/// Returns the values of the witness, failing if its options call for
+/// another circuit than SortCircuit, which would not prove them.
+pub fn witness_values(witness: &WitnessInput) -> Result<Vec<u64>, WitnessError> {
+    let values = witness.values_u64()?;
+    if witness.builder() != builder(values.len()) {
+        return Err(WitnessError::Options);
+    }
+    Ok(values)
+}
+
+/// Returns the builder of the circuit sorting n values, of the shape of
+/// SortCircuit.
+fn builder(n: usize) -> SortCircuitBuilder {
+    SortCircuitBuilder::new()
+        .elements(n)
+        .bytes(8)
+        .allow_duplicates()
+}
+
+/// Returns the circuit sorting the values, along with its instance values.
+fn circuit<const N: usize>(values: &[u64]) -> Result<(SortCircuit<N>, Vec<Fp>), Error> {
+    let values: Vec<_> = values.iter().map(|v| Fp::from(*v)).collect();
+    let (circuit, _) = builder(N).build_with(&values)?;
+    let instance = circuit.instance();
+    Ok((circuit, instance))
+}
+
+fn k<const N: usize>() -> Result<u32, Error> {
+    let (circuit, _): (SortCircuit<N>, _) = builder(N).build()?;
+    prover::min_k(&circuit)
+}
+
+fn params<const N: usize>() -> Result<Params<EqAffine>, Error> {
+    Ok(Params::new(k::<N>()?))
+}
+
+/// Returns the keys of the circuit sorting N values, generating them, or
+/// reading them from the KeyCache, only the first time.
+fn keys<const N: usize>() -> Result<Keys, Error> {
+    // the lock is held through the keygen, so that it runs once per N
+    let mut keys = KEYS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((_, built)) = keys.iter().find(|(n, _)| *n == N) {
+        return Ok(built.clone());
+    }
+    let (circuit, _): (SortCircuit<N>, _) = builder(N).build()?;
+    let k = prover::min_k(&circuit)?;
+    let built = match &*KEY_CACHE.lock().unwrap_or_else(PoisonError::into_inner) {
+        Some(cache) => {
+            let key = format!("sort{}-v{}", N, crate::CIRCUIT_VERSION);
+            cache.setup(&key, k, &circuit)?
+        }
+        None => prover::setup(k, &circuit)?,
+    };
+    let built = Arc::new(built);
+    keys.push((N, built.clone()));
+    Ok(built)
+}
+
+fn prove<const N: usize>(values: &[u64]) -> Result<Vec<u8>, Error> {
+    let (circuit, instance) = circuit::<N>(values)?;
+    let keys = keys::<N>()?;
+    let (params, pk) = &*keys;
+    prover::prove(params, pk, circuit, &[&instance])
+}
+
+fn mock<const N: usize>(values: &[u64]) -> Result<Vec<VerifyFailure>, Error> {
+    let (circuit, instance) = circuit::<N>(values)?;
+    let k = prover::min_k(&circuit)?;
+    let prover = MockProver::run(k, &circuit, vec![instance])?;
+    Ok(prover.verify().err().unwrap_or_default())
+}
+
+fn verify<const N: usize>(values: &[u64], proof: &[u8]) -> Result<(), Error> {
+    let (_, instance) = circuit::<N>(values)?;
+    let keys = keys::<N>()?;
+    let (params, pk) = &*keys;
+    prover::verify(params, pk.get_vk(), proof, &[&instance])
+}
+
+fn vk<const N: usize>() -> Result<Vec<u8>, Error> {
+    let keys = keys::<N>()?;
+    let mut vk = vec![];
+    prover::write_vk(keys.1.get_vk(), &mut vk).map_err(Error::Transcript)?;
+    Ok(vk)
+}
+
+fn verify_with_vk<const N: usize>(values: &[u64], proof: &[u8], vk: &[u8]) -> Result<(), Error> {
+    let params = params::<N>()?;
+    let (circuit, instance) = circuit::<N>(values)?;
+    let vk = prover::read_vk(&mut &vk[..], &params, &circuit)?;
+    prover::verify(&params, &vk, proof, &[&instance])
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        keys, mock_sort, prove_sort, setup_sort, sort_k, sort_vk, verify_sort, verify_sort_with_vk,
+        witness_values,
+    };
+    use crate::witness::{WitnessError, WitnessInput};
+    use std::sync::Arc;
+
+    #[test]
+    fn prove_and_verify_sort() {
+        // the keys are built from zero values, sorted by the identity, while
+        // these values are sorted by another permutation
+        let values = [3, 1, 4, 1];
+        let proof = prove_sort(&values).unwrap();
+        let vk = sort_vk(values.len()).unwrap();
+
+        // ok
+        assert!(verify_sort(&values, &proof).is_ok());
+        assert!(verify_sort_with_vk(&values, &proof, &vk).is_ok());
+        let reversed = [4, 3, 2, 1];
+        let proof = prove_sort(&reversed).unwrap();
+        assert!(verify_sort_with_vk(&reversed, &proof, &vk).is_ok());
+        // error: other values
+        assert!(verify_sort(&[3, 1, 4, 2], &proof).is_err());
+        // error: not a power of two
+        assert!(prove_sort(&[3, 1, 4]).is_err());
+        assert!(setup_sort(3).is_err());
+        assert_eq!(mock_sort(&values).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn sort_keys_are_cached() {
+        setup_sort(2).unwrap();
+        assert!(Arc::ptr_eq(&keys::<2>().unwrap(), &keys::<2>().unwrap()));
+    }
+
+    #[test]
+    fn sort_k_grows_with_n() {
+        // ok
+        assert!(sort_k(2).unwrap() <= sort_k(64).unwrap());
+        // error: not a power of two
+        assert!(sort_k(3).is_err());
+    }
+
+    #[test]
+    fn sort_witness_values() {
+        let values = |json| WitnessInput::from_json(json).and_then(|w| witness_values(&w));
+        // ok
+        assert_eq!(values(r#"{"values": ["3", "1"]}"#).unwrap(), vec![3, 1]);
+        // error: options of another circuit
+        assert!(matches!(
+            values(r#"{"values": ["3", "1"], "options": {"n_bytes": 4}}"#),
+            Err(WitnessError::Options)
+        ));
+        assert!(matches!(
+            values(r#"{"values": ["3", "1"], "options": {"allow_duplicates": false}}"#),
+            Err(WitnessError::Options)
+        ));
+    }
+}
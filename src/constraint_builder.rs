@@ -0,0 +1,107 @@
+use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+use crate::util::{bool_check, Expr};
+
+/// Builder collecting the named constraints of a gate, so that gates read as
+/// a list of requirements and every MockProver failure carries a name.
+///
+/// The constraints added within `condition` are multiplied by the condition,
+/// and the degree of every constraint, condition included, is checked against
+/// max_degree as it is added.
+#[derive(Clone, Debug)]
+pub struct BaseConstraintBuilder<F> {
+    constraints: Vec<(&'static str, Expression<F>)>,
+    max_degree: usize,
+    degree: usize,
+    condition: Option<Expression<F>>,
+}
+
+impl<F: FieldExt> BaseConstraintBuilder<F> {
+    /// Creates a builder whose constraints are at most of degree max_degree,
+    /// before the gate selector is applied.
+    pub fn new(max_degree: usize) -> Self {
+        BaseConstraintBuilder {
+            constraints: Vec::new(),
+            max_degree,
+            degree: 0,
+            condition: None,
+        }
+    }
+
+    /// Requires constraint == 0.
+    pub fn require_zero(&mut self, name: &'static str, constraint: Expression<F>) {
+        self.add_constraint(name, constraint);
+    }
+
+    /// Requires lhs == rhs.
+    pub fn require_equal(&mut self, name: &'static str, lhs: Expression<F>, rhs: Expression<F>) {
+        self.add_constraint(name, lhs - rhs);
+    }
+
+    /// Requires value to be 0 or 1.
+    pub fn require_boolean(&mut self, name: &'static str, value: Expression<F>) {
+        self.add_constraint(name, bool_check(value));
+    }
+
+    /// Requires value to be one of the set, with a degree of set.len().
+    pub fn require_in_set(
+        &mut self,
+        name: &'static str,
+        value: Expression<F>,
+        set: &[Expression<F>],
+    ) {
+        self.add_constraint(
+            name,
+            set.iter()
+                .fold(1.expr(), |acc, item| acc * (value.clone() - item.clone())),
+        );
+    }
+
+    /// Runs build with every constraint it adds multiplied by condition,
+    /// nesting within the conditions already applied.
+    pub fn condition<R>(
+        &mut self,
+        condition: Expression<F>,
+        build: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let outer = self.condition.take();
+        self.condition = Some(match outer.clone() {
+            Some(outer) => outer * condition,
+            None => condition,
+        });
+        let ret = build(self);
+        self.condition = outer;
+        ret
+    }
+
+    /// Returns the highest degree of the constraints added so far.
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Returns the named constraints multiplied by the gate selector, to be
+    /// returned from create_gate.
+    pub fn gate(self, selector: Expression<F>) -> Vec<(&'static str, Expression<F>)> {
+        self.constraints
+            .into_iter()
+            .map(|(name, constraint)| (name, selector.clone() * constraint))
+            .collect()
+    }
+
+    fn add_constraint(&mut self, name: &'static str, constraint: Expression<F>) {
+        let constraint = match &self.condition {
+            Some(condition) => condition.clone() * constraint,
+            None => constraint,
+        };
+        let degree = constraint.degree();
+        assert!(
+            degree <= self.max_degree,
+            "constraint {} has degree {}, above the max degree {}",
+            name,
+            degree,
+            self.max_degree
+        );
+        self.degree = self.degree.max(degree);
+        self.constraints.push((name, constraint));
+    }
+}
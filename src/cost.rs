@@ -0,0 +1,77 @@
+use halo2_proofs::{
+    dev::CircuitCost,
+    pasta::{Eq, Fp},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::prover::{min_k, rows_used, CsSizes};
+
+/// The cost of a circuit: the size of its constraint system, the rows it
+/// assigns and the size of its proofs, to be printed as a table or tracked
+/// in CI as JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostReport {
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    pub gates: usize,
+    pub max_degree: usize,
+    pub lookups: usize,
+    pub rows_used: usize,
+    /// The smallest k the circuit fits in, see min_k.
+    pub k: u32,
+    /// The byte size of a proof at k, from the halo2 cost model.
+    pub proof_size: usize,
+}
+
+impl CostReport {
+    /// Measures the circuit, which may be without witnesses.
+    pub fn measure<C: Circuit<Fp>>(circuit: &C) -> Result<Self, Error> {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        C::configure(&mut cs);
+        let k = min_k(circuit)?;
+        let cost = CircuitCost::<Eq, C>::measure(k as usize, circuit);
+        let sizes = CsSizes::of(&cs);
+
+        Ok(CostReport {
+            advice_columns: sizes.advice_columns,
+            fixed_columns: sizes.fixed_columns,
+            instance_columns: sizes.instance_columns,
+            selectors: sizes.selectors,
+            gates: sizes.gates,
+            max_degree: cs.degree(),
+            lookups: sizes.lookups,
+            rows_used: rows_used(circuit)?,
+            k,
+            proof_size: cost.proof_size(sizes.instance_columns).into(),
+        })
+    }
+
+    /// Returns the report as JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in [
+            ("advice columns", self.advice_columns),
+            ("fixed columns", self.fixed_columns),
+            ("instance columns", self.instance_columns),
+            ("selectors", self.selectors),
+            ("gates", self.gates),
+            ("max degree", self.max_degree),
+            ("lookups", self.lookups),
+            ("rows used", self.rows_used),
+            ("k", self.k as usize),
+            ("proof size (bytes)", self.proof_size),
+        ] {
+            writeln!(f, "{:<20}{:>10}", name, value)?;
+        }
+        Ok(())
+    }
+}
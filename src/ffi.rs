@@ -0,0 +1,147 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::bindings;
+
+/// The call succeeded.
+pub const HO_OK: i32 = 0;
+/// A pointer is null, or the number of values is not one of
+/// bindings::SORT_LENS.
+pub const HO_INVALID_ARGUMENT: i32 = 1;
+/// Proving failed.
+pub const HO_PROVE_FAILED: i32 = 2;
+/// The proof is not valid for the values.
+pub const HO_VERIFY_FAILED: i32 = 3;
+/// The proof is empty.
+pub const HO_INVALID_PROOF: i32 = 4;
+/// The call panicked, which must not unwind into the C caller.
+pub const HO_PANIC: i32 = 5;
+
+// Runs the body of an extern "C" function, mapping a panic to HO_PANIC.
+fn catch_panic(body: impl FnOnce() -> i32) -> i32 {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(HO_PANIC)
+}
+
+/// Writes to proof and proof_len the proof that the len values sort to
+/// their ascending order, the public inputs being the values followed by
+/// the sorted values. The proof is to be freed with ho_free_proof.
+///
+/// # Safety
+///
+/// values must point to len u64, and proof and proof_len must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn ho_prove_sort(
+    values: *const u64,
+    len: usize,
+    proof: *mut *mut u8,
+    proof_len: *mut usize,
+) -> i32 {
+    if values.is_null() || proof.is_null() || proof_len.is_null() {
+        return HO_INVALID_ARGUMENT;
+    }
+    if !bindings::SORT_LENS.contains(&len) {
+        return HO_INVALID_ARGUMENT;
+    }
+    catch_panic(
+        || match bindings::prove_sort(slice::from_raw_parts(values, len)) {
+            Ok(bytes) => {
+                let bytes = bytes.into_boxed_slice();
+                *proof_len = bytes.len();
+                *proof = Box::into_raw(bytes) as *mut u8;
+                HO_OK
+            }
+            Err(_) => HO_PROVE_FAILED,
+        },
+    )
+}
+
+/// Verifies a proof of ho_prove_sort for the len values.
+///
+/// # Safety
+///
+/// values must point to len u64, and proof to proof_len bytes. proof may
+/// be null if proof_len is 0.
+#[no_mangle]
+pub unsafe extern "C" fn ho_verify_sort(
+    values: *const u64,
+    len: usize,
+    proof: *const u8,
+    proof_len: usize,
+) -> i32 {
+    if values.is_null() || !bindings::SORT_LENS.contains(&len) {
+        return HO_INVALID_ARGUMENT;
+    }
+    if proof_len == 0 {
+        return HO_INVALID_PROOF;
+    }
+    if proof.is_null() {
+        return HO_INVALID_ARGUMENT;
+    }
+    let values = slice::from_raw_parts(values, len);
+    let proof = slice::from_raw_parts(proof, proof_len);
+    catch_panic(|| match bindings::verify_sort(values, proof) {
+        Ok(()) => HO_OK,
+        Err(_) => HO_VERIFY_FAILED,
+    })
+}
+
+/// Frees a proof of ho_prove_sort.
+///
+/// # Safety
+///
+/// proof and proof_len must be those written by ho_prove_sort, and the
+/// proof not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn ho_free_proof(proof: *mut u8, proof_len: usize) {
+    if !proof.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            proof, proof_len,
+        )));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        catch_panic, ho_verify_sort, HO_INVALID_ARGUMENT, HO_INVALID_PROOF, HO_OK, HO_PANIC,
+    };
+    use std::ptr;
+
+    #[test]
+    fn catch_panics() {
+        // ok
+        assert_eq!(catch_panic(|| HO_OK), HO_OK);
+        // error: the panic does not unwind out of the call
+        assert_eq!(catch_panic(|| panic!("unwinding into C")), HO_PANIC);
+    }
+
+    #[test]
+    fn verify_sort_arguments() {
+        let values = [3u64, 1, 4, 2];
+        let proof = [0u8; 32];
+        unsafe {
+            // error: null pointers
+            assert_eq!(
+                ho_verify_sort(ptr::null(), 4, proof.as_ptr(), proof.len()),
+                HO_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                ho_verify_sort(values.as_ptr(), 4, ptr::null(), proof.len()),
+                HO_INVALID_ARGUMENT
+            );
+            // error: not a supported number of values
+            assert_eq!(
+                ho_verify_sort(values.as_ptr(), 3, proof.as_ptr(), proof.len()),
+                HO_INVALID_ARGUMENT
+            );
+            // error: an empty proof, which may be null
+            assert_eq!(
+                ho_verify_sort(values.as_ptr(), 4, ptr::null(), 0),
+                HO_INVALID_PROOF
+            );
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! The chips of the crate, each with its config and instructions, for
+//! circuits of other crates to configure and assign. Most range check their
+//! operands with a U8Table (see `table`), which the circuit loads once.
+//!
+//! The chips also implement `Gadget`, configured on the `SharedColumns` of a
+//! circuit and wired to each other by their cells. So do the sort chips,
+//! which read their inputs from the instance column in their own circuits
+//! and from the input cells as gadgets. SortCommitCircuit is a circuit
+//! rather than a chip, so it has no gadget: its Poseidon commitment is
+//! over Fp only.
+
+pub mod accumulator;
+pub mod add_checked;
+pub mod bitwise;
+pub mod clamp;
+pub mod comparator;
+pub mod cswap;
+pub mod div_mod;
+pub mod dot_product;
+pub mod fixed_point;
+pub mod greater_equal;
+pub mod greater_than;
+pub mod is_zero;
+pub mod less_equal;
+pub mod less_than;
+pub mod min_max_tree;
+pub mod modulo;
+pub mod mul_checked;
+pub mod num_to_bits;
+pub mod range_check;
+pub mod select;
+pub mod shift;
+pub mod sort;
+pub mod wide_less_than;
+pub mod word;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
+};
+use std::fmt::Debug;
+
+use crate::table::U8Table;
+
+/// A chip configured on the columns a circuit shares between its gadgets and
+/// assigned in a region of its own, from the cells of other gadgets, so that
+/// gadgets are wired together by copy constraints rather than by the columns
+/// each chip's own configure takes.
+pub trait Gadget<F: FieldExt> {
+    /// The config of the gadget: the config of the chip, gated by a
+    /// selector of its own if the chip takes a q_enable (see `Gated`).
+    type Config: Clone + Debug;
+    /// What the chip is configured with besides its columns, e.g. the
+    /// operation of a BitwiseChip, or () for most chips.
+    type Params;
+    /// The cells the gadget is assigned.
+    type Input;
+    /// The cells the gadget outputs.
+    type Output;
+
+    /// Configures the gadget on the shared columns, taking the advice
+    /// columns it needs from the first. Panics if there are too few. As the
+    /// chips have a configure of their own, call it as
+    /// `<LtChip<F, 8> as Gadget<F>>::configure`.
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        params: Self::Params,
+    ) -> Self::Config;
+
+    /// Loads the tables of the gadget other than the shared u8 table.
+    fn load(_config: &Self::Config, _layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Assigns the gadget in a region of its own, copying the input cells
+    /// into it, and returns its output cells.
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error>;
+}
+
+/// The columns a circuit shares between its gadgets. Every gadget lays out
+/// its cells in the advice columns from the first, in regions of its own.
+#[derive(Clone, Debug)]
+pub struct SharedColumns {
+    /// The advice columns, with equality enabled.
+    pub advice: Vec<Column<Advice>>,
+    /// The fixed column of constants.
+    pub constants: Column<Fixed>,
+    /// The instance column, with equality enabled.
+    pub instance: Column<Instance>,
+    /// The u8 table range checking bytes.
+    pub u8_table: U8Table,
+}
+
+impl SharedColumns {
+    /// Configures n_advice advice columns, as many as the widest gadget of
+    /// the circuit needs, along with the other shared columns.
+    pub fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>, n_advice: usize) -> Self {
+        let advice: Vec<_> = (0..n_advice).map(|_| meta.advice_column()).collect();
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        SharedColumns {
+            advice,
+            constants,
+            instance,
+            u8_table: U8Table::configure(meta),
+        }
+    }
+
+    /// Returns the L advice columns from the first one.
+    pub fn advice<const L: usize>(&self, first: usize) -> [Column<Advice>; L] {
+        assert!(
+            first + L <= self.advice.len(),
+            "the gadget needs {} shared advice columns, there are {}",
+            first + L,
+            self.advice.len()
+        );
+        self.advice[first..first + L].try_into().unwrap()
+    }
+
+    /// Loads the u8 table, once per circuit.
+    pub fn load<F: FieldExt>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.u8_table.load(layouter)
+    }
+
+    /// Witnesses the values in a row of the advice columns, returning their
+    /// cells to be assigned to gadgets.
+    pub fn witness<F: FieldExt, const L: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: [Value<F>; L],
+    ) -> Result<[AssignedCell<F, F>; L], Error> {
+        let columns = self.advice::<L>(0);
+        layouter.assign_region(
+            || "witness",
+            |mut region| {
+                let mut cells = Vec::with_capacity(L);
+                for (i, (column, value)) in columns.iter().zip(values).enumerate() {
+                    cells.push(region.assign_advice(
+                        || format!("witness[{}]", i),
+                        *column,
+                        0,
+                        || value,
+                    )?);
+                }
+                Ok(cells.try_into().unwrap())
+            },
+        )
+    }
+}
+
+/// The config of a gadget whose chip takes a q_enable: the config of the
+/// chip, the selector its gates are enabled by, and the columns of the
+/// inputs it does not copy into its region itself.
+#[derive(Clone, Copy, Debug)]
+pub struct Gated<C, const INPUTS: usize> {
+    /// Denotes the rows of the gadget.
+    pub selector: Selector,
+    /// Denotes the inputs the chip reads but does not assign.
+    pub inputs: [Column<Advice>; INPUTS],
+    /// The config of the chip.
+    pub chip: C,
+}
+
+impl<C, const INPUTS: usize> Gated<C, INPUTS> {
+    /// Enables the selector at the offset and copies the input cells into
+    /// the input columns there, returning their values for the chip.
+    pub(crate) fn enable<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        inputs: [&AssignedCell<F, F>; INPUTS],
+    ) -> Result<[F; INPUTS], Error> {
+        self.selector.enable(region, offset)?;
+        for (i, (column, cell)) in self.inputs.iter().zip(inputs).enumerate() {
+            cell.copy_advice(|| format!("input[{}]", i), region, *column, offset)?;
+        }
+        Ok(inputs.map(known_value))
+    }
+}
+
+/// Returns the value of the cell, for the chips taking their operands as
+/// values, which is only zero while the witnesses are unknown.
+pub(crate) fn known_value<F: FieldExt>(cell: &AssignedCell<F, F>) -> F {
+    let mut value = F::zero();
+    cell.value().map(|v| value = *v);
+    value
+}
+
+#[cfg(test)]
+mod test {
+    use super::{less_than::LtChip, select::SelectChip, Gadget, Gated, SharedColumns};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        columns: SharedColumns,
+        lt: Gated<super::less_than::LtConfig<F, 8>, 2>,
+        select: Gated<super::select::SelectConfig, 0>,
+    }
+
+    // max(a, b), as b if a < b else a, wiring the lt gadget to the select one
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        a: F,
+        b: F,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let columns = SharedColumns::configure(meta, 11);
+            let lt = <LtChip<F, 8> as Gadget<F>>::configure(meta, &columns, ());
+            let select = <SelectChip<F> as Gadget<F>>::configure(meta, &columns, ());
+
+            Self::Config {
+                columns,
+                lt,
+                select,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.columns.load(&mut layouter)?;
+
+            let [a, b] = config.columns.witness(
+                layouter.namespace(|| "witness"),
+                [Value::known(self.a), Value::known(self.b)],
+            )?;
+            let lt = <LtChip<F, 8> as Gadget<F>>::assign(
+                &config.lt,
+                layouter.namespace(|| "lt"),
+                [a.clone(), b.clone()],
+            )?;
+            let max =
+                SelectChip::assign(&config.select, layouter.namespace(|| "select"), [lt, b, a])?;
+
+            layouter.constrain_instance(max.cell(), config.columns.instance, 0)
+        }
+    }
+
+    fn try_max(a: u64, b: u64, max: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            a: Fp::from(a),
+            b: Fp::from(b),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(max)]]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn composed_gadgets() {
+        // ok
+        assert_eq!(try_max(3, 7, 7), Ok(()));
+        assert_eq!(try_max(7, 3, 7), Ok(()));
+        assert_eq!(try_max(5, 5, 5), Ok(()));
+        // error
+        assert!(try_max(3, 7, 3).is_err());
+        assert!(try_max(7, 3, 3).is_err());
+    }
+}
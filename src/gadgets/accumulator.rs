@@ -0,0 +1,283 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::gadgets::{Gadget, SharedColumns};
+use std::marker::PhantomData;
+
+/// Instruction that the Accumulator chip needs to implement.
+pub trait AccumulatorInstruction<F: FieldExt> {
+    /// Copy the values cells to the Accumulator chip's region, on the rows
+    /// from offset, returning the cells of the prefix sums
+    /// acc_0 == 0, acc_1 == x_0, ..., acc_n == x_0 + ... + x_(n-1).
+    fn assign_prefix_sums(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>;
+
+    /// Copy the values cells to the Accumulator chip's region, on the rows
+    /// from offset, returning the cell of their sum.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let mut acc_cells = self.assign_prefix_sums(region, offset, values)?;
+        Ok(acc_cells.pop().unwrap())
+    }
+}
+
+/// Config for the Accumulator chip.
+#[derive(Clone, Copy, Debug)]
+pub struct AccumulatorConfig {
+    /// Denotes the x and acc columns, where acc::next == acc::cur + x::cur.
+    pub advice: [Column<Advice>; 2],
+    /// Denotes the first row of an accumulation, where acc == 0.
+    pub q_first: Selector,
+    /// Denotes the rows adding x to acc.
+    pub q_step: Selector,
+}
+
+impl AccumulatorConfig {
+    /// Returns an expression that denotes the accumulated sum.
+    pub fn acc<F: FieldExt>(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[1], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that accumulates a running sum of values down a column, as the
+/// common primitive for sums, counts and prefix-sum based gadgets.
+#[derive(Clone, Debug)]
+pub struct AccumulatorChip<F> {
+    config: AccumulatorConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> AccumulatorChip<F> {
+    /// Configures the Accumulator chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+    ) -> AccumulatorConfig {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let [x, acc] = advice;
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        // The first row is a gate of its own, as it is enabled on its own
+        // when there are no values, with no x or acc::next to query.
+        meta.create_gate("accumulator first", |meta| {
+            // acc    | selectors
+            // 0        q_first
+            let q_first = meta.query_selector(q_first);
+            vec![q_first * meta.query_advice(acc, Rotation::cur())]
+        });
+
+        meta.create_gate("accumulator gate", |meta| {
+            // x      | acc                 | selectors
+            // x0       0                     q_first, q_step
+            // x1       x0                    q_step
+            // ...
+            //          x0 + ... + x(n-1)
+            let q_step = meta.query_selector(q_step);
+            let x = meta.query_advice(x, Rotation::cur());
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+
+            vec![q_step * (acc_next - acc_cur - x)]
+        });
+
+        AccumulatorConfig {
+            advice,
+            q_first,
+            q_step,
+        }
+    }
+
+    /// Constructs an Accumulator chip given a config.
+    pub fn construct(config: AccumulatorConfig) -> AccumulatorChip<F> {
+        AccumulatorChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt> AccumulatorInstruction<F> for AccumulatorChip<F> {
+    fn assign_prefix_sums(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let config = self.config();
+        let [x_column, acc_column] = config.advice;
+        config.q_first.enable(region, offset)?;
+
+        let mut acc = Value::known(F::zero());
+        let mut acc_cells = Vec::with_capacity(values.len() + 1);
+        for (i, x) in values.iter().enumerate() {
+            config.q_step.enable(region, offset + i)?;
+            x.copy_advice(|| format!("x[{}]", i), region, x_column, offset + i)?;
+            acc_cells.push(region.assign_advice(
+                || format!("acc[{}]", i),
+                acc_column,
+                offset + i,
+                || acc,
+            )?);
+            acc = acc + x.value().copied();
+        }
+        acc_cells.push(region.assign_advice(
+            || format!("acc[{}]", values.len()),
+            acc_column,
+            offset + values.len(),
+            || acc,
+        )?);
+
+        Ok(acc_cells)
+    }
+}
+
+impl<F: FieldExt> Chip<F> for AccumulatorChip<F> {
+    type Config = AccumulatorConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Gadget<F> for AccumulatorChip<F> {
+    type Config = AccumulatorConfig;
+    type Params = ();
+    type Input = Vec<AssignedCell<F, F>>;
+    /// The cell of the sum of the values.
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        AccumulatorChip::configure(meta, columns.advice(0))
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        values: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "accumulator gadget",
+            |mut region| AccumulatorChip::construct(*config).assign(&mut region, 0, &values),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AccumulatorChip, AccumulatorConfig, AccumulatorInstruction};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        accumulator: AccumulatorConfig,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 2].map(|_| meta.advice_column());
+
+            Self::Config {
+                witness,
+                instance,
+                accumulator: AccumulatorChip::configure(meta, advice),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = AccumulatorChip::construct(config.accumulator);
+
+            let sum = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let values = self
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| {
+                            region.assign_advice(
+                                || "value",
+                                config.witness,
+                                i,
+                                || Value::known(*value),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    chip.assign(&mut region, 0, &values)
+                },
+            )?;
+
+            layouter.constrain_instance(sum.cell(), config.instance, 0)
+        }
+    }
+
+    fn try_accumulate(values: Vec<u64>, sum: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values.into_iter().map(Fp::from).collect(),
+        };
+        let prover = MockProver::<Fp>::run(5, &circuit, vec![vec![Fp::from(sum)]]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn accumulate() {
+        // ok
+        assert_eq!(try_accumulate(vec![3, 1, 4, 1, 5], 14), Ok(()));
+        assert_eq!(try_accumulate(vec![], 0), Ok(()));
+        // error
+        assert!(try_accumulate(vec![3, 1, 4, 1, 5], 13).is_err());
+        assert!(try_accumulate(vec![7], 0).is_err());
+    }
+}
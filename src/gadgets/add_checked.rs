@@ -0,0 +1,348 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{bool_check, expr_from_bytes, pow_of_two},
+};
+
+/// Instruction that the AddChecked chip needs to implement.
+pub trait AddCheckedInstruction<F: FieldExt> {
+    /// Copy the a and b cells to the AddChecked chip's region, returning the
+    /// cells of (sum, overflow).
+    #[allow(clippy::type_complexity)]
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+}
+
+/// Config for the AddChecked chip.
+#[derive(Clone, Copy, Debug)]
+pub struct AddCheckedConfig<F, const N_BYTES: usize> {
+    /// Denotes the a, b, sum and overflow columns, where
+    /// a + b == sum + overflow * range.
+    pub advice: [Column<Advice>; 4],
+    /// Denotes the bytes representation of sum.
+    /// Each byte is range checked by a lookup into the shared u8 table.
+    pub sum_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the range within which a, b and sum lie.
+    pub range: F,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> AddCheckedConfig<F, N_BYTES> {
+    /// Returns an expression that denotes whether a + b overflows, or not.
+    pub fn is_overflow(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[3], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that adds two N_BYTES-byte unsigned values, outputting the sum
+/// wrapped to N_BYTES bytes and an overflow bit, so that accumulations can't
+/// silently wrap around the field modulus. Both operands are expected to be
+/// range checked already, e.g. as the sum of a previous addition.
+#[derive(Clone, Debug)]
+pub struct AddCheckedChip<F, const N_BYTES: usize> {
+    config: AddCheckedConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> AddCheckedChip<F, N_BYTES> {
+    /// Configures the AddChecked chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        advice: [Column<Advice>; 4],
+        sum_bytes: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
+    ) -> AddCheckedConfig<F, N_BYTES> {
+        assert!(N_BYTES < 31, "the unwrapped sum must fit in the field");
+        let range = pow_of_two(N_BYTES * 8);
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("add checked gate", |meta| {
+            let q_enable = q_enable(meta);
+            let [a, b, sum, overflow] = advice.map(|c| meta.query_advice(c, Rotation::cur()));
+
+            let sum_bytes = sum_bytes
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::cur()))
+                .collect::<Vec<Expression<F>>>();
+
+            let check_sum = sum.clone() - expr_from_bytes(&sum_bytes);
+            let check_add = a + b - sum - overflow.clone() * range;
+
+            [check_sum, check_add, bool_check(overflow)]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        u8_table.range_check(meta, &q_enable, &sum_bytes);
+
+        AddCheckedConfig {
+            advice,
+            sum_bytes,
+            range,
+        }
+    }
+
+    /// Constructs an AddChecked chip given a config.
+    pub fn construct(config: AddCheckedConfig<F, N_BYTES>) -> AddCheckedChip<F, N_BYTES> {
+        AddCheckedChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> AddCheckedInstruction<F> for AddCheckedChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+        let [a_column, b_column, sum_column, overflow_column] = config.advice;
+
+        a.copy_advice(|| "add checked chip: a", region, a_column, offset)?;
+        b.copy_advice(|| "add checked chip: b", region, b_column, offset)?;
+
+        let total = a.value().copied() + b.value().copied();
+        // the sum overflows iff the total has a byte at or above N_BYTES
+        let overflow = total.map(|total| {
+            total.to_repr().as_ref()[N_BYTES..]
+                .iter()
+                .any(|byte| *byte != 0)
+        });
+        let sum = total.zip(overflow).map(|(total, overflow)| {
+            if overflow {
+                total - config.range
+            } else {
+                total
+            }
+        });
+
+        let sum_cell =
+            region.assign_advice(|| "add checked chip: sum", sum_column, offset, || sum)?;
+        let overflow_cell = region.assign_advice(
+            || "add checked chip: overflow",
+            overflow_column,
+            offset,
+            || overflow.map(|overflow| F::from(overflow as u64)),
+        )?;
+
+        let sum_bytes = sum.map(|sum| sum.to_repr());
+        for (idx, sum_column) in config.sum_bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("add checked chip: sum byte {}", idx),
+                *sum_column,
+                offset,
+                || {
+                    sum_bytes
+                        .as_ref()
+                        .map(|bytes| F::from(bytes.as_ref()[idx] as u64))
+                },
+            )?;
+        }
+
+        Ok((sum_cell, overflow_cell))
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for AddCheckedChip<F, N_BYTES> {
+    type Config = AddCheckedConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for AddCheckedChip<F, N_BYTES> {
+    type Config = Gated<AddCheckedConfig<F, N_BYTES>, 0>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    /// The cells of (sum, overflow).
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let chip = AddCheckedChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            columns.advice(0),
+            columns.advice(4),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs: [],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [a, b]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "add checked gadget",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                AddCheckedChip::construct(config.chip).assign(&mut region, 0, &a, &b)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AddCheckedChip, AddCheckedConfig, AddCheckedInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        add: AddCheckedConfig<F, 1>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        pairs: Vec<(F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let sum_bytes = [(); 1].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            let add = AddCheckedChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                advice,
+                sum_bytes,
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                witness,
+                instance,
+                add,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = AddCheckedChip::construct(config.add);
+            config.u8_table.load(&mut layouter)?;
+
+            let out_cells = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut out_cells = vec![];
+                    for (idx, (a, b)) in self.pairs.iter().enumerate() {
+                        // the operands of a pair are on the two rows from 2 * idx
+                        let offset = 2 * idx;
+                        let a = region.assign_advice(
+                            || "a",
+                            config.witness,
+                            offset,
+                            || Value::known(*a),
+                        )?;
+                        let b = region.assign_advice(
+                            || "b",
+                            config.witness,
+                            offset + 1,
+                            || Value::known(*b),
+                        )?;
+
+                        config.q_enable.enable(&mut region, offset)?;
+                        let (sum, overflow) = chip.assign(&mut region, offset, &a, &b)?;
+                        out_cells.extend([sum, overflow]);
+                    }
+
+                    Ok(out_cells)
+                },
+            )?;
+
+            for (i, cell) in out_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_add(pairs: Vec<(u64, u64)>, outs: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            pairs: pairs
+                .into_iter()
+                .map(|(a, b)| (Fp::from(a), Fp::from(b)))
+                .collect(),
+        };
+        let instance = outs.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn add_checked() {
+        // ok
+        assert_eq!(
+            try_add(
+                vec![(3, 7), (200, 55), (200, 56), (255, 255)],
+                vec![10, 0, 255, 0, 0, 1, 254, 1]
+            ),
+            Ok(())
+        );
+        // error: the overflow is dropped
+        assert!(try_add(vec![(200, 56)], vec![256, 0]).is_err());
+        // error: wrong sum
+        assert!(try_add(vec![(3, 7)], vec![11, 0]).is_err());
+    }
+}
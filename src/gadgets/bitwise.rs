@@ -0,0 +1,342 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, VirtualCells},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    gadgets::{Gadget, SharedColumns},
+    table::{BitwiseOp, BitwiseTable},
+    util::{expr_from_bytes, value},
+};
+
+/// Instruction that the Bitwise chip needs to implement.
+pub trait BitwiseInstruction<F: FieldExt> {
+    /// Copy the lhs and rhs cells to the Bitwise chip's region, returning the
+    /// cell of lhs op rhs.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the Bitwise chip.
+#[derive(Clone, Copy, Debug)]
+pub struct BitwiseConfig<const N_BYTES: usize> {
+    /// Denotes the lhs, rhs and out columns, where out == lhs op rhs.
+    pub advice: [Column<Advice>; 3],
+    /// Denotes the little-endian bytes of lhs.
+    pub lhs_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the little-endian bytes of rhs.
+    pub rhs_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the little-endian bytes of out.
+    pub out_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the rows holding an operation.
+    pub q_enable: Selector,
+    /// The table of the operation, which the circuit loads once.
+    pub table: BitwiseTable,
+}
+
+impl<const N_BYTES: usize> BitwiseConfig<N_BYTES> {
+    /// Returns an expression that denotes lhs op rhs.
+    pub fn out<F: FieldExt>(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[2], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that computes the bitwise AND, OR or XOR of two N_BYTES-byte values.
+/// Both operands are decomposed into bytes, and every triple of lhs, rhs and
+/// out bytes is looked up in the table of the operation, which also range
+/// checks the bytes.
+#[derive(Clone, Debug)]
+pub struct BitwiseChip<F, const N_BYTES: usize> {
+    config: BitwiseConfig<N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> BitwiseChip<F, N_BYTES> {
+    /// Configures the Bitwise chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        table: BitwiseTable,
+    ) -> BitwiseConfig<N_BYTES> {
+        assert!(N_BYTES < 32, "operands must fit in the field");
+
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let lhs_bytes = [(); N_BYTES].map(|_| meta.advice_column());
+        let rhs_bytes = [(); N_BYTES].map(|_| meta.advice_column());
+        let out_bytes = [(); N_BYTES].map(|_| meta.advice_column());
+        let q_enable = meta.complex_selector();
+
+        meta.create_gate("bitwise gate", |meta| {
+            let q_enable = meta.query_selector(q_enable);
+
+            advice
+                .into_iter()
+                .zip([lhs_bytes, rhs_bytes, out_bytes])
+                .map(|(value, bytes)| {
+                    let value = meta.query_advice(value, Rotation::cur());
+                    let bytes = bytes.map(|c| meta.query_advice(c, Rotation::cur()));
+                    q_enable.clone() * (value - expr_from_bytes(&bytes))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        for i in 0..N_BYTES {
+            meta.lookup(|meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let lhs = meta.query_advice(lhs_bytes[i], Rotation::cur());
+                let rhs = meta.query_advice(rhs_bytes[i], Rotation::cur());
+                let out = meta.query_advice(out_bytes[i], Rotation::cur());
+                vec![
+                    (q_enable.clone() * lhs, table.lhs),
+                    (q_enable.clone() * rhs, table.rhs),
+                    (q_enable * out, table.out),
+                ]
+            });
+        }
+
+        BitwiseConfig {
+            advice,
+            lhs_bytes,
+            rhs_bytes,
+            out_bytes,
+            q_enable,
+            table,
+        }
+    }
+
+    /// Constructs a Bitwise chip given a config.
+    pub fn construct(config: BitwiseConfig<N_BYTES>) -> BitwiseChip<F, N_BYTES> {
+        BitwiseChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> BitwiseInstruction<F> for BitwiseChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        config.q_enable.enable(region, offset)?;
+
+        lhs.copy_advice(|| "bitwise chip: lhs", region, config.advice[0], offset)?;
+        rhs.copy_advice(|| "bitwise chip: rhs", region, config.advice[1], offset)?;
+
+        let to_bytes = |value: &F| {
+            let repr = value.to_repr();
+            let mut bytes = [0u8; N_BYTES];
+            bytes.copy_from_slice(&repr.as_ref()[..N_BYTES]);
+            bytes
+        };
+        let lhs_bytes = lhs.value().map(to_bytes);
+        let rhs_bytes = rhs.value().map(to_bytes);
+        let out_bytes = lhs_bytes.zip(rhs_bytes).map(|(lhs, rhs)| {
+            let mut out = [0u8; N_BYTES];
+            for i in 0..N_BYTES {
+                out[i] = config.table.op.apply(lhs[i], rhs[i]);
+            }
+            out
+        });
+
+        for (name, columns, bytes) in [
+            ("lhs", config.lhs_bytes, lhs_bytes),
+            ("rhs", config.rhs_bytes, rhs_bytes),
+            ("out", config.out_bytes, out_bytes),
+        ] {
+            for (idx, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("bitwise chip: {} byte {}", name, idx),
+                    *column,
+                    offset,
+                    || bytes.map(|bytes| F::from(bytes[idx] as u64)),
+                )?;
+            }
+        }
+
+        let out = value::from_le_bytes(out_bytes);
+        region.assign_advice(|| "bitwise chip: out", config.advice[2], offset, || out)
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for BitwiseChip<F, N_BYTES> {
+    type Config = BitwiseConfig<N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for BitwiseChip<F, N_BYTES> {
+    type Config = BitwiseConfig<N_BYTES>;
+    type Params = BitwiseOp;
+    type Input = [AssignedCell<F, F>; 2];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        op: BitwiseOp,
+    ) -> Self::Config {
+        let table = BitwiseTable::configure(meta, op);
+        BitwiseChip::configure(meta, columns.advice(0), table)
+    }
+
+    fn load(config: &Self::Config, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        config.table.load(layouter)
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [lhs, rhs]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "bitwise gadget",
+            |mut region| BitwiseChip::construct(*config).assign(&mut region, 0, &lhs, &rhs),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BitwiseChip, BitwiseConfig, BitwiseInstruction};
+    use crate::table::{BitwiseOp, BitwiseTable};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        bitwise: [BitwiseConfig<2>; 3],
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        lhs: F,
+        rhs: F,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+
+            let bitwise = [BitwiseOp::And, BitwiseOp::Or, BitwiseOp::Xor].map(|op| {
+                let advice = [(); 3].map(|_| meta.advice_column());
+                let table = BitwiseTable::configure(meta, op);
+                BitwiseChip::configure(meta, advice, table)
+            });
+
+            Self::Config {
+                input,
+                instance,
+                bitwise,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            for bitwise in config.bitwise.iter() {
+                bitwise.table.load(&mut layouter)?;
+            }
+
+            let outs = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let lhs = region.assign_advice(
+                        || "lhs",
+                        config.input,
+                        0,
+                        || Value::known(self.lhs),
+                    )?;
+                    let rhs = region.assign_advice(
+                        || "rhs",
+                        config.input,
+                        1,
+                        || Value::known(self.rhs),
+                    )?;
+                    config
+                        .bitwise
+                        .iter()
+                        .map(|bitwise| {
+                            BitwiseChip::construct(*bitwise).assign(&mut region, 0, &lhs, &rhs)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            // the and, or and xor outcomes are exposed in order
+            for (i, out) in outs.iter().enumerate() {
+                layouter.constrain_instance(out.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_bitwise(lhs: u64, rhs: u64, outs: [u64; 3]) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            lhs: Fp::from(lhs),
+            rhs: Fp::from(rhs),
+        };
+        let instance = outs.map(Fp::from).to_vec();
+        let prover = MockProver::<Fp>::run(17, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn bitwise_ops() {
+        // ok
+        assert_eq!(
+            try_bitwise(0xf0f0, 0x3c3c, [0x3030, 0xfcfc, 0xcccc]),
+            Ok(())
+        );
+        assert_eq!(try_bitwise(0, 0xffff, [0, 0xffff, 0xffff]), Ok(()));
+        // error: wrong outcome
+        assert!(try_bitwise(0xf0f0, 0x3c3c, [0x3030, 0xfcfc, 0xcccd]).is_err());
+        // error: operand wider than 2 bytes
+        assert!(try_bitwise(0x10000, 1, [0, 0x10001, 0x10001]).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::Expr,
+};
+
+/// Instruction that the Clamp chip needs to implement.
+pub trait ClampInstruction<F: FieldExt> {
+    /// Copy the x, lo and hi cells to the Clamp chip's region, returning the
+    /// cell of min(max(x, lo), hi).
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        x: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        hi: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the Clamp chip.
+#[derive(Clone, Copy, Debug)]
+pub struct ClampConfig<F, const N_BYTES: usize> {
+    /// Denotes the x, lo, hi, mid and out columns, where mid == max(x, lo)
+    /// and out == min(mid, hi).
+    pub advice: [Column<Advice>; 5],
+    /// Denotes the comparison x < lo, which is set if x is raised to lo.
+    pub below: LtConfig<F, N_BYTES>,
+    /// Denotes the comparison hi < mid, which is set if mid is lowered to hi.
+    pub above: LtConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ClampConfig<F, N_BYTES> {
+    /// Returns an expression that denotes the clamped value.
+    pub fn out(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        meta.query_advice(self.advice[4], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that clamps x to out = min(max(x, lo), hi), for bounding values to
+/// sane ranges. It takes an Lt check of x < lo to select mid = max(x, lo), and
+/// an Lt check of hi < mid to select out = min(mid, hi).
+#[derive(Clone, Debug)]
+pub struct ClampChip<F, const N_BYTES: usize> {
+    config: ClampConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ClampChip<F, N_BYTES> {
+    /// Configures the Clamp chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + Copy,
+        advice: [Column<Advice>; 5],
+        lt: [Column<Advice>; 2],
+        diff: [[Column<Advice>; N_BYTES]; 2],
+        u8_table: U8Table,
+    ) -> ClampConfig<F, N_BYTES> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let [x, lo, hi, mid, _] = advice;
+
+        let below = LtChip::configure(
+            meta,
+            q_enable,
+            |meta| meta.query_advice(x, Rotation::cur()),
+            |meta| meta.query_advice(lo, Rotation::cur()),
+            lt[0],
+            diff[0],
+            u8_table,
+        );
+        let above = LtChip::configure(
+            meta,
+            q_enable,
+            |meta| meta.query_advice(hi, Rotation::cur()),
+            |meta| meta.query_advice(mid, Rotation::cur()),
+            lt[1],
+            diff[1],
+            u8_table,
+        );
+
+        meta.create_gate("clamp gate", |meta| {
+            let q_enable = q_enable(meta);
+            let raise = below.is_lt(meta, None);
+            let lower = above.is_lt(meta, None);
+            let [x, lo, hi, mid, out] = advice.map(|c| meta.query_advice(c, Rotation::cur()));
+
+            // mid = raise ? lo : x, out = lower ? hi : mid
+            let check_mid = mid.clone() - (raise.clone() * lo + (1.expr() - raise) * x);
+            let check_out = out - (lower.clone() * hi + (1.expr() - lower) * mid);
+
+            [check_mid, check_out]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        ClampConfig {
+            advice,
+            below,
+            above,
+        }
+    }
+
+    /// Constructs a Clamp chip given a config.
+    pub fn construct(config: ClampConfig<F, N_BYTES>) -> ClampChip<F, N_BYTES> {
+        ClampChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ClampInstruction<F> for ClampChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        x: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        hi: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let [x_column, lo_column, hi_column, mid_column, out_column] = config.advice;
+
+        x.copy_advice(|| "clamp chip: x", region, x_column, offset)?;
+        lo.copy_advice(|| "clamp chip: lo", region, lo_column, offset)?;
+        hi.copy_advice(|| "clamp chip: hi", region, hi_column, offset)?;
+
+        // the Lt chips take the values themselves, which are only zero while
+        // the witnesses are unknown
+        let mut values = (F::zero(), F::zero(), F::zero());
+        x.value()
+            .zip(lo.value())
+            .zip(hi.value())
+            .map(|((x, lo), hi)| values = (*x, *lo, *hi));
+        let (x_value, lo_value, hi_value) = values;
+
+        LtChip::construct(config.below).assign(region, offset, x_value, lo_value)?;
+        let mid = if x_value < lo_value { lo } else { x };
+        let mid_cell = region.assign_advice(
+            || "clamp chip: mid",
+            mid_column,
+            offset,
+            || mid.value().copied(),
+        )?;
+
+        let mut mid_value = F::zero();
+        mid.value().map(|mid| mid_value = *mid);
+        LtChip::construct(config.above).assign(region, offset, hi_value, mid_value)?;
+        let out = if hi_value < mid_value { hi } else { &mid_cell };
+        region.assign_advice(
+            || "clamp chip: out",
+            out_column,
+            offset,
+            || out.value().copied(),
+        )
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for ClampChip<F, N_BYTES> {
+    type Config = ClampConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for ClampChip<F, N_BYTES> {
+    type Config = Gated<ClampConfig<F, N_BYTES>, 0>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 3];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let chip = ClampChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            columns.advice(0),
+            columns.advice(5),
+            [columns.advice(7), columns.advice(7 + N_BYTES)],
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs: [],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [x, lo, hi]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "clamp gadget",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                ClampChip::construct(config.chip).assign(&mut region, 0, &x, &lo, &hi)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ClampChip, ClampConfig, ClampInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        clamp: ClampConfig<F, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        // each x is clamped to [lo, hi]
+        values: Vec<(F, F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let lt = [(); 2].map(|_| meta.advice_column());
+            let diff = [(); 2].map(|_| [(); 8].map(|_| meta.advice_column()));
+            let u8_table = U8Table::configure(meta);
+
+            let clamp = ClampChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                advice,
+                lt,
+                diff,
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                witness,
+                instance,
+                clamp,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = ClampChip::construct(config.clamp);
+            config.u8_table.load(&mut layouter)?;
+
+            let out_cells = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut out_cells = vec![];
+                    for (idx, (x, lo, hi)) in self.values.iter().enumerate() {
+                        // the operands of a clamp are on the three rows from 3 * idx
+                        let offset = 3 * idx;
+                        let [x, lo, hi] = [(x, 0), (lo, 1), (hi, 2)].map(|(value, i)| {
+                            region.assign_advice(
+                                || "operand",
+                                config.witness,
+                                offset + i,
+                                || Value::known(*value),
+                            )
+                        });
+
+                        config.q_enable.enable(&mut region, offset)?;
+                        out_cells.push(chip.assign(&mut region, offset, &x?, &lo?, &hi?)?);
+                    }
+
+                    Ok(out_cells)
+                },
+            )?;
+
+            for (i, cell) in out_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_clamp(values: Vec<(u64, u64, u64)>, outs: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values
+                .into_iter()
+                .map(|(x, lo, hi)| (Fp::from(x), Fp::from(lo), Fp::from(hi)))
+                .collect(),
+        };
+        let instance = outs.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn clamp() {
+        // ok
+        assert_eq!(
+            try_clamp(
+                vec![(1, 3, 7), (5, 3, 7), (9, 3, 7), (3, 3, 7), (7, 3, 7)],
+                vec![3, 5, 7, 3, 7]
+            ),
+            Ok(())
+        );
+        // error
+        assert!(try_clamp(vec![(1, 3, 7)], vec![1]).is_err());
+        assert!(try_clamp(vec![(9, 3, 7)], vec![9]).is_err());
+        assert!(try_clamp(vec![(5, 3, 7)], vec![3]).is_err());
+    }
+}
@@ -0,0 +1,392 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{bool_check, expr_from_bytes, is_zero, pow_of_two, Expr},
+};
+
+/// Instruction that the Comparator chip needs to implement.
+pub trait ComparatorInstruction<F: FieldExt> {
+    /// Assign the lhs and rhs witnesses to the Comparator chip's region,
+    /// returning the cells of the lt, eq and gt outcomes.
+    #[allow(clippy::type_complexity)]
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+}
+
+/// Config for the Comparator chip.
+#[derive(Clone, Copy, Debug)]
+pub struct ComparatorConfig<F, const N_BYTES: usize> {
+    /// Denotes the lt, eq and gt outcomes, exactly one of which is 1.
+    pub outcome: [Column<Advice>; 3],
+    /// Denotes the inverse of lhs - rhs, or 0 if lhs == rhs.
+    pub diff_inv: Column<Advice>,
+    /// Denotes the bytes representation of the difference between lhs and rhs.
+    /// Each byte is range checked by a lookup into the shared u8 table.
+    pub diff: [Column<Advice>; N_BYTES],
+    /// Denotes the range within which both lhs and rhs lie.
+    pub range: F,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ComparatorConfig<F, N_BYTES> {
+    /// Returns an expression that denotes whether lhs < rhs, or not.
+    pub fn is_lt(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        meta.query_advice(self.outcome[0], rotation.unwrap_or_else(Rotation::cur))
+    }
+
+    /// Returns an expression that denotes whether lhs == rhs, or not.
+    pub fn is_eq(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        meta.query_advice(self.outcome[1], rotation.unwrap_or_else(Rotation::cur))
+    }
+
+    /// Returns an expression that denotes whether lhs > rhs, or not.
+    pub fn is_gt(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        meta.query_advice(self.outcome[2], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that outputs the full ordering of lhs and rhs as three mutually
+/// exclusive booleans. lt uses the diff decomposition of the Lt chip, eq is
+/// an is-zero check of lhs - rhs, and gt is whatever is left.
+#[derive(Clone, Debug)]
+pub struct ComparatorChip<F, const N_BYTES: usize> {
+    config: ComparatorConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ComparatorChip<F, N_BYTES> {
+    /// Configures the Comparator chip.
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        lhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        rhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        outcome: [Column<Advice>; 3],
+        diff_inv: Column<Advice>,
+        diff: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
+    ) -> ComparatorConfig<F, N_BYTES> {
+        let range = pow_of_two(N_BYTES * 8);
+        for column in outcome {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("comparator gate", |meta| {
+            let q_enable = q_enable(meta);
+            let [lt, eq, gt] = outcome.map(|c| meta.query_advice(c, Rotation::cur()));
+            let diff_inv = meta.query_advice(diff_inv, Rotation::cur());
+
+            let diff_bytes = diff
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::cur()))
+                .collect::<Vec<Expression<F>>>();
+
+            let delta = lhs(meta) - rhs(meta);
+
+            let check_lt = delta.clone() - expr_from_bytes(&diff_bytes) + (lt.clone() * range);
+            // eq == 1 iff lhs - rhs == 0
+            let check_eq_zero = delta.clone() * eq.clone();
+            let check_eq_inv = 1.expr() - eq.clone() - delta * diff_inv;
+            let check_one_hot = lt.clone() + eq.clone() + gt.clone() - 1.expr();
+
+            [
+                check_lt,
+                check_eq_zero,
+                check_eq_inv,
+                check_one_hot,
+                bool_check(lt),
+                bool_check(eq),
+                bool_check(gt),
+            ]
+            .into_iter()
+            .map(move |poly| q_enable.clone() * poly)
+        });
+
+        u8_table.range_check(meta, &q_enable, &diff);
+
+        ComparatorConfig {
+            outcome,
+            diff_inv,
+            diff,
+            range,
+        }
+    }
+
+    /// Constructs a Comparator chip given a config.
+    pub fn construct(config: ComparatorConfig<F, N_BYTES>) -> ComparatorChip<F, N_BYTES> {
+        ComparatorChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ComparatorInstruction<F> for ComparatorChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+
+        let flags = [lhs < rhs, lhs == rhs, lhs > rhs];
+        let [lt_cell, eq_cell, gt_cell] = [0, 1, 2].map(|i| {
+            region.assign_advice(
+                || format!("comparator chip: outcome {}", i),
+                config.outcome[i],
+                offset,
+                || Value::known(F::from(flags[i] as u64)),
+            )
+        });
+
+        region.assign_advice(
+            || "comparator chip: diff_inv",
+            config.diff_inv,
+            offset,
+            || Value::known(is_zero::value_inv(lhs - rhs)),
+        )?;
+
+        let diff = (lhs - rhs) + (if flags[0] { config.range } else { F::zero() });
+        let diff_bytes = diff.to_repr();
+        let diff_bytes = diff_bytes.as_ref();
+        for (idx, diff_column) in config.diff.iter().enumerate() {
+            region.assign_advice(
+                || format!("comparator chip: diff byte {}", idx),
+                *diff_column,
+                offset,
+                || Value::known(F::from(diff_bytes[idx] as u64)),
+            )?;
+        }
+
+        Ok((lt_cell?, eq_cell?, gt_cell?))
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for ComparatorChip<F, N_BYTES> {
+    type Config = ComparatorConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for ComparatorChip<F, N_BYTES> {
+    type Config = Gated<ComparatorConfig<F, N_BYTES>, 2>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    /// The cells of the lt, eq and gt outcomes.
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let inputs = columns.advice::<2>(0);
+        let [diff_inv] = columns.advice::<1>(5);
+        let chip = ComparatorChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(inputs[0], Rotation::cur()),
+            |meta| meta.query_advice(inputs[1], Rotation::cur()),
+            columns.advice(2),
+            diff_inv,
+            columns.advice(6),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs,
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [lhs, rhs]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "comparator gadget",
+            |mut region| {
+                let [lhs, rhs] = config.enable(&mut region, 0, [&lhs, &rhs])?;
+                ComparatorChip::construct(config.chip).assign(&mut region, 0, lhs, rhs)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ComparatorChip, ComparatorConfig, ComparatorInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+    use std::cmp::Ordering;
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        value_a: Column<Advice>,
+        value_b: Column<Advice>,
+        check: Column<Advice>,
+        comparator: ComparatorConfig<F, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<(F, F)>,
+        // checks[i] = [lt, eq, gt] of values[i].0 and values[i].1
+        checks: Vec<[bool; 3]>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let (value_a, value_b) = (meta.advice_column(), meta.advice_column());
+            let check = meta.advice_column();
+            meta.enable_equality(check);
+            let outcome = [(); 3].map(|_| meta.advice_column());
+            let diff_inv = meta.advice_column();
+            let diff = [(); 8].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            let comparator = ComparatorChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| meta.query_advice(value_a, Rotation::cur()),
+                |meta| meta.query_advice(value_b, Rotation::cur()),
+                outcome,
+                diff_inv,
+                diff,
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                value_a,
+                value_b,
+                check,
+                comparator,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = ComparatorChip::construct(config.comparator);
+            config.u8_table.load(&mut layouter)?;
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (idx, ((value_a, value_b), checks)) in
+                        self.values.iter().zip(&self.checks).enumerate()
+                    {
+                        // the checks of a row are on the three rows from 3 * idx
+                        let offset = 3 * idx;
+                        config.q_enable.enable(&mut region, offset)?;
+                        region.assign_advice(
+                            || "value_a",
+                            config.value_a,
+                            offset,
+                            || Value::known(*value_a),
+                        )?;
+                        region.assign_advice(
+                            || "value_b",
+                            config.value_b,
+                            offset,
+                            || Value::known(*value_b),
+                        )?;
+                        let (lt, eq, gt) = chip.assign(&mut region, offset, *value_a, *value_b)?;
+                        for (i, (cell, check)) in [lt, eq, gt].iter().zip(checks).enumerate() {
+                            let check_cell = region.assign_advice(
+                                || "check",
+                                config.check,
+                                offset + i,
+                                || Value::known(F::from(*check as u64)),
+                            )?;
+                            region.constrain_equal(cell.cell(), check_cell.cell())?;
+                        }
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn try_compare(
+        values: Vec<(u64, u64)>,
+        checks: Vec<[bool; 3]>,
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values
+                .into_iter()
+                .map(|(a, b)| (Fp::from(a), Fp::from(b)))
+                .collect(),
+            checks,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn compare_columns() {
+        let outcome = |ordering: Ordering| {
+            [
+                ordering == Ordering::Less,
+                ordering == Ordering::Equal,
+                ordering == Ordering::Greater,
+            ]
+        };
+
+        // ok
+        assert_eq!(
+            try_compare(
+                vec![(1, 2), (4, 4), (6, 5), (383168731, 383168732)],
+                vec![
+                    outcome(Ordering::Less),
+                    outcome(Ordering::Equal),
+                    outcome(Ordering::Greater),
+                    outcome(Ordering::Less),
+                ]
+            ),
+            Ok(())
+        );
+        // error
+        assert!(try_compare(vec![(4, 4)], vec![outcome(Ordering::Less)]).is_err());
+        assert!(try_compare(vec![(6, 5)], vec![outcome(Ordering::Equal)]).is_err());
+        assert!(try_compare(vec![(1, 2)], vec![[true, false, true]]).is_err());
+        assert!(try_compare(vec![(1, 2)], vec![[false, false, false]]).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::Expr,
+};
+
+/// Instruction that the Cswap chip needs to implement.
+pub trait CswapInstruction<F: FieldExt> {
+    /// Copy the a and b cells to the Cswap chip's region, returning the cells
+    /// of (min, max).
+    #[allow(clippy::type_complexity)]
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+}
+
+/// Config for the Cswap chip.
+#[derive(Clone, Copy, Debug)]
+pub struct CswapConfig<F, const N_BYTES: usize> {
+    /// Denotes the a, b, min and max columns.
+    pub advice: [Column<Advice>; 4],
+    /// Denotes the comparison b < a, which is set if a and b are swapped.
+    pub lt: LtConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> CswapConfig<F, N_BYTES> {
+    /// Returns an expression that denotes whether a and b are swapped, or not.
+    pub fn is_swapped(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        self.lt.is_lt(meta, rotation)
+    }
+}
+
+/// Chip that compares and swaps two values, outputting (min, max). It takes
+/// one Lt check of b < a and selects both outputs with its outcome, so that
+/// it can be chained into sorting networks and min/max trees.
+#[derive(Clone, Debug)]
+pub struct CswapChip<F, const N_BYTES: usize> {
+    config: CswapConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> CswapChip<F, N_BYTES> {
+    /// Configures the Cswap chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + Copy,
+        advice: [Column<Advice>; 4],
+        lt: Column<Advice>,
+        diff: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
+    ) -> CswapConfig<F, N_BYTES> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let [a, b, min, max] = advice;
+
+        let lt = LtChip::configure(
+            meta,
+            q_enable,
+            |meta| meta.query_advice(b, Rotation::cur()),
+            |meta| meta.query_advice(a, Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        meta.create_gate("cswap gate", |meta| {
+            let q_enable = q_enable(meta);
+            let swap = lt.is_lt(meta, None);
+            let [a, b, min, max] = [a, b, min, max].map(|c| meta.query_advice(c, Rotation::cur()));
+
+            // min = swap ? b : a, max = swap ? a : b
+            let check_min =
+                min - (swap.clone() * b.clone() + (1.expr() - swap.clone()) * a.clone());
+            let check_max = max - (swap.clone() * a + (1.expr() - swap) * b);
+
+            [check_min, check_max]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        CswapConfig { advice, lt }
+    }
+
+    /// Constructs a Cswap chip given a config.
+    pub fn construct(config: CswapConfig<F, N_BYTES>) -> CswapChip<F, N_BYTES> {
+        CswapChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> CswapInstruction<F> for CswapChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+        let [a_column, b_column, min_column, max_column] = config.advice;
+
+        a.copy_advice(|| "cswap chip: a", region, a_column, offset)?;
+        b.copy_advice(|| "cswap chip: b", region, b_column, offset)?;
+
+        // the Lt chip takes the values themselves, which are only zero while
+        // the witnesses are unknown
+        let mut values = (F::zero(), F::zero());
+        a.value().zip(b.value()).map(|(a, b)| values = (*a, *b));
+        let (a_value, b_value) = values;
+        LtChip::construct(config.lt).assign(region, offset, b_value, a_value)?;
+
+        let (min, max) = if b_value < a_value { (b, a) } else { (a, b) };
+        let min_cell = region.assign_advice(
+            || "cswap chip: min",
+            min_column,
+            offset,
+            || min.value().copied(),
+        )?;
+        let max_cell = region.assign_advice(
+            || "cswap chip: max",
+            max_column,
+            offset,
+            || max.value().copied(),
+        )?;
+
+        Ok((min_cell, max_cell))
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for CswapChip<F, N_BYTES> {
+    type Config = CswapConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for CswapChip<F, N_BYTES> {
+    type Config = Gated<CswapConfig<F, N_BYTES>, 0>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    /// The cells of (min, max).
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let [lt] = columns.advice::<1>(4);
+        let chip = CswapChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            columns.advice(0),
+            lt,
+            columns.advice(5),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs: [],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [a, b]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "cswap gadget",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                CswapChip::construct(config.chip).assign(&mut region, 0, &a, &b)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CswapChip, CswapConfig, CswapInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        cswap: CswapConfig<F, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        pairs: Vec<(F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let lt = meta.advice_column();
+            let diff = [(); 8].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            let cswap = CswapChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                advice,
+                lt,
+                diff,
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                witness,
+                instance,
+                cswap,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = CswapChip::construct(config.cswap);
+            config.u8_table.load(&mut layouter)?;
+
+            let out_cells = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut out_cells = vec![];
+                    for (idx, (a, b)) in self.pairs.iter().enumerate() {
+                        // the operands of a pair are on the two rows from 2 * idx
+                        let offset = 2 * idx;
+                        let a = region.assign_advice(
+                            || "a",
+                            config.witness,
+                            offset,
+                            || Value::known(*a),
+                        )?;
+                        let b = region.assign_advice(
+                            || "b",
+                            config.witness,
+                            offset + 1,
+                            || Value::known(*b),
+                        )?;
+
+                        config.q_enable.enable(&mut region, offset)?;
+                        let (min, max) = chip.assign(&mut region, offset, &a, &b)?;
+                        out_cells.extend([min, max]);
+                    }
+
+                    Ok(out_cells)
+                },
+            )?;
+
+            for (i, cell) in out_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_cswap(pairs: Vec<(u64, u64)>, outs: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            pairs: pairs
+                .into_iter()
+                .map(|(a, b)| (Fp::from(a), Fp::from(b)))
+                .collect(),
+        };
+        let instance = outs.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn cswap() {
+        // ok
+        assert_eq!(
+            try_cswap(vec![(3, 7), (7, 3), (5, 5)], vec![3, 7, 3, 7, 5, 5]),
+            Ok(())
+        );
+        // error
+        assert!(try_cswap(vec![(7, 3)], vec![7, 3]).is_err());
+        assert!(try_cswap(vec![(3, 7)], vec![7, 3]).is_err());
+        assert!(try_cswap(vec![(3, 7)], vec![3, 3]).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{expr_from_bytes, Expr},
+};
+
+/// Instruction that the DivMod chip needs to implement.
+pub trait DivModInstruction<F: FieldExt> {
+    /// Copy the a and b cells to the DivMod chip's region, returning the
+    /// cells of (a / b, a % b).
+    #[allow(clippy::type_complexity)]
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+}
+
+/// Config for the DivMod chip.
+#[derive(Clone, Copy, Debug)]
+pub struct DivModConfig<F, const N_BYTES: usize> {
+    /// Denotes the a, b, quotient and remainder columns, where
+    /// a == quotient * b + remainder.
+    pub advice: [Column<Advice>; 4],
+    /// Denotes the bytes representation of the quotient.
+    /// Each byte is range checked by a lookup into the shared u8 table.
+    pub quotient_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the bytes representation of the remainder.
+    /// Each byte is range checked by a lookup into the shared u8 table.
+    pub remainder_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the comparison remainder < b, which must be set.
+    pub lt: LtConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> DivModConfig<F, N_BYTES> {
+    /// Returns an expression that denotes a / b.
+    pub fn quotient(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[2], rotation.unwrap_or_else(Rotation::cur))
+    }
+
+    /// Returns an expression that denotes a % b.
+    pub fn remainder(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[3], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that divides an N_BYTES-byte unsigned value a by a nonzero b,
+/// outputting the quotient and remainder with a == quotient * b + remainder.
+/// Both are range checked to N_BYTES bytes and the remainder is constrained
+/// below b, which makes them unique. b is expected to be range checked
+/// already.
+#[derive(Clone, Debug)]
+pub struct DivModChip<F, const N_BYTES: usize> {
+    config: DivModConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> DivModChip<F, N_BYTES> {
+    /// Configures the DivMod chip.
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + Copy,
+        advice: [Column<Advice>; 4],
+        quotient_bytes: [Column<Advice>; N_BYTES],
+        remainder_bytes: [Column<Advice>; N_BYTES],
+        lt: Column<Advice>,
+        diff: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
+    ) -> DivModConfig<F, N_BYTES> {
+        // quotient * b + remainder must not wrap around the field
+        assert!(N_BYTES < 16, "the product must fit in the field");
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let [a, b, quotient, remainder] = advice;
+
+        let lt = LtChip::configure(
+            meta,
+            q_enable,
+            |meta| meta.query_advice(remainder, Rotation::cur()),
+            |meta| meta.query_advice(b, Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        meta.create_gate("div mod gate", |meta| {
+            let q_enable = q_enable(meta);
+            let [a, b, quotient, remainder] =
+                [a, b, quotient, remainder].map(|c| meta.query_advice(c, Rotation::cur()));
+            let [quotient_bytes, remainder_bytes] = [quotient_bytes, remainder_bytes]
+                .map(|bytes| bytes.map(|c| meta.query_advice(c, Rotation::cur())));
+
+            let check_quotient = quotient.clone() - expr_from_bytes(&quotient_bytes);
+            let check_remainder = remainder.clone() - expr_from_bytes(&remainder_bytes);
+            let check_div = a - quotient * b - remainder;
+            let check_lt = lt.is_lt(meta, None) - 1.expr();
+
+            [check_quotient, check_remainder, check_div, check_lt]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        u8_table.range_check(meta, q_enable, &quotient_bytes);
+        u8_table.range_check(meta, q_enable, &remainder_bytes);
+
+        DivModConfig {
+            advice,
+            quotient_bytes,
+            remainder_bytes,
+            lt,
+        }
+    }
+
+    /// Constructs a DivMod chip given a config.
+    pub fn construct(config: DivModConfig<F, N_BYTES>) -> DivModChip<F, N_BYTES> {
+        DivModChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> DivModInstruction<F> for DivModChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+        let [a_column, b_column, quotient_column, remainder_column] = config.advice;
+
+        a.copy_advice(|| "div mod chip: a", region, a_column, offset)?;
+        b.copy_advice(|| "div mod chip: b", region, b_column, offset)?;
+
+        // both operands have at most 15 bytes, so they fit in a u128, and a
+        // zero b leaves a remainder of a, which fails the lt check
+        let to_u128 = |value: &F| {
+            let mut bytes = [0u8; 16];
+            bytes.copy_from_slice(&value.to_repr().as_ref()[..16]);
+            u128::from_le_bytes(bytes)
+        };
+        let division = a.value().zip(b.value()).map(|(a, b)| {
+            let (a, b) = (to_u128(a), to_u128(b));
+            match b {
+                0 => (0, a),
+                b => (a / b, a % b),
+            }
+        });
+        let quotient = division.map(|(quotient, _)| quotient);
+        let remainder = division.map(|(_, remainder)| remainder);
+
+        let quotient_cell = region.assign_advice(
+            || "div mod chip: quotient",
+            quotient_column,
+            offset,
+            || quotient.map(F::from_u128),
+        )?;
+        let remainder_cell = region.assign_advice(
+            || "div mod chip: remainder",
+            remainder_column,
+            offset,
+            || remainder.map(F::from_u128),
+        )?;
+
+        for (name, columns, value) in [
+            ("quotient", config.quotient_bytes, quotient),
+            ("remainder", config.remainder_bytes, remainder),
+        ] {
+            for (idx, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("div mod chip: {} byte {}", name, idx),
+                    *column,
+                    offset,
+                    || value.map(|value| F::from(value.to_le_bytes()[idx] as u64)),
+                )?;
+            }
+        }
+
+        // the Lt chip takes the values themselves, which are only zero while
+        // the witnesses are unknown
+        let mut values = (F::zero(), F::zero());
+        remainder
+            .zip(b.value())
+            .map(|(remainder, b)| values = (F::from_u128(remainder), *b));
+        let (remainder_value, b_value) = values;
+        LtChip::construct(config.lt).assign(region, offset, remainder_value, b_value)?;
+
+        Ok((quotient_cell, remainder_cell))
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for DivModChip<F, N_BYTES> {
+    type Config = DivModConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for DivModChip<F, N_BYTES> {
+    type Config = Gated<DivModConfig<F, N_BYTES>, 0>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    /// The cells of (a / b, a % b).
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let [lt] = columns.advice::<1>(4 + 2 * N_BYTES);
+        let chip = DivModChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            columns.advice(0),
+            columns.advice(4),
+            columns.advice(4 + N_BYTES),
+            lt,
+            columns.advice(5 + 2 * N_BYTES),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs: [],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [a, b]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "div mod gadget",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                DivModChip::construct(config.chip).assign(&mut region, 0, &a, &b)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DivModChip, DivModConfig, DivModInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        div_mod: DivModConfig<F, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        pairs: Vec<(F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let quotient_bytes = [(); 8].map(|_| meta.advice_column());
+            let remainder_bytes = [(); 8].map(|_| meta.advice_column());
+            let lt = meta.advice_column();
+            let diff = [(); 8].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            let div_mod = DivModChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                advice,
+                quotient_bytes,
+                remainder_bytes,
+                lt,
+                diff,
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                witness,
+                instance,
+                div_mod,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = DivModChip::construct(config.div_mod);
+            config.u8_table.load(&mut layouter)?;
+
+            let out_cells = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut out_cells = vec![];
+                    for (idx, (a, b)) in self.pairs.iter().enumerate() {
+                        // the operands of a pair are on the two rows from 2 * idx
+                        let offset = 2 * idx;
+                        let a = region.assign_advice(
+                            || "a",
+                            config.witness,
+                            offset,
+                            || Value::known(*a),
+                        )?;
+                        let b = region.assign_advice(
+                            || "b",
+                            config.witness,
+                            offset + 1,
+                            || Value::known(*b),
+                        )?;
+
+                        config.q_enable.enable(&mut region, offset)?;
+                        let (quotient, remainder) = chip.assign(&mut region, offset, &a, &b)?;
+                        out_cells.extend([quotient, remainder]);
+                    }
+
+                    Ok(out_cells)
+                },
+            )?;
+
+            for (i, cell) in out_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_div_mod(pairs: Vec<(u64, u64)>, outs: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            pairs: pairs
+                .into_iter()
+                .map(|(a, b)| (Fp::from(a), Fp::from(b)))
+                .collect(),
+        };
+        let instance = outs.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn div_mod() {
+        // ok
+        assert_eq!(
+            try_div_mod(
+                vec![(17, 5), (15, 5), (3, 7), (0, 1), (10_000, 10_000)],
+                vec![3, 2, 3, 0, 0, 3, 0, 0, 1, 0]
+            ),
+            Ok(())
+        );
+        // error: the remainder is not below b
+        assert!(try_div_mod(vec![(17, 5)], vec![2, 7]).is_err());
+        // error: division by zero
+        assert!(try_div_mod(vec![(17, 0)], vec![0, 17]).is_err());
+    }
+}
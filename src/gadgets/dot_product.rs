@@ -0,0 +1,294 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    gadgets::mul_checked::{MulCheckedChip, MulCheckedConfig, MulCheckedInstruction},
+    gadgets::{Gadget, SharedColumns},
+    table::U8Table,
+};
+
+/// Instruction that the DotProduct chip needs to implement.
+pub trait DotProductInstruction<F: FieldExt> {
+    /// Copy the a and b cells to the DotProduct chip's region, on the rows
+    /// from offset, returning the cell of a_0 * b_0 + ... + a_(n-1) * b_(n-1).
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &[AssignedCell<F, F>],
+        b: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the DotProduct chip.
+#[derive(Clone, Copy, Debug)]
+pub struct DotProductConfig {
+    /// Denotes the acc column, where acc::next == acc::cur + a::cur * b::cur.
+    pub acc: Column<Advice>,
+    /// The range checked product of the terms.
+    pub mul: MulCheckedConfig,
+    /// Denotes the first row of a dot product, where acc == 0.
+    pub q_first: Selector,
+    /// Denotes the rows adding a term to acc.
+    pub q_step: Selector,
+}
+
+/// Chip that computes the dot product of two vectors of 64-bit values, e.g.
+/// for weighted averages. Every term is a MulChecked product, so the
+/// operands and the 128-bit products are range checked, and the products are
+/// accumulated down the acc column.
+#[derive(Clone, Debug)]
+pub struct DotProductChip<F> {
+    config: DotProductConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> DotProductChip<F> {
+    /// Configures the DotProduct chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        acc: Column<Advice>,
+        mul_advice: [Column<Advice>; 5],
+        mul_bytes: [[Column<Advice>; 8]; 4],
+        u8_table: U8Table,
+    ) -> DotProductConfig {
+        meta.enable_equality(acc);
+        let q_first = meta.selector();
+        let q_step = meta.complex_selector();
+
+        let mul = MulCheckedChip::configure(
+            meta,
+            |meta| meta.query_selector(q_step),
+            mul_advice,
+            mul_bytes,
+            u8_table,
+        );
+
+        meta.create_gate("dot product gate", |meta| {
+            // a  | b  | product   | acc                     | selectors
+            // a0   b0   a0 * b0     0                         q_first, q_step
+            // a1   b1   a1 * b1     a0 * b0                   q_step
+            // ...
+            //                       a0 * b0 + ... + a(n-1) * b(n-1)
+            let q_first = meta.query_selector(q_first);
+            let q_step = meta.query_selector(q_step);
+            let product = mul.product(meta, None);
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+
+            vec![
+                q_first * acc_cur.clone(),
+                q_step * (acc_next - acc_cur - product),
+            ]
+        });
+
+        DotProductConfig {
+            acc,
+            mul,
+            q_first,
+            q_step,
+        }
+    }
+
+    /// Constructs a DotProduct chip given a config.
+    pub fn construct(config: DotProductConfig) -> DotProductChip<F> {
+        DotProductChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt> DotProductInstruction<F> for DotProductChip<F> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &[AssignedCell<F, F>],
+        b: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if a.len() != b.len() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let mul_chip = MulCheckedChip::construct(config.mul);
+        config.q_first.enable(region, offset)?;
+
+        let mut acc = Value::known(F::zero());
+        for (i, (a, b)) in a.iter().zip(b).enumerate() {
+            config.q_step.enable(region, offset + i)?;
+            region.assign_advice(
+                || format!("dot product chip: acc[{}]", i),
+                config.acc,
+                offset + i,
+                || acc,
+            )?;
+            let (product, _, _) = mul_chip.assign(region, offset + i, a, b)?;
+            acc = acc + product.value().copied();
+        }
+
+        region.assign_advice(
+            || format!("dot product chip: acc[{}]", a.len()),
+            config.acc,
+            offset + a.len(),
+            || acc,
+        )
+    }
+}
+
+impl<F: FieldExt> Chip<F> for DotProductChip<F> {
+    type Config = DotProductConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Gadget<F> for DotProductChip<F> {
+    type Config = DotProductConfig;
+    type Params = ();
+    type Input = (Vec<AssignedCell<F, F>>, Vec<AssignedCell<F, F>>);
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let [acc] = columns.advice::<1>(0);
+        DotProductChip::configure(
+            meta,
+            acc,
+            columns.advice(1),
+            [6, 14, 22, 30].map(|first| columns.advice(first)),
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        (a, b): Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "dot product gadget",
+            |mut region| DotProductChip::construct(*config).assign(&mut region, 0, &a, &b),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DotProductChip, DotProductConfig, DotProductInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        dot_product: DotProductConfig,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        a: Vec<F>,
+        b: Vec<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let acc = meta.advice_column();
+            let mul_advice = [(); 5].map(|_| meta.advice_column());
+            let mul_bytes = [(); 4].map(|_| [(); 8].map(|_| meta.advice_column()));
+            let u8_table = U8Table::configure(meta);
+
+            Self::Config {
+                witness,
+                instance,
+                dot_product: DotProductChip::configure(meta, acc, mul_advice, mul_bytes, u8_table),
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = DotProductChip::construct(config.dot_product);
+            config.u8_table.load(&mut layouter)?;
+
+            let sum = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    // a is on the rows from 0 and b on the rows from a.len()
+                    let cells = self
+                        .a
+                        .iter()
+                        .chain(self.b.iter())
+                        .enumerate()
+                        .map(|(i, value)| {
+                            region.assign_advice(
+                                || "value",
+                                config.witness,
+                                i,
+                                || Value::known(*value),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let (a, b) = cells.split_at(self.a.len());
+
+                    chip.assign(&mut region, 0, a, b)
+                },
+            )?;
+
+            layouter.constrain_instance(sum.cell(), config.instance, 0)
+        }
+    }
+
+    fn try_dot_product(a: Vec<u64>, b: Vec<u64>, sum: u128) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            a: a.into_iter().map(Fp::from).collect(),
+            b: b.into_iter().map(Fp::from).collect(),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from_u128(sum)]]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn dot_product() {
+        // ok
+        assert_eq!(try_dot_product(vec![1, 2, 3], vec![4, 5, 6], 32), Ok(()));
+        assert_eq!(
+            try_dot_product(vec![u64::MAX, u64::MAX], vec![2, 3], u64::MAX as u128 * 5),
+            Ok(())
+        );
+        // error
+        assert!(try_dot_product(vec![1, 2, 3], vec![4, 5, 6], 31).is_err());
+    }
+}
@@ -0,0 +1,403 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{one_hot, pow_of_ten, Expr},
+};
+
+/// A fixed-point number, denoting value / 10**scale.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FixedPoint<F> {
+    /// The unscaled value.
+    pub value: F,
+    /// The number of decimals of the value.
+    pub scale: usize,
+}
+
+impl<F: FieldExt> FixedPoint<F> {
+    /// Returns the value rescaled to `scale` decimals, which must be at least
+    /// the scale of the number.
+    pub fn normalize(&self, scale: usize) -> F {
+        assert!(self.scale <= scale, "cannot normalize to fewer decimals");
+        self.value * pow_of_ten::<F>(scale - self.scale)
+    }
+}
+
+/// Instruction that the FixedPointLt chip needs to implement.
+pub trait FixedPointLtInstruction<F: FieldExt> {
+    /// Assign the lhs and rhs witnesses to the FixedPointLt chip's region,
+    /// returning the cell of the lt outcome.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: FixedPoint<F>,
+        rhs: FixedPoint<F>,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the FixedPointLt chip.
+#[derive(Clone, Debug)]
+pub struct FixedPointLtConfig<F, const MAX_SCALE: usize, const N_BYTES: usize> {
+    /// Denotes the one-hot representation of the lhs scale, where the s-th
+    /// column is set if the scale is s.
+    pub lhs_scale: Vec<Column<Advice>>,
+    /// Denotes the one-hot representation of the rhs scale.
+    pub rhs_scale: Vec<Column<Advice>>,
+    /// Denotes the comparison of lhs and rhs, both normalized to MAX_SCALE
+    /// decimals. Note that the normalized values must fit in N_BYTES bytes.
+    pub lt: LtConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const MAX_SCALE: usize, const N_BYTES: usize>
+    FixedPointLtConfig<F, MAX_SCALE, N_BYTES>
+{
+    /// Returns an expression that denotes whether lhs < rhs, or not.
+    pub fn is_lt(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        self.lt.is_lt(meta, rotation)
+    }
+}
+
+/// Chip that compares lhs < rhs for fixed-point numbers of different scales.
+/// Both sides are normalized to MAX_SCALE decimals in-circuit before being
+/// compared, so that e.g. 1.5 (15, 1) is not less than 1.25 (125, 2).
+#[derive(Clone, Debug)]
+pub struct FixedPointLtChip<F, const MAX_SCALE: usize, const N_BYTES: usize> {
+    config: FixedPointLtConfig<F, MAX_SCALE, N_BYTES>,
+}
+
+impl<F: FieldExt, const MAX_SCALE: usize, const N_BYTES: usize>
+    FixedPointLtChip<F, MAX_SCALE, N_BYTES>
+{
+    /// Configures the FixedPointLt chip. The value and scale of each side are
+    /// read from the given columns, in this order, at the current row.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + Copy,
+        lhs: [Column<Advice>; 2],
+        rhs: [Column<Advice>; 2],
+        u8_table: U8Table,
+    ) -> FixedPointLtConfig<F, MAX_SCALE, N_BYTES> {
+        assert!(N_BYTES < 32, "normalized values must fit in the field");
+
+        let lhs_scale: Vec<_> = (0..=MAX_SCALE).map(|_| meta.advice_column()).collect();
+        let rhs_scale: Vec<_> = (0..=MAX_SCALE).map(|_| meta.advice_column()).collect();
+
+        for ([_, scale], bits) in [(lhs, &lhs_scale), (rhs, &rhs_scale)] {
+            meta.create_gate("fixed point scale", |meta| {
+                let q_enable = q_enable(meta);
+                let scale = meta.query_advice(scale, Rotation::cur());
+                let bits: Vec<_> = bits
+                    .iter()
+                    .map(|c| meta.query_advice(*c, Rotation::cur()))
+                    .collect();
+
+                // the bits are a one-hot encoding of the scale
+                one_hot::constraints(&bits)
+                    .into_iter()
+                    .chain([scale - one_hot::index(&bits)])
+                    .map(move |poly| q_enable.clone() * poly)
+                    .collect::<Vec<_>>()
+            });
+        }
+
+        let lt = meta.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta.advice_column());
+        let lt = LtChip::configure(
+            meta,
+            q_enable,
+            |meta| Self::normalized(meta, lhs[0], &lhs_scale),
+            |meta| Self::normalized(meta, rhs[0], &rhs_scale),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        FixedPointLtConfig {
+            lhs_scale,
+            rhs_scale,
+            lt,
+        }
+    }
+
+    /// Constructs a FixedPointLt chip given a config.
+    pub fn construct(
+        config: FixedPointLtConfig<F, MAX_SCALE, N_BYTES>,
+    ) -> FixedPointLtChip<F, MAX_SCALE, N_BYTES> {
+        FixedPointLtChip { config }
+    }
+
+    // value * 10**(MAX_SCALE - scale), selecting the power with the one-hot
+    // scale bits
+    fn normalized(
+        meta: &mut VirtualCells<F>,
+        value: Column<Advice>,
+        bits: &[Column<Advice>],
+    ) -> Expression<F> {
+        let value = meta.query_advice(value, Rotation::cur());
+        let multiplier = bits.iter().enumerate().fold(0.expr(), |acc, (s, bit)| {
+            acc + meta.query_advice(*bit, Rotation::cur())
+                * Expression::Constant(pow_of_ten::<F>(MAX_SCALE - s))
+        });
+        value * multiplier
+    }
+}
+
+impl<F: FieldExt, const MAX_SCALE: usize, const N_BYTES: usize> FixedPointLtInstruction<F>
+    for FixedPointLtChip<F, MAX_SCALE, N_BYTES>
+{
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: FixedPoint<F>,
+        rhs: FixedPoint<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        for (number, bits) in [(lhs, &config.lhs_scale), (rhs, &config.rhs_scale)] {
+            assert!(number.scale <= MAX_SCALE, "scale exceeds MAX_SCALE");
+            for (s, bit) in bits.iter().enumerate() {
+                region.assign_advice(
+                    || format!("fixed point lt chip: scale bit {}", s),
+                    *bit,
+                    offset,
+                    || Value::known(F::from((s == number.scale) as u64)),
+                )?;
+            }
+        }
+
+        LtChip::construct(config.lt).assign(
+            region,
+            offset,
+            lhs.normalize(MAX_SCALE),
+            rhs.normalize(MAX_SCALE),
+        )
+    }
+}
+
+impl<F: FieldExt, const MAX_SCALE: usize, const N_BYTES: usize> Chip<F>
+    for FixedPointLtChip<F, MAX_SCALE, N_BYTES>
+{
+    type Config = FixedPointLtConfig<F, MAX_SCALE, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const MAX_SCALE: usize, const N_BYTES: usize> Gadget<F>
+    for FixedPointLtChip<F, MAX_SCALE, N_BYTES>
+{
+    type Config = Gated<FixedPointLtConfig<F, MAX_SCALE, N_BYTES>, 4>;
+    type Params = ();
+    /// The cells of the value and scale of lhs, then of rhs.
+    type Input = [AssignedCell<F, F>; 4];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let inputs = columns.advice::<4>(0);
+        let chip = FixedPointLtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            [inputs[0], inputs[1]],
+            [inputs[2], inputs[3]],
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs,
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "fixed point lt gadget",
+            |mut region| {
+                let [lhs_value, lhs_scale, rhs_value, rhs_scale] =
+                    config.enable(&mut region, 0, [&input[0], &input[1], &input[2], &input[3]])?;
+                let lhs = FixedPoint {
+                    value: lhs_value,
+                    scale: lhs_scale.get_lower_128() as usize,
+                };
+                let rhs = FixedPoint {
+                    value: rhs_value,
+                    scale: rhs_scale.get_lower_128() as usize,
+                };
+                FixedPointLtChip::construct(config.chip.clone()).assign(&mut region, 0, lhs, rhs)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FixedPoint, FixedPointLtChip, FixedPointLtConfig, FixedPointLtInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        lhs: [Column<Advice>; 2],
+        rhs: [Column<Advice>; 2],
+        check: Column<Advice>,
+        lt: FixedPointLtConfig<F, 4, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        // (lhs, rhs, lhs < rhs)
+        rows: Vec<(FixedPoint<F>, FixedPoint<F>, bool)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let lhs = [meta.advice_column(), meta.advice_column()];
+            let rhs = [meta.advice_column(), meta.advice_column()];
+            let check = meta.advice_column();
+            let u8_table = U8Table::configure(meta);
+
+            let lt = FixedPointLtChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                lhs,
+                rhs,
+                u8_table,
+            );
+
+            let config = Self::Config {
+                q_enable,
+                lhs,
+                rhs,
+                check,
+                lt,
+                u8_table,
+            };
+
+            meta.create_gate("check fixed point is_lt", |meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let check = meta.query_advice(config.check, Rotation::cur());
+
+                vec![q_enable * (config.lt.is_lt(meta, None) - check)]
+            });
+
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = FixedPointLtChip::construct(config.lt.clone());
+            config.u8_table.load(&mut layouter)?;
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (offset, (lhs, rhs, check)) in self.rows.iter().enumerate() {
+                        config.q_enable.enable(&mut region, offset)?;
+                        for (number, [value, scale]) in [(lhs, config.lhs), (rhs, config.rhs)] {
+                            region.assign_advice(
+                                || "value",
+                                value,
+                                offset,
+                                || Value::known(number.value),
+                            )?;
+                            region.assign_advice(
+                                || "scale",
+                                scale,
+                                offset,
+                                || Value::known(F::from(number.scale as u64)),
+                            )?;
+                        }
+                        region.assign_advice(
+                            || "check",
+                            config.check,
+                            offset,
+                            || Value::known(F::from(*check as u64)),
+                        )?;
+                        chip.assign(&mut region, offset, *lhs, *rhs)?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn try_fixed_point_lt(
+        rows: Vec<((u64, usize), (u64, usize), bool)>,
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let number = |(value, scale): (u64, usize)| FixedPoint {
+            value: Fp::from(value),
+            scale,
+        };
+        let circuit = TestCircuit {
+            rows: rows
+                .into_iter()
+                .map(|(lhs, rhs, check)| (number(lhs), number(rhs), check))
+                .collect(),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn fixed_point_is_lt() {
+        // ok
+        assert_eq!(
+            try_fixed_point_lt(vec![
+                // 1.5 < 1.25
+                ((15, 1), (125, 2), false),
+                // 1.25 < 1.5
+                ((125, 2), (15, 1), true),
+                // 2 < 2.0000
+                ((2, 0), (20000, 4), false),
+                // 0.0001 < 1
+                ((1, 4), (1, 0), true),
+            ]),
+            Ok(())
+        );
+        // error: compared without normalizing
+        assert!(try_fixed_point_lt(vec![((15, 1), (125, 2), true)]).is_err());
+        // error
+        assert!(try_fixed_point_lt(vec![((2, 0), (20000, 4), true)]).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+};
+
+/// Instruction that the Gt chip needs to implement.
+pub trait GtInstruction<F: FieldExt> {
+    /// Assign the lhs and rhs witnesses to the Gt chip's region, returning
+    /// the cell of the gt outcome.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the Gt chip.
+#[derive(Clone, Copy, Debug)]
+pub struct GtConfig<F, const N_BYTES: usize> {
+    /// The comparison rhs < lhs, whose outcome is the gt outcome.
+    pub lt: LtConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> GtConfig<F, N_BYTES> {
+    /// Returns an expression that denotes whether lhs > rhs, or not.
+    pub fn is_gt(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        self.lt.is_lt(meta, rotation)
+    }
+}
+
+/// Chip that compares lhs > rhs. It is the Lt chip with its operands
+/// swapped, as lhs > rhs is rhs < lhs.
+#[derive(Clone, Debug)]
+pub struct GtChip<F, const N_BYTES: usize> {
+    config: GtConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> GtChip<F, N_BYTES> {
+    /// Configures the Gt chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        lhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        rhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        gt: Column<Advice>,
+        diff: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
+    ) -> GtConfig<F, N_BYTES> {
+        let lt = LtChip::configure(meta, q_enable, rhs, lhs, gt, diff, u8_table);
+
+        GtConfig { lt }
+    }
+
+    /// Constructs a Gt chip given a config.
+    pub fn construct(config: GtConfig<F, N_BYTES>) -> GtChip<F, N_BYTES> {
+        GtChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> GtInstruction<F> for GtChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        LtChip::construct(self.config().lt).assign(region, offset, rhs, lhs)
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for GtChip<F, N_BYTES> {
+    type Config = GtConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for GtChip<F, N_BYTES> {
+    type Config = Gated<GtConfig<F, N_BYTES>, 2>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let inputs = columns.advice::<2>(0);
+        let [gt] = columns.advice::<1>(2);
+        let chip = GtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(inputs[0], Rotation::cur()),
+            |meta| meta.query_advice(inputs[1], Rotation::cur()),
+            gt,
+            columns.advice(3),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs,
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [lhs, rhs]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "gt gadget",
+            |mut region| {
+                let [lhs, rhs] = config.enable(&mut region, 0, [&lhs, &rhs])?;
+                GtChip::construct(config.chip).assign(&mut region, 0, lhs, rhs)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GtChip, GtConfig, GtInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        value_a: Column<Advice>,
+        value_b: Column<Advice>,
+        check: Column<Advice>,
+        gt: GtConfig<F, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<(F, F)>,
+        // checks[i] = gt(values[i].0, values[i].1)
+        checks: Vec<bool>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let (value_a, value_b) = (meta.advice_column(), meta.advice_column());
+            let check = meta.advice_column();
+            meta.enable_equality(check);
+            let gt_col = meta.advice_column();
+            let diff = [(); 8].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            let gt = GtChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| meta.query_advice(value_a, Rotation::cur()),
+                |meta| meta.query_advice(value_b, Rotation::cur()),
+                gt_col,
+                diff,
+                u8_table,
+            );
+
+            let config = Self::Config {
+                q_enable,
+                value_a,
+                value_b,
+                check,
+                gt,
+                u8_table,
+            };
+
+            meta.create_gate("check is_gt between columns in the same row", |meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let check = meta.query_advice(config.check, Rotation::cur());
+
+                vec![q_enable * (config.gt.is_gt(meta, None) - check)]
+            });
+
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = GtChip::construct(config.gt);
+            config.u8_table.load(&mut layouter)?;
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (idx, ((value_a, value_b), check)) in
+                        self.values.iter().zip(&self.checks).enumerate()
+                    {
+                        config.q_enable.enable(&mut region, idx)?;
+                        region.assign_advice(
+                            || "value_a",
+                            config.value_a,
+                            idx,
+                            || Value::known(*value_a),
+                        )?;
+                        region.assign_advice(
+                            || "value_b",
+                            config.value_b,
+                            idx,
+                            || Value::known(*value_b),
+                        )?;
+                        let gt_cell = chip.assign(&mut region, idx, *value_a, *value_b)?;
+                        let check_cell = region.assign_advice(
+                            || "check",
+                            config.check,
+                            idx,
+                            || Value::known(F::from(*check as u64)),
+                        )?;
+                        region.constrain_equal(gt_cell.cell(), check_cell.cell())?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn try_gt(values: Vec<(u64, u64)>, checks: Vec<bool>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values
+                .into_iter()
+                .map(|(a, b)| (Fp::from(a), Fp::from(b)))
+                .collect(),
+            checks,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn column_diff_is_gt() {
+        // ok
+        assert_eq!(
+            try_gt(vec![(1, 2), (4, 4), (6, 5)], vec![false, false, true]),
+            Ok(())
+        );
+        // error
+        assert!(try_gt(vec![(4, 4)], vec![true]).is_err());
+        assert!(try_gt(vec![(1, 2), (6, 5)], vec![true, false]).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::{Gadget, Gated, SharedColumns},
+    util::is_zero,
+};
+use std::marker::PhantomData;
+
+/// Instruction that the IsZero chip needs to implement.
+pub trait IsZeroInstruction<F: FieldExt> {
+    /// Assign the value witness to the IsZero chip's region, returning the
+    /// cell of the is_zero outcome.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: F,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the IsZero chip.
+#[derive(Clone, Copy, Debug)]
+pub struct IsZeroConfig {
+    /// Denotes the is_zero outcome. If value == 0 then is_zero == 1, otherwise
+    /// is_zero == 0.
+    pub is_zero: Column<Advice>,
+    /// Denotes the inverse of the value, or 0 if the value is 0.
+    pub value_inv: Column<Advice>,
+}
+
+impl IsZeroConfig {
+    /// Returns an expression that denotes whether value == 0, or not.
+    pub fn is_zero<F: FieldExt>(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.is_zero, rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that checks value == 0. The value times its inverse is 1 unless the
+/// value is 0, in which case is_zero is the only way to satisfy
+/// 1 - is_zero - value * value_inv == 0, and value * is_zero == 0 prevents
+/// is_zero from being set for any other value.
+#[derive(Clone, Debug)]
+pub struct IsZeroChip<F> {
+    config: IsZeroConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> IsZeroChip<F> {
+    /// Configures the IsZero chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        is_zero: Column<Advice>,
+        value_inv: Column<Advice>,
+    ) -> IsZeroConfig {
+        meta.enable_equality(is_zero);
+
+        meta.create_gate("is_zero gate", |meta| {
+            let q_enable = q_enable(meta);
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+            let value_inv = meta.query_advice(value_inv, Rotation::cur());
+            let value = value(meta);
+
+            let check_a = value.clone() * is_zero.clone();
+            let check_b = is_zero::expr(value, value_inv) - is_zero;
+
+            [check_a, check_b]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        IsZeroConfig { is_zero, value_inv }
+    }
+
+    /// Constructs an IsZero chip given a config.
+    pub fn construct(config: IsZeroConfig) -> IsZeroChip<F> {
+        IsZeroChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt> IsZeroInstruction<F> for IsZeroChip<F> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        region.assign_advice(
+            || "is_zero chip: value_inv",
+            config.value_inv,
+            offset,
+            || Value::known(is_zero::value_inv(value)),
+        )?;
+        region.assign_advice(
+            || "is_zero chip: is_zero",
+            config.is_zero,
+            offset,
+            || Value::known(F::from(bool::from(value.is_zero()) as u64)),
+        )
+    }
+}
+
+impl<F: FieldExt> Chip<F> for IsZeroChip<F> {
+    type Config = IsZeroConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Gadget<F> for IsZeroChip<F> {
+    type Config = Gated<IsZeroConfig, 1>;
+    type Params = ();
+    type Input = AssignedCell<F, F>;
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.selector();
+        let [value, is_zero, value_inv] = columns.advice(0);
+        let chip = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            is_zero,
+            value_inv,
+        );
+        Gated {
+            selector,
+            inputs: [value],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        value: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "is_zero gadget",
+            |mut region| {
+                let [value] = config.enable(&mut region, 0, [&value])?;
+                IsZeroChip::construct(config.chip).assign(&mut region, 0, value)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IsZeroChip, IsZeroConfig, IsZeroInstruction};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        q_enable: Selector,
+        value: Column<Advice>,
+        check: Column<Advice>,
+        is_zero: IsZeroConfig,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<F>,
+        // checks[i] = is_zero(values[i])
+        checks: Vec<bool>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.selector();
+            let value = meta.advice_column();
+            let check = meta.advice_column();
+            let (is_zero, value_inv) = (meta.advice_column(), meta.advice_column());
+
+            let is_zero = IsZeroChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| meta.query_advice(value, Rotation::cur()),
+                is_zero,
+                value_inv,
+            );
+
+            let config = Self::Config {
+                q_enable,
+                value,
+                check,
+                is_zero,
+            };
+
+            meta.create_gate("check is_zero", |meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let check = meta.query_advice(config.check, Rotation::cur());
+
+                vec![q_enable * (config.is_zero.is_zero(meta, None) - check)]
+            });
+
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = IsZeroChip::construct(config.is_zero);
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (idx, (value, check)) in self.values.iter().zip(&self.checks).enumerate() {
+                        config.q_enable.enable(&mut region, idx)?;
+                        region.assign_advice(
+                            || "value",
+                            config.value,
+                            idx,
+                            || Value::known(*value),
+                        )?;
+                        region.assign_advice(
+                            || "check",
+                            config.check,
+                            idx,
+                            || Value::known(F::from(*check as u64)),
+                        )?;
+                        chip.assign(&mut region, idx, *value)?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn try_is_zero(values: Vec<Fp>, checks: Vec<bool>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit { values, checks };
+        let prover = MockProver::<Fp>::run(4, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn value_is_zero() {
+        // ok
+        assert_eq!(
+            try_is_zero(
+                vec![Fp::from(0), Fp::from(1), -Fp::from(1), Fp::from(383168731)],
+                vec![true, false, false, false]
+            ),
+            Ok(())
+        );
+        // error
+        assert!(try_is_zero(vec![Fp::from(0)], vec![false]).is_err());
+        assert!(try_is_zero(vec![Fp::from(5)], vec![true]).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{bool_check, expr_from_bytes, pow_of_two, Expr},
+};
+
+/// Instruction that the Leq chip needs to implement.
+pub trait LeqInstruction<F: FieldExt> {
+    /// Assign the lhs and rhs witnesses to the Leq chip's region, returning
+    /// the cell of the leq outcome.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the Leq chip.
+#[derive(Clone, Copy, Debug)]
+pub struct LeqConfig<F, const N_BYTES: usize> {
+    /// Denotes the leq outcome. If lhs <= rhs then leq == 1, otherwise leq == 0.
+    pub leq: Column<Advice>,
+    /// Denotes the bytes representation of lhs - rhs - 1.
+    /// Each byte is range checked by a lookup into the shared u8 table.
+    pub diff: [Column<Advice>; N_BYTES],
+    /// Denotes the range within which both lhs and rhs lie.
+    pub range: F,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> LeqConfig<F, N_BYTES> {
+    /// Returns an expression that denotes whether lhs <= rhs, or not.
+    pub fn is_leq(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        meta.query_advice(self.leq, rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that compares lhs <= rhs. It uses the diff decomposition of the Lt
+/// chip, as lhs <= rhs is lhs < rhs + 1.
+#[derive(Clone, Debug)]
+pub struct LeqChip<F, const N_BYTES: usize> {
+    config: LeqConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> LeqChip<F, N_BYTES> {
+    /// Configures the Leq chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        lhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        rhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        leq: Column<Advice>,
+        diff: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
+    ) -> LeqConfig<F, N_BYTES> {
+        let range = pow_of_two(N_BYTES * 8);
+        meta.enable_equality(leq);
+
+        meta.create_gate("leq gate", |meta| {
+            let q_enable = q_enable(meta);
+            let leq = meta.query_advice(leq, Rotation::cur());
+
+            let diff_bytes = diff
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::cur()))
+                .collect::<Vec<Expression<F>>>();
+
+            let check_a = lhs(meta) - rhs(meta) - 1.expr() - expr_from_bytes(&diff_bytes)
+                + (leq.clone() * range);
+
+            let check_b = bool_check(leq);
+
+            [check_a, check_b]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        u8_table.range_check(meta, &q_enable, &diff);
+
+        LeqConfig { leq, diff, range }
+    }
+
+    /// Constructs a Leq chip given a config.
+    pub fn construct(config: LeqConfig<F, N_BYTES>) -> LeqChip<F, N_BYTES> {
+        LeqChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> LeqInstruction<F> for LeqChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let leq = lhs <= rhs;
+        let leq_cell = region.assign_advice(
+            || "leq chip: leq",
+            config.leq,
+            offset,
+            || Value::known(F::from(leq as u64)),
+        )?;
+
+        let diff = (lhs - rhs - F::one()) + (if leq { config.range } else { F::zero() });
+        let diff_bytes = diff.to_repr();
+        let diff_bytes = diff_bytes.as_ref();
+        for (idx, diff_column) in config.diff.iter().enumerate() {
+            region.assign_advice(
+                || format!("leq chip: diff byte {}", idx),
+                *diff_column,
+                offset,
+                || Value::known(F::from(diff_bytes[idx] as u64)),
+            )?;
+        }
+
+        Ok(leq_cell)
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for LeqChip<F, N_BYTES> {
+    type Config = LeqConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for LeqChip<F, N_BYTES> {
+    type Config = Gated<LeqConfig<F, N_BYTES>, 2>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let inputs = columns.advice::<2>(0);
+        let [leq] = columns.advice::<1>(2);
+        let chip = LeqChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(inputs[0], Rotation::cur()),
+            |meta| meta.query_advice(inputs[1], Rotation::cur()),
+            leq,
+            columns.advice(3),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs,
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [lhs, rhs]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "leq gadget",
+            |mut region| {
+                let [lhs, rhs] = config.enable(&mut region, 0, [&lhs, &rhs])?;
+                LeqChip::construct(config.chip).assign(&mut region, 0, lhs, rhs)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LeqChip, LeqConfig, LeqInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        value_a: Column<Advice>,
+        value_b: Column<Advice>,
+        check: Column<Advice>,
+        leq: LeqConfig<F, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<(F, F)>,
+        // checks[i] = leq(values[i].0, values[i].1)
+        checks: Vec<bool>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let (value_a, value_b) = (meta.advice_column(), meta.advice_column());
+            let check = meta.advice_column();
+            meta.enable_equality(check);
+            let leq_col = meta.advice_column();
+            let diff = [(); 8].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            let leq = LeqChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| meta.query_advice(value_a, Rotation::cur()),
+                |meta| meta.query_advice(value_b, Rotation::cur()),
+                leq_col,
+                diff,
+                u8_table,
+            );
+
+            let config = Self::Config {
+                q_enable,
+                value_a,
+                value_b,
+                check,
+                leq,
+                u8_table,
+            };
+
+            meta.create_gate("check is_leq between columns in the same row", |meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let check = meta.query_advice(config.check, Rotation::cur());
+
+                vec![q_enable * (config.leq.is_leq(meta, None) - check)]
+            });
+
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = LeqChip::construct(config.leq);
+            config.u8_table.load(&mut layouter)?;
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (idx, ((value_a, value_b), check)) in
+                        self.values.iter().zip(&self.checks).enumerate()
+                    {
+                        config.q_enable.enable(&mut region, idx)?;
+                        region.assign_advice(
+                            || "value_a",
+                            config.value_a,
+                            idx,
+                            || Value::known(*value_a),
+                        )?;
+                        region.assign_advice(
+                            || "value_b",
+                            config.value_b,
+                            idx,
+                            || Value::known(*value_b),
+                        )?;
+                        let leq_cell = chip.assign(&mut region, idx, *value_a, *value_b)?;
+                        // the returned cell is the leq outcome
+                        let check_cell = region.assign_advice(
+                            || "check",
+                            config.check,
+                            idx,
+                            || Value::known(F::from(*check as u64)),
+                        )?;
+                        region.constrain_equal(leq_cell.cell(), check_cell.cell())?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn try_leq(values: Vec<(u64, u64)>, checks: Vec<bool>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values
+                .into_iter()
+                .map(|(a, b)| (Fp::from(a), Fp::from(b)))
+                .collect(),
+            checks,
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn column_diff_is_leq() {
+        // ok
+        assert_eq!(
+            try_leq(vec![(1, 2), (4, 4), (6, 5)], vec![true, true, false]),
+            Ok(())
+        );
+        assert_eq!(
+            try_leq(
+                vec![(0, 0), (383168732, 383168731), (383168731, 383168732)],
+                vec![true, false, true]
+            ),
+            Ok(())
+        );
+        // error
+        assert!(try_leq(vec![(4, 4)], vec![false]).is_err());
+        assert!(try_leq(vec![(1, 2), (6, 5)], vec![false, true]).is_err());
+    }
+}
@@ -1,22 +1,41 @@
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{Chip, Region, Value},
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
     poly::Rotation,
 };
 
-use crate::util::{bool_check, expr_from_bytes, pow_of_two};
+use crate::{
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{bool_check, expr_from_bytes, pow_of_two},
+};
 
 /// Instruction that the Lt chip needs to implement.
 pub trait LtInstruction<F: FieldExt> {
-    /// Assign the lhs and rhs witnesses to the Lt chip's region.
+    /// Assign the lhs and rhs witnesses to the Lt chip's region, returning
+    /// the cell of the lt outcome.
     fn assign(
         &self,
         region: &mut Region<'_, F>,
         offset: usize,
         lhs: F,
         rhs: F,
-    ) -> Result<(), Error>;
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_with_diff(region, offset, lhs, rhs)
+            .map(|(lt, _)| lt)
+    }
+
+    /// Assign the lhs and rhs witnesses to the Lt chip's region, returning
+    /// the cell of the lt outcome along with the cells of the diff bytes.
+    #[allow(clippy::type_complexity)]
+    fn assign_with_diff(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error>;
 }
 
 /// Config for the Lt chip.
@@ -25,7 +44,7 @@ pub struct LtConfig<F, const N_BYTES: usize> {
     /// Denotes the lt outcome. If lhs < rhs then lt == 1, otherwise lt == 0.
     pub lt: Column<Advice>,
     /// Denotes the bytes representation of the difference between lhs and rhs.
-    /// Note that the range of each byte is not checked by this config.
+    /// Each byte is range checked by a lookup into the shared u8 table.
     pub diff: [Column<Advice>; N_BYTES],
     /// Denotes the range within which both lhs and rhs lie.
     pub range: F,
@@ -36,6 +55,28 @@ impl<F: FieldExt, const N_BYTES: usize> LtConfig<F, N_BYTES> {
     pub fn is_lt(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
         meta.query_advice(self.lt, rotation.unwrap_or_else(Rotation::cur))
     }
+
+    /// Returns the witness of lhs < rhs, to be assigned by
+    /// `LtChip::assign_witness`.
+    pub fn witness(&self, lhs: F, rhs: F) -> LtWitness<N_BYTES> {
+        let lt = lhs < rhs;
+        let diff = (lhs - rhs) + (if lt { self.range } else { F::zero() });
+        let diff = diff.to_repr();
+        LtWitness {
+            lt,
+            diff: diff.as_ref()[..N_BYTES].try_into().unwrap(),
+        }
+    }
+}
+
+/// Witness of the Lt chip, computed apart from its assignment so that many
+/// can be computed in parallel ahead of a region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LtWitness<const N_BYTES: usize> {
+    /// Whether lhs < rhs.
+    pub lt: bool,
+    /// The little-endian bytes of lhs - rhs, plus the range if lhs < rhs.
+    pub diff: [u8; N_BYTES],
 }
 
 /// Chip that compares lhs < rhs.
@@ -48,15 +89,17 @@ impl<F: FieldExt, const N_BYTES: usize> LtChip<F, N_BYTES> {
     /// Configures the Lt chip.
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
-        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
         lhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
         rhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
         lt: Column<Advice>,
         diff: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
     ) -> LtConfig<F, N_BYTES> {
         // let lt = meta.advice_column();
         // let diff = [(); N_BYTES].map(|_| meta.advice_column());
         let range = pow_of_two(N_BYTES * 8);
+        meta.enable_equality(lt);
 
         meta.create_gate("lt gate", |meta| {
             let q_enable = q_enable(meta);
@@ -77,6 +120,8 @@ impl<F: FieldExt, const N_BYTES: usize> LtChip<F, N_BYTES> {
                 .map(move |poly| q_enable.clone() * poly)
         });
 
+        u8_table.range_check(meta, &q_enable, &diff);
+
         LtConfig { lt, diff, range }
     }
 
@@ -86,37 +131,48 @@ impl<F: FieldExt, const N_BYTES: usize> LtChip<F, N_BYTES> {
     }
 }
 
-impl<F: FieldExt, const N_BYTES: usize> LtInstruction<F> for LtChip<F, N_BYTES> {
-    fn assign(
+impl<F: FieldExt, const N_BYTES: usize> LtChip<F, N_BYTES> {
+    /// Assigns a witness of `LtConfig::witness`, returning the cell of the lt
+    /// outcome along with the cells of the diff bytes.
+    #[allow(clippy::type_complexity)]
+    pub fn assign_witness(
         &self,
         region: &mut Region<'_, F>,
         offset: usize,
-        lhs: F,
-        rhs: F,
-    ) -> Result<(), Error> {
+        witness: &LtWitness<N_BYTES>,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
         let config = self.config();
 
-        let lt = lhs < rhs;
-        region.assign_advice(
+        let lt_cell = region.assign_advice(
             || "lt chip: lt",
             config.lt,
             offset,
-            || Value::known(F::from(lt as u64)),
+            || Value::known(F::from(witness.lt as u64)),
         )?;
 
-        let diff = (lhs - rhs) + (if lt { config.range } else { F::zero() });
-        let diff_bytes = diff.to_repr();
-        let diff_bytes = diff_bytes.as_ref();
-        for (idx, diff_column) in config.diff.iter().enumerate() {
-            region.assign_advice(
+        let mut diff_cells = Vec::with_capacity(N_BYTES);
+        for (idx, (diff_column, byte)) in config.diff.iter().zip(witness.diff).enumerate() {
+            diff_cells.push(region.assign_advice(
                 || format!("lt chip: diff byte {}", idx),
                 *diff_column,
                 offset,
-                || Value::known(F::from(diff_bytes[idx] as u64)),
-            )?;
+                || Value::known(F::from(byte as u64)),
+            )?);
         }
 
-        Ok(())
+        Ok((lt_cell, diff_cells))
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> LtInstruction<F> for LtChip<F, N_BYTES> {
+    fn assign_with_diff(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        self.assign_witness(region, offset, &self.config.witness(lhs, rhs))
     }
 }
 
@@ -133,9 +189,51 @@ impl<F: FieldExt, const N_BYTES: usize> Chip<F> for LtChip<F, N_BYTES> {
     }
 }
 
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for LtChip<F, N_BYTES> {
+    type Config = Gated<LtConfig<F, N_BYTES>, 2>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let inputs = columns.advice::<2>(0);
+        let [lt] = columns.advice::<1>(2);
+        let chip = LtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(inputs[0], Rotation::cur()),
+            |meta| meta.query_advice(inputs[1], Rotation::cur()),
+            lt,
+            columns.advice(3),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs,
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [lhs, rhs]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "lt gadget",
+            |mut region| {
+                let [lhs, rhs] = config.enable(&mut region, 0, [&lhs, &rhs])?;
+                LtChip::construct(config.chip).assign(&mut region, 0, lhs, rhs)
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{LtChip, LtConfig, LtInstruction};
+    use crate::table::U8Table;
     use halo2_proofs::{
         arithmetic::FieldExt,
         circuit::{Layouter, SimpleFloorPlanner, Value},
@@ -152,7 +250,8 @@ mod test {
 
             // TODO: remove zk blinding factors in halo2 to restore the
             // correct k (without the extra + 2).
-            let k = usize::BITS - $values.len().leading_zeros() + 2;
+            // The u8 table needs 256 rows.
+            let k = (usize::BITS - $values.len().leading_zeros() + 2).max(9);
             let circuit = TestCircuit::<Fp> {
                 values: Some($values),
                 checks: Some($checks),
@@ -169,7 +268,8 @@ mod test {
 
             // TODO: remove zk blinding factors in halo2 to restore the
             // correct k (without the extra + 2).
-            let k = usize::BITS - $values.len().leading_zeros() + 2;
+            // The u8 table needs 256 rows.
+            let k = (usize::BITS - $values.len().leading_zeros() + 2).max(9);
             let circuit = TestCircuit::<Fp> {
                 values: Some($values),
                 checks: Some($checks),
@@ -188,6 +288,7 @@ mod test {
             value: Column<Advice>,
             check: Column<Advice>,
             lt: LtConfig<F, 8>,
+            u8_table: U8Table,
         }
 
         #[derive(Default)]
@@ -212,6 +313,7 @@ mod test {
                 let check = meta.advice_column();
                 let lt = meta.advice_column();
                 let diff = [(); 8].map(|_| meta.advice_column());
+                let u8_table = U8Table::configure(meta);
 
                 let lt = LtChip::configure(
                     meta,
@@ -220,6 +322,7 @@ mod test {
                     |meta| meta.query_advice(value, Rotation::cur()),
                     lt,
                     diff,
+                    u8_table,
                 );
 
                 let config = Self::Config {
@@ -227,6 +330,7 @@ mod test {
                     value,
                     check,
                     lt,
+                    u8_table,
                 };
 
                 meta.create_gate("check is_lt between adjacent rows", |meta| {
@@ -247,6 +351,7 @@ mod test {
                 mut layouter: impl Layouter<F>,
             ) -> Result<(), Error> {
                 let chip = LtChip::construct(config.lt);
+                config.u8_table.load(&mut layouter)?;
 
                 let values: Vec<_> = self
                     .values
@@ -310,6 +415,7 @@ mod test {
             value_b: Column<Advice>,
             check: Column<Advice>,
             lt: LtConfig<F, 8>,
+            u8_table: U8Table,
         }
 
         #[derive(Default)]
@@ -332,8 +438,10 @@ mod test {
                 let q_enable = meta.complex_selector();
                 let (value_a, value_b) = (meta.advice_column(), meta.advice_column());
                 let check = meta.advice_column();
+                meta.enable_equality(check);
                 let lt_col = meta.advice_column();
                 let diff = [(); 8].map(|_| meta.advice_column());
+                let u8_table = U8Table::configure(meta);
 
                 let lt = LtChip::configure(
                     meta,
@@ -342,6 +450,7 @@ mod test {
                     |meta| meta.query_advice(value_b, Rotation::cur()),
                     lt_col,
                     diff,
+                    u8_table,
                 );
 
                 let config = Self::Config {
@@ -350,6 +459,7 @@ mod test {
                     value_b,
                     check,
                     lt,
+                    u8_table,
                 };
 
                 meta.create_gate("check is_lt between columns in the same row", |meta| {
@@ -370,6 +480,7 @@ mod test {
                 mut layouter: impl Layouter<F>,
             ) -> Result<(), Error> {
                 let chip = LtChip::construct(config.lt);
+                config.u8_table.load(&mut layouter)?;
 
                 let values: Vec<_> = self
                     .values
@@ -390,7 +501,7 @@ mod test {
                             values.iter().zip(checks).enumerate()
                         {
                             config.q_enable.enable(&mut region, idx + 1)?;
-                            region.assign_advice(
+                            let check_cell = region.assign_advice(
                                 || "check",
                                 config.check,
                                 idx + 1,
@@ -408,7 +519,9 @@ mod test {
                                 idx + 1,
                                 || Value::known(*value_b),
                             )?;
-                            chip.assign(&mut region, idx + 1, *value_a, *value_b)?;
+                            let lt_cell = chip.assign(&mut region, idx + 1, *value_a, *value_b)?;
+                            // the returned cell is the lt outcome
+                            region.constrain_equal(lt_cell.cell(), check_cell.cell())?;
                         }
 
                         Ok(())
@@ -436,4 +549,4 @@ mod test {
         try_test_circuit_error!(vec![(1, 2), (3, 4), (5, 6)], vec![false, false, false]);
         try_test_circuit_error!(vec![(1, 1), (3, 4), (6, 6)], vec![true, false, true]);
     }
-}
\ No newline at end of file
+}
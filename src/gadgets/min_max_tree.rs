@@ -0,0 +1,270 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+};
+
+use crate::{
+    gadgets::cswap::{CswapChip, CswapConfig, CswapInstruction},
+    gadgets::{Gadget, SharedColumns},
+    table::U8Table,
+};
+
+/// Instruction that the MinMaxTree chip needs to implement.
+pub trait MinMaxTreeInstruction<F: FieldExt> {
+    /// Reduce the values cells on the rows from offset, one row per
+    /// comparison, returning the cells of (min, max).
+    #[allow(clippy::type_complexity)]
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+}
+
+/// Config for the MinMaxTree chip.
+#[derive(Clone, Copy, Debug)]
+pub struct MinMaxTreeConfig<F, const N_BYTES: usize> {
+    /// The compare and swap of every comparison.
+    pub cswap: CswapConfig<F, N_BYTES>,
+    /// Denotes the rows holding a comparison.
+    pub q_enable: Selector,
+}
+
+/// Chip that finds both the min and the max of N values in about 1.5N
+/// comparisons, cheaper than sorting when only the extremes are needed. The
+/// values are compared and swapped in pairs, then the min is reduced over the
+/// lesser of every pair and the max over the greater, with an odd value out
+/// joining both.
+#[derive(Clone, Debug)]
+pub struct MinMaxTreeChip<F, const N_BYTES: usize> {
+    config: MinMaxTreeConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> MinMaxTreeChip<F, N_BYTES> {
+    /// Configures the MinMaxTree chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        lt: Column<Advice>,
+        diff: [Column<Advice>; N_BYTES],
+        u8_table: U8Table,
+    ) -> MinMaxTreeConfig<F, N_BYTES> {
+        let q_enable = meta.complex_selector();
+        let cswap = CswapChip::configure(
+            meta,
+            |meta| meta.query_selector(q_enable),
+            advice,
+            lt,
+            diff,
+            u8_table,
+        );
+
+        MinMaxTreeConfig { cswap, q_enable }
+    }
+
+    /// Constructs a MinMaxTree chip given a config.
+    pub fn construct(config: MinMaxTreeConfig<F, N_BYTES>) -> MinMaxTreeChip<F, N_BYTES> {
+        MinMaxTreeChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> MinMaxTreeInstruction<F> for MinMaxTreeChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        values: &[AssignedCell<F, F>],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        if values.is_empty() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let cswap_chip = CswapChip::construct(config.cswap);
+
+        let mut row = offset;
+        let mut cswap = |region: &mut Region<'_, F>, a: &AssignedCell<F, F>, b| {
+            config.q_enable.enable(region, row)?;
+            row += 1;
+            cswap_chip.assign(region, row - 1, a, b)
+        };
+
+        let mut mins = Vec::with_capacity(values.len() / 2 + 1);
+        let mut maxs = Vec::with_capacity(values.len() / 2 + 1);
+        for pair in values.chunks(2) {
+            match pair {
+                [a, b] => {
+                    let (min, max) = cswap(region, a, b)?;
+                    mins.push(min);
+                    maxs.push(max);
+                }
+                [odd] => {
+                    mins.push(odd.clone());
+                    maxs.push(odd.clone());
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        let mut min = mins[0].clone();
+        for candidate in &mins[1..] {
+            min = cswap(region, &min, candidate)?.0;
+        }
+        let mut max = maxs[0].clone();
+        for candidate in &maxs[1..] {
+            max = cswap(region, &max, candidate)?.1;
+        }
+
+        Ok((min, max))
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for MinMaxTreeChip<F, N_BYTES> {
+    type Config = MinMaxTreeConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for MinMaxTreeChip<F, N_BYTES> {
+    type Config = MinMaxTreeConfig<F, N_BYTES>;
+    type Params = ();
+    type Input = Vec<AssignedCell<F, F>>;
+    /// The cells of (min, max).
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let [lt] = columns.advice::<1>(4);
+        MinMaxTreeChip::configure(
+            meta,
+            columns.advice(0),
+            lt,
+            columns.advice(5),
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        values: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "min max tree gadget",
+            |mut region| MinMaxTreeChip::construct(*config).assign(&mut region, 0, &values),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MinMaxTreeChip, MinMaxTreeConfig, MinMaxTreeInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        min_max: MinMaxTreeConfig<F, 8>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let lt = meta.advice_column();
+            let diff = [(); 8].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            Self::Config {
+                witness,
+                instance,
+                min_max: MinMaxTreeChip::configure(meta, advice, lt, diff, u8_table),
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = MinMaxTreeChip::construct(config.min_max);
+            config.u8_table.load(&mut layouter)?;
+
+            let (min, max) = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let values = self
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| {
+                            region.assign_advice(
+                                || "value",
+                                config.witness,
+                                i,
+                                || Value::known(*value),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    chip.assign(&mut region, 0, &values)
+                },
+            )?;
+
+            layouter.constrain_instance(min.cell(), config.instance, 0)?;
+            layouter.constrain_instance(max.cell(), config.instance, 1)
+        }
+    }
+
+    fn try_min_max(values: Vec<u64>, min: u64, max: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values.into_iter().map(Fp::from).collect(),
+        };
+        let instance = vec![Fp::from(min), Fp::from(max)];
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn min_max_tree() {
+        // ok
+        assert_eq!(try_min_max(vec![7, 1, 9, 3, 5], 1, 9), Ok(()));
+        assert_eq!(try_min_max(vec![2, 8, 6, 4], 2, 8), Ok(()));
+        assert_eq!(try_min_max(vec![4], 4, 4), Ok(()));
+        // error
+        assert!(try_min_max(vec![7, 1, 9, 3, 5], 3, 9).is_err());
+        assert!(try_min_max(vec![7, 1, 9, 3, 5], 1, 7).is_err());
+    }
+}
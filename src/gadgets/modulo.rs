@@ -0,0 +1,392 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{expr_from_bytes, Expr},
+};
+
+/// Instruction that the Mod chip needs to implement.
+pub trait ModInstruction<F: FieldExt> {
+    /// Copy the value cell to the Mod chip's region, returning the cells of
+    /// (value / modulus, value % modulus).
+    #[allow(clippy::type_complexity)]
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+}
+
+/// Config for the Mod chip.
+#[derive(Clone, Copy, Debug)]
+pub struct ModConfig<F, const N_BYTES: usize> {
+    /// Denotes the value, quotient and remainder columns, where
+    /// value == quotient * modulus + remainder.
+    pub advice: [Column<Advice>; 3],
+    /// Denotes the bytes representation of the quotient.
+    /// Each byte is range checked by a lookup into the shared u8 table.
+    pub quotient_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the comparison remainder < modulus, which must be set. The
+    /// remainder itself is range checked to a byte.
+    pub lt: LtConfig<F, 1>,
+    /// The constant modulus, at most 256.
+    pub modulus: u64,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ModConfig<F, N_BYTES> {
+    /// Returns an expression that denotes value / modulus.
+    pub fn quotient(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[1], rotation.unwrap_or_else(Rotation::cur))
+    }
+
+    /// Returns an expression that denotes value % modulus.
+    pub fn remainder(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[2], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that reduces a value modulo a small constant modulus, e.g. for
+/// bucketing values into histogram bins. The quotient is range checked to
+/// N_BYTES bytes, so the value must be below modulus * 2**(8 * N_BYTES).
+#[derive(Clone, Debug)]
+pub struct ModChip<F, const N_BYTES: usize> {
+    config: ModConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ModChip<F, N_BYTES> {
+    /// Configures the Mod chip.
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + Copy,
+        advice: [Column<Advice>; 3],
+        quotient_bytes: [Column<Advice>; N_BYTES],
+        lt: Column<Advice>,
+        diff: [Column<Advice>; 1],
+        modulus: u64,
+        u8_table: U8Table,
+    ) -> ModConfig<F, N_BYTES> {
+        assert!(
+            (1..=256).contains(&modulus),
+            "the modulus must be in 1..=256"
+        );
+        assert!(N_BYTES < 31, "the value must fit in the field");
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let [value, quotient, remainder] = advice;
+
+        let lt = LtChip::configure(
+            meta,
+            q_enable,
+            |meta| meta.query_advice(remainder, Rotation::cur()),
+            |_| modulus.expr(),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        meta.create_gate("mod gate", |meta| {
+            let q_enable = q_enable(meta);
+            let [value, quotient, remainder] =
+                [value, quotient, remainder].map(|c| meta.query_advice(c, Rotation::cur()));
+            let quotient_bytes = quotient_bytes.map(|c| meta.query_advice(c, Rotation::cur()));
+
+            let check_quotient = quotient.clone() - expr_from_bytes(&quotient_bytes);
+            let check_mod = value - quotient * modulus.expr() - remainder;
+            let check_lt = lt.is_lt(meta, None) - 1.expr();
+
+            [check_quotient, check_mod, check_lt]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        u8_table.range_check(meta, q_enable, &quotient_bytes);
+        u8_table.range_check(meta, q_enable, &[remainder]);
+
+        ModConfig {
+            advice,
+            quotient_bytes,
+            lt,
+            modulus,
+        }
+    }
+
+    /// Constructs a Mod chip given a config.
+    pub fn construct(config: ModConfig<F, N_BYTES>) -> ModChip<F, N_BYTES> {
+        ModChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> ModInstruction<F> for ModChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+        let [value_column, quotient_column, remainder_column] = config.advice;
+
+        value.copy_advice(|| "mod chip: value", region, value_column, offset)?;
+
+        // divide the little-endian bytes from the most significant one down
+        let division = value.value().map(|value| {
+            let repr = value.to_repr();
+            let mut quotient = vec![0u8; repr.as_ref().len()];
+            let mut remainder = 0u64;
+            for (idx, byte) in repr.as_ref().iter().enumerate().rev() {
+                let current = (remainder << 8) + *byte as u64;
+                quotient[idx] = (current / config.modulus) as u8;
+                remainder = current % config.modulus;
+            }
+            (quotient, remainder)
+        });
+        let quotient = division.as_ref().map(|(quotient, _)| {
+            quotient.iter().rev().fold(F::zero(), |acc, byte| {
+                acc * F::from(256) + F::from(*byte as u64)
+            })
+        });
+        let remainder = division.as_ref().map(|(_, remainder)| *remainder);
+
+        let quotient_cell = region.assign_advice(
+            || "mod chip: quotient",
+            quotient_column,
+            offset,
+            || quotient,
+        )?;
+        let remainder_cell = region.assign_advice(
+            || "mod chip: remainder",
+            remainder_column,
+            offset,
+            || remainder.map(F::from),
+        )?;
+
+        for (idx, column) in config.quotient_bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("mod chip: quotient byte {}", idx),
+                *column,
+                offset,
+                || {
+                    division
+                        .as_ref()
+                        .map(|(quotient, _)| F::from(quotient[idx] as u64))
+                },
+            )?;
+        }
+
+        // the Lt chip takes the values themselves, which are only zero while
+        // the witnesses are unknown
+        let mut remainder_value = F::zero();
+        remainder.map(|remainder| remainder_value = F::from(remainder));
+        LtChip::construct(config.lt).assign(
+            region,
+            offset,
+            remainder_value,
+            F::from(config.modulus),
+        )?;
+
+        Ok((quotient_cell, remainder_cell))
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for ModChip<F, N_BYTES> {
+    type Config = ModConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for ModChip<F, N_BYTES> {
+    type Config = Gated<ModConfig<F, N_BYTES>, 0>;
+    type Params = u64;
+    type Input = AssignedCell<F, F>;
+    /// The cells of (value / modulus, value % modulus).
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        modulus: u64,
+    ) -> Self::Config {
+        let selector = meta.complex_selector();
+        let [lt] = columns.advice::<1>(3 + N_BYTES);
+        let chip = ModChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            columns.advice(0),
+            columns.advice(3),
+            lt,
+            columns.advice(4 + N_BYTES),
+            modulus,
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs: [],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        value: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "mod gadget",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                ModChip::construct(config.chip).assign(&mut region, 0, &value)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ModChip, ModConfig, ModInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        modulo: ModConfig<F, 4>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let quotient_bytes = [(); 4].map(|_| meta.advice_column());
+            let lt = meta.advice_column();
+            let diff = [meta.advice_column()];
+            let u8_table = U8Table::configure(meta);
+
+            let modulo = ModChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                advice,
+                quotient_bytes,
+                lt,
+                diff,
+                10,
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                witness,
+                instance,
+                modulo,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = ModChip::construct(config.modulo);
+            config.u8_table.load(&mut layouter)?;
+
+            let out_cells = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut out_cells = vec![];
+                    for (idx, value) in self.values.iter().enumerate() {
+                        let value = region.assign_advice(
+                            || "value",
+                            config.witness,
+                            idx,
+                            || Value::known(*value),
+                        )?;
+
+                        config.q_enable.enable(&mut region, idx)?;
+                        let (quotient, remainder) = chip.assign(&mut region, idx, &value)?;
+                        out_cells.extend([quotient, remainder]);
+                    }
+
+                    Ok(out_cells)
+                },
+            )?;
+
+            for (i, cell) in out_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_mod(values: Vec<u64>, outs: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values.into_iter().map(Fp::from).collect(),
+        };
+        let instance = outs.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn mod_ten() {
+        // ok
+        assert_eq!(
+            try_mod(vec![0, 7, 10, 1234567], vec![0, 0, 0, 7, 1, 0, 123456, 7]),
+            Ok(())
+        );
+        // error: the remainder is not reduced
+        assert!(try_mod(vec![17], vec![0, 17]).is_err());
+        // error: the quotient is wider than 4 bytes
+        assert!(try_mod(vec![1 << 40], vec![(1 << 40) / 10, (1 << 40) % 10]).is_err());
+    }
+}
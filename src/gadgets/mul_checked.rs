@@ -0,0 +1,367 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::{expr_from_bytes, pow_of_two, value_to_bytes},
+};
+
+/// Instruction that the MulChecked chip needs to implement.
+pub trait MulCheckedInstruction<F: FieldExt> {
+    /// Copy the a and b cells to the MulChecked chip's region, returning the
+    /// cells of the product and its (lo, hi) 64-bit limbs.
+    #[allow(clippy::type_complexity)]
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error>;
+}
+
+/// Config for the MulChecked chip.
+#[derive(Clone, Copy, Debug)]
+pub struct MulCheckedConfig {
+    /// Denotes the a, b, product, lo and hi columns, where
+    /// a * b == product == lo + hi * 2**64.
+    pub advice: [Column<Advice>; 5],
+    /// Denotes the bytes representations of a, b, lo and hi.
+    /// Each byte is range checked by a lookup into the shared u8 table.
+    pub bytes: [[Column<Advice>; 8]; 4],
+}
+
+impl MulCheckedConfig {
+    /// Returns an expression that denotes the product a * b.
+    pub fn product<F: FieldExt>(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[2], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that multiplies two 64-bit values into their 128-bit product. Both
+/// operands and both 64-bit limbs of the product are range checked, so the
+/// product provably doesn't wrap around the field modulus.
+#[derive(Clone, Debug)]
+pub struct MulCheckedChip<F> {
+    config: MulCheckedConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> MulCheckedChip<F> {
+    /// Configures the MulChecked chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        advice: [Column<Advice>; 5],
+        bytes: [[Column<Advice>; 8]; 4],
+        u8_table: U8Table,
+    ) -> MulCheckedConfig {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("mul checked gate", |meta| {
+            let q_enable = q_enable(meta);
+            let [a, b, product, lo, hi] = advice.map(|c| meta.query_advice(c, Rotation::cur()));
+            let [a_bytes, b_bytes, lo_bytes, hi_bytes] =
+                bytes.map(|bytes| bytes.map(|c| meta.query_advice(c, Rotation::cur())));
+
+            let check_a = a.clone() - expr_from_bytes(&a_bytes);
+            let check_b = b.clone() - expr_from_bytes(&b_bytes);
+            let check_lo = lo.clone() - expr_from_bytes(&lo_bytes);
+            let check_hi = hi.clone() - expr_from_bytes(&hi_bytes);
+            let check_limbs = product.clone() - lo - hi * pow_of_two::<F>(64);
+            let check_product = a * b - product;
+
+            [
+                check_a,
+                check_b,
+                check_lo,
+                check_hi,
+                check_limbs,
+                check_product,
+            ]
+            .into_iter()
+            .map(move |poly| q_enable.clone() * poly)
+        });
+
+        for bytes in bytes.iter() {
+            u8_table.range_check(meta, &q_enable, bytes);
+        }
+
+        MulCheckedConfig { advice, bytes }
+    }
+
+    /// Constructs a MulChecked chip given a config.
+    pub fn construct(config: MulCheckedConfig) -> MulCheckedChip<F> {
+        MulCheckedChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt> MulCheckedInstruction<F> for MulCheckedChip<F> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = self.config();
+        let [a_column, b_column, product_column, lo_column, hi_column] = config.advice;
+
+        a.copy_advice(|| "mul checked chip: a", region, a_column, offset)?;
+        b.copy_advice(|| "mul checked chip: b", region, b_column, offset)?;
+
+        let product = a.value().copied() * b.value().copied();
+        // operands wider than 64 bits keep their low bytes, which fails the gate
+        let low_bytes = value_to_bytes::<F, 8>;
+        let high_bytes = |value: &F| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&value.to_repr().as_ref()[8..16]);
+            bytes
+        };
+        let limbs = [
+            a.value().map(low_bytes),
+            b.value().map(low_bytes),
+            product.map(|product| low_bytes(&product)),
+            product.map(|product| high_bytes(&product)),
+        ];
+
+        let product_cell = region.assign_advice(
+            || "mul checked chip: product",
+            product_column,
+            offset,
+            || product,
+        )?;
+        let lo_cell = region.assign_advice(
+            || "mul checked chip: lo",
+            lo_column,
+            offset,
+            || limbs[2].map(|bytes| F::from(u64::from_le_bytes(bytes))),
+        )?;
+        let hi_cell = region.assign_advice(
+            || "mul checked chip: hi",
+            hi_column,
+            offset,
+            || limbs[3].map(|bytes| F::from(u64::from_le_bytes(bytes))),
+        )?;
+
+        for (name, (columns, limb)) in ["a", "b", "lo", "hi"]
+            .iter()
+            .zip(config.bytes.iter().zip(limbs))
+        {
+            for (idx, column) in columns.iter().enumerate() {
+                region.assign_advice(
+                    || format!("mul checked chip: {} byte {}", name, idx),
+                    *column,
+                    offset,
+                    || limb.map(|bytes| F::from(bytes[idx] as u64)),
+                )?;
+            }
+        }
+
+        Ok((product_cell, lo_cell, hi_cell))
+    }
+}
+
+impl<F: FieldExt> Chip<F> for MulCheckedChip<F> {
+    type Config = MulCheckedConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Gadget<F> for MulCheckedChip<F> {
+    type Config = Gated<MulCheckedConfig, 0>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    /// The cells of the product and its (lo, hi) 64-bit limbs.
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let chip = MulCheckedChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            columns.advice(0),
+            [5, 13, 21, 29].map(|first| columns.advice(first)),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs: [],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [a, b]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "mul checked gadget",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                MulCheckedChip::construct(config.chip).assign(&mut region, 0, &a, &b)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MulCheckedChip, MulCheckedConfig, MulCheckedInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        q_enable: Selector,
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        mul: MulCheckedConfig,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        pairs: Vec<(F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let bytes = [(); 4].map(|_| [(); 8].map(|_| meta.advice_column()));
+            let u8_table = U8Table::configure(meta);
+
+            let mul = MulCheckedChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                advice,
+                bytes,
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                witness,
+                instance,
+                mul,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = MulCheckedChip::construct(config.mul);
+            config.u8_table.load(&mut layouter)?;
+
+            let out_cells = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut out_cells = vec![];
+                    for (idx, (a, b)) in self.pairs.iter().enumerate() {
+                        // the operands of a pair are on the two rows from 2 * idx
+                        let offset = 2 * idx;
+                        let a = region.assign_advice(
+                            || "a",
+                            config.witness,
+                            offset,
+                            || Value::known(*a),
+                        )?;
+                        let b = region.assign_advice(
+                            || "b",
+                            config.witness,
+                            offset + 1,
+                            || Value::known(*b),
+                        )?;
+
+                        config.q_enable.enable(&mut region, offset)?;
+                        let (_, lo, hi) = chip.assign(&mut region, offset, &a, &b)?;
+                        out_cells.extend([lo, hi]);
+                    }
+
+                    Ok(out_cells)
+                },
+            )?;
+
+            for (i, cell) in out_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_mul(pairs: Vec<(u128, u128)>, outs: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            pairs: pairs
+                .into_iter()
+                .map(|(a, b)| (Fp::from_u128(a), Fp::from_u128(b)))
+                .collect(),
+        };
+        let instance = outs.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn mul_checked() {
+        let max = u64::MAX as u128;
+
+        // ok
+        assert_eq!(
+            try_mul(
+                vec![(3, 7), (max, 2), (max, max)],
+                // (2**64 - 1)**2 == 2**128 - 2**65 + 1
+                vec![21, 0, max as u64 - 1, 1, 1, max as u64 - 1]
+            ),
+            Ok(())
+        );
+        // error: wrong limbs
+        assert!(try_mul(vec![(max, 2)], vec![max as u64, 0]).is_err());
+        // error: operand wider than 64 bits
+        assert!(try_mul(vec![(max + 1, 1)], vec![0, 1]).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    gadgets::{Gadget, SharedColumns},
+    util::range_check_bits,
+};
+
+/// Instruction that the Num2Bits chip needs to implement.
+pub trait Num2BitsInstruction<F: FieldExt> {
+    /// Copies value into the Num2Bits chip's region and decomposes it,
+    /// returning the cells of its little-endian bits.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error>;
+}
+
+/// Config for the Num2Bits chip.
+#[derive(Clone, Copy, Debug)]
+pub struct Num2BitsConfig<const N_BITS: usize> {
+    /// Denotes the decomposed value.
+    pub value: Column<Advice>,
+    /// Denotes the little-endian bits of value, each constrained to be a
+    /// boolean.
+    pub bits: [Column<Advice>; N_BITS],
+    /// Denotes the rows holding a decomposition.
+    pub q_enable: Selector,
+}
+
+/// Chip that decomposes a value into N_BITS boolean cells, so that bitwise
+/// gadgets and sign extraction can consume them. The bits recompose to the
+/// value, so it also restricts the value to 0 <= value < 2**N_BITS.
+#[derive(Clone, Debug)]
+pub struct Num2BitsChip<F, const N_BITS: usize> {
+    config: Num2BitsConfig<N_BITS>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N_BITS: usize> Num2BitsChip<F, N_BITS> {
+    /// Configures the Num2Bits chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        bits: [Column<Advice>; N_BITS],
+    ) -> Num2BitsConfig<N_BITS> {
+        // the bits of a value below the modulus are unique
+        assert!(
+            N_BITS < F::NUM_BITS as usize,
+            "decomposed values must fit in the field"
+        );
+
+        meta.enable_equality(value);
+        for bit in bits {
+            meta.enable_equality(bit);
+        }
+        let q_enable = meta.selector();
+
+        meta.create_gate("num2bits gate", |meta| {
+            let q_enable = meta.query_selector(q_enable);
+            let value = meta.query_advice(value, Rotation::cur());
+            let bits = bits.map(|c| meta.query_advice(c, Rotation::cur()));
+
+            range_check_bits(value, &bits)
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        Num2BitsConfig {
+            value,
+            bits,
+            q_enable,
+        }
+    }
+
+    /// Constructs a Num2Bits chip given a config.
+    pub fn construct(config: Num2BitsConfig<N_BITS>) -> Num2BitsChip<F, N_BITS> {
+        Num2BitsChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt, const N_BITS: usize> Num2BitsInstruction<F> for Num2BitsChip<F, N_BITS> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let config = self.config();
+        config.q_enable.enable(region, offset)?;
+
+        value.copy_advice(|| "num2bits chip: value", region, config.value, offset)?;
+
+        // values out of range keep their low bits, which fails the gate
+        let repr = value.value().map(|value| value.to_repr());
+        let mut bit_cells = Vec::with_capacity(N_BITS);
+        for (idx, bit) in config.bits.iter().enumerate() {
+            let bit_value: Value<F> = repr
+                .as_ref()
+                .map(|repr| F::from(((repr.as_ref()[idx / 8] >> (idx % 8)) & 1) as u64));
+            bit_cells.push(region.assign_advice(
+                || format!("num2bits chip: bit {}", idx),
+                *bit,
+                offset,
+                || bit_value,
+            )?);
+        }
+
+        Ok(bit_cells)
+    }
+}
+
+impl<F: FieldExt, const N_BITS: usize> Chip<F> for Num2BitsChip<F, N_BITS> {
+    type Config = Num2BitsConfig<N_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BITS: usize> Gadget<F> for Num2BitsChip<F, N_BITS> {
+    type Config = Num2BitsConfig<N_BITS>;
+    type Params = ();
+    type Input = AssignedCell<F, F>;
+    /// The cells of the little-endian bits.
+    type Output = Vec<AssignedCell<F, F>>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let [value] = columns.advice::<1>(0);
+        Num2BitsChip::configure(meta, value, columns.advice(1))
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        value: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "num2bits gadget",
+            |mut region| Num2BitsChip::construct(*config).assign(&mut region, 0, &value),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Num2BitsChip, Num2BitsConfig, Num2BitsInstruction};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        num2bits: Num2BitsConfig<8>,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+            let value = meta.advice_column();
+            let bits = [(); 8].map(|_| meta.advice_column());
+
+            Self::Config {
+                input,
+                instance,
+                num2bits: Num2BitsChip::configure(meta, value, bits),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = Num2BitsChip::construct(config.num2bits);
+
+            let bits = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut bits = vec![];
+                    for (idx, value) in self.values.iter().enumerate() {
+                        let cell = region.assign_advice(
+                            || "input",
+                            config.input,
+                            idx,
+                            || Value::known(*value),
+                        )?;
+                        bits.extend(chip.assign(&mut region, idx, &cell)?);
+                    }
+                    Ok(bits)
+                },
+            )?;
+
+            // the bits of every value are exposed in order
+            for (i, bit) in bits.iter().enumerate() {
+                layouter.constrain_instance(bit.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_num2bits(values: Vec<u64>, bits: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values.into_iter().map(Fp::from).collect(),
+        };
+        let instance = bits.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(5, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn decompose_to_bits() {
+        let bits_of = |value: u64| (0..8).map(move |i| (value >> i) & 1);
+
+        // ok
+        assert_eq!(
+            try_num2bits(
+                vec![0, 5, 255],
+                bits_of(0).chain(bits_of(5)).chain(bits_of(255)).collect()
+            ),
+            Ok(())
+        );
+        // error: wrong bits
+        assert!(try_num2bits(vec![5], bits_of(6).collect()).is_err());
+        // error: out of range
+        assert!(try_num2bits(vec![256], bits_of(0).collect()).is_err());
+    }
+}
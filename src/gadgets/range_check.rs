@@ -0,0 +1,291 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    gadgets::{Gadget, SharedColumns},
+    table::U8Table,
+    util::expr_from_bytes,
+};
+
+/// The bit widths the RangeCheck chip supports, one mode each.
+pub const RANGE_CHECK_BITS: [usize; 4] = [8, 16, 32, 64];
+
+/// Instruction that the RangeCheck chip needs to implement.
+pub trait RangeCheckInstruction<F: FieldExt> {
+    /// Copies value into the RangeCheck chip's region and restricts it to
+    /// 0 <= value < 2**num_bits, returning the copied cell. num_bits must be
+    /// one of RANGE_CHECK_BITS.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the RangeCheck chip.
+#[derive(Clone, Copy, Debug)]
+pub struct RangeCheckConfig {
+    /// Denotes the range checked value.
+    pub value: Column<Advice>,
+    /// Denotes the little-endian bytes of value. Each byte is range checked
+    /// by a lookup into the shared u8 table, but only as many bytes as the
+    /// mode of the row uses.
+    pub bytes: [Column<Advice>; 8],
+    /// Denotes the mode of the row, one selector per RANGE_CHECK_BITS.
+    pub q_modes: [Selector; 4],
+}
+
+/// Chip that restricts a value to 8, 16, 32 or 64 bits by decomposing it
+/// into bytes looked up in the u8 table. It replaces util::range_check,
+/// whose degree grows with the range, for anything but tiny ranges.
+#[derive(Clone, Debug)]
+pub struct RangeCheckChip<F> {
+    config: RangeCheckConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> RangeCheckChip<F> {
+    /// Configures the RangeCheck chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        bytes: [Column<Advice>; 8],
+        u8_table: U8Table,
+    ) -> RangeCheckConfig {
+        meta.enable_equality(value);
+        let q_modes = [(); 4].map(|_| meta.complex_selector());
+
+        meta.create_gate("range check gate", |meta| {
+            let value = meta.query_advice(value, Rotation::cur());
+            let bytes = bytes.map(|c| meta.query_advice(c, Rotation::cur()));
+
+            q_modes
+                .iter()
+                .zip(RANGE_CHECK_BITS)
+                .map(|(q_mode, num_bits)| {
+                    let q_mode = meta.query_selector(*q_mode);
+                    q_mode * (value.clone() - expr_from_bytes(&bytes[..num_bits / 8]))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // a byte is checked by every mode wide enough to use it
+        for (idx, byte) in bytes.iter().enumerate() {
+            u8_table.range_check(
+                meta,
+                |meta| {
+                    q_modes
+                        .iter()
+                        .zip(RANGE_CHECK_BITS)
+                        .filter(|(_, num_bits)| idx < num_bits / 8)
+                        .fold(Expression::Constant(F::zero()), |acc, (q_mode, _)| {
+                            acc + meta.query_selector(*q_mode)
+                        })
+                },
+                &[*byte],
+            );
+        }
+
+        RangeCheckConfig {
+            value,
+            bytes,
+            q_modes,
+        }
+    }
+
+    /// Constructs a RangeCheck chip given a config.
+    pub fn construct(config: RangeCheckConfig) -> RangeCheckChip<F> {
+        RangeCheckChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt> RangeCheckInstruction<F> for RangeCheckChip<F> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        let mode = RANGE_CHECK_BITS
+            .iter()
+            .position(|bits| *bits == num_bits)
+            .ok_or(Error::Synthesis)?;
+        config.q_modes[mode].enable(region, offset)?;
+
+        let cell = value.copy_advice(|| "range check chip: value", region, config.value, offset)?;
+
+        // values out of range keep their low bytes, which fails the gate
+        let bytes = value.value().map(|value| value.to_repr());
+        for (idx, byte) in config.bytes.iter().enumerate() {
+            region.assign_advice(
+                || format!("range check chip: byte {}", idx),
+                *byte,
+                offset,
+                || {
+                    bytes
+                        .as_ref()
+                        .map(|bytes| F::from(bytes.as_ref()[idx] as u64))
+                },
+            )?;
+        }
+
+        Ok(cell)
+    }
+}
+
+impl<F: FieldExt> Chip<F> for RangeCheckChip<F> {
+    type Config = RangeCheckConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Gadget<F> for RangeCheckChip<F> {
+    type Config = RangeCheckConfig;
+    type Params = ();
+    type Input = (AssignedCell<F, F>, usize);
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let [value] = columns.advice::<1>(0);
+        RangeCheckChip::configure(meta, value, columns.advice(1), columns.u8_table)
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        (value, num_bits): Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "range check gadget",
+            |mut region| {
+                RangeCheckChip::construct(*config).assign(&mut region, 0, &value, num_bits)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RangeCheckChip, RangeCheckConfig, RangeCheckInstruction};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        input: Column<Advice>,
+        range_check: RangeCheckConfig,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        // each value is range checked to its number of bits
+        values: Vec<(F, usize)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            meta.enable_equality(input);
+            let value = meta.advice_column();
+            let bytes = [(); 8].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+
+            Self::Config {
+                input,
+                range_check: RangeCheckChip::configure(meta, value, bytes, u8_table),
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = RangeCheckChip::construct(config.range_check);
+            config.u8_table.load(&mut layouter)?;
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (idx, (value, num_bits)) in self.values.iter().enumerate() {
+                        let cell = region.assign_advice(
+                            || "input",
+                            config.input,
+                            idx,
+                            || Value::known(*value),
+                        )?;
+                        chip.assign(&mut region, idx, &cell, *num_bits)?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn try_range_check(values: Vec<(u64, usize)>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values
+                .into_iter()
+                .map(|(value, num_bits)| (Fp::from(value), num_bits))
+                .collect(),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn value_in_range() {
+        // ok
+        assert_eq!(
+            try_range_check(vec![
+                (0, 8),
+                (255, 8),
+                (256, 16),
+                (65535, 16),
+                (65536, 32),
+                (u32::MAX as u64, 32),
+                (u64::MAX, 64),
+            ]),
+            Ok(())
+        );
+        // error
+        assert!(try_range_check(vec![(256, 8)]).is_err());
+        assert!(try_range_check(vec![(65536, 16)]).is_err());
+        assert!(try_range_check(vec![(1 << 32, 32)]).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    constraint_builder::BaseConstraintBuilder,
+    gadgets::{Gadget, Gated, SharedColumns},
+    util::select,
+};
+use std::marker::PhantomData;
+
+/// Instruction that the Select chip needs to implement.
+pub trait SelectInstruction<F: FieldExt> {
+    /// Copy the cond, a and b cells to the Select chip's region, returning
+    /// the cell of the selected value.
+    fn select(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        cond: &AssignedCell<F, F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the Select chip.
+#[derive(Clone, Copy, Debug)]
+pub struct SelectConfig {
+    /// Denotes the cond, a, b and out columns, where out == a if cond == 1,
+    /// otherwise out == b.
+    pub advice: [Column<Advice>; 4],
+}
+
+impl SelectConfig {
+    /// Returns an expression that denotes the selected value.
+    pub fn out<F: FieldExt>(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Option<Rotation>,
+    ) -> Expression<F> {
+        meta.query_advice(self.advice[3], rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that selects out = cond * a + (1 - cond) * b for a boolean cond.
+#[derive(Clone, Debug)]
+pub struct SelectChip<F> {
+    config: SelectConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> SelectChip<F> {
+    /// Configures the Select chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        advice: [Column<Advice>; 4],
+    ) -> SelectConfig {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("select gate", |meta| {
+            let q_enable = q_enable(meta);
+            let [cond, a, b, out] = advice.map(|c| meta.query_advice(c, Rotation::cur()));
+
+            let mut cb = BaseConstraintBuilder::new(3);
+            cb.require_equal("out is a or b", out, select::expr(cond.clone(), a, b));
+            cb.require_boolean("cond is boolean", cond);
+            cb.gate(q_enable)
+        });
+
+        SelectConfig { advice }
+    }
+
+    /// Constructs a Select chip given a config.
+    pub fn construct(config: SelectConfig) -> SelectChip<F> {
+        SelectChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt> SelectInstruction<F> for SelectChip<F> {
+    fn select(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        cond: &AssignedCell<F, F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [cond_column, a_column, b_column, out_column] = self.config().advice;
+
+        cond.copy_advice(|| "select chip: cond", region, cond_column, offset)?;
+        a.copy_advice(|| "select chip: a", region, a_column, offset)?;
+        b.copy_advice(|| "select chip: b", region, b_column, offset)?;
+
+        let out = cond
+            .value()
+            .zip(a.value())
+            .zip(b.value())
+            .map(|((cond, a), b)| *cond * a + (F::one() - cond) * b);
+        region.assign_advice(|| "select chip: out", out_column, offset, || out)
+    }
+}
+
+impl<F: FieldExt> Chip<F> for SelectChip<F> {
+    type Config = SelectConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> Gadget<F> for SelectChip<F> {
+    type Config = Gated<SelectConfig, 0>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 3];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.selector();
+        let chip = SelectChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            columns.advice(0),
+        );
+        Gated {
+            selector,
+            inputs: [],
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [cond, a, b]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "select gadget",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                SelectChip::construct(config.chip).select(&mut region, 0, &cond, &a, &b)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SelectChip, SelectConfig, SelectInstruction};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        q_enable: Selector,
+        witness: Column<Advice>,
+        instance: Column<Instance>,
+        select: SelectConfig,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        // (cond, a, b)
+        rows: Vec<(F, F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.selector();
+            let witness = meta.advice_column();
+            meta.enable_equality(witness);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let advice = [(); 4].map(|_| meta.advice_column());
+
+            let select = SelectChip::configure(meta, |meta| meta.query_selector(q_enable), advice);
+
+            Self::Config {
+                q_enable,
+                witness,
+                instance,
+                select,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let chip = SelectChip::construct(config.select);
+
+            let out_cells = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let mut out_cells = vec![];
+                    for (idx, (cond, a, b)) in self.rows.iter().enumerate() {
+                        // the operands of a row are on the three rows from 3 * idx
+                        let offset = 3 * idx;
+                        let [cond, a, b] = [(0, cond), (1, a), (2, b)].map(|(i, value)| {
+                            region.assign_advice(
+                                || "witness",
+                                config.witness,
+                                offset + i,
+                                || Value::known(*value),
+                            )
+                        });
+
+                        config.q_enable.enable(&mut region, offset)?;
+                        out_cells.push(chip.select(&mut region, offset, &cond?, &a?, &b?)?);
+                    }
+
+                    Ok(out_cells)
+                },
+            )?;
+
+            for (i, cell) in out_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_select(rows: Vec<(u64, u64, u64)>, outs: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            rows: rows
+                .into_iter()
+                .map(|(cond, a, b)| (Fp::from(cond), Fp::from(a), Fp::from(b)))
+                .collect(),
+        };
+        let instance = outs.into_iter().map(Fp::from).collect();
+        let prover = MockProver::<Fp>::run(5, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn select() {
+        // ok
+        assert_eq!(
+            try_select(vec![(1, 3, 7), (0, 3, 7), (1, 5, 5)], vec![3, 7, 5]),
+            Ok(())
+        );
+        // error
+        assert!(try_select(vec![(1, 3, 7)], vec![7]).is_err());
+        assert!(try_select(vec![(0, 3, 7)], vec![3]).is_err());
+        // error: cond is not a boolean
+        assert!(try_select(vec![(2, 7, 3)], vec![11]).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    gadgets::num_to_bits::{Num2BitsChip, Num2BitsConfig, Num2BitsInstruction},
+    gadgets::{Gadget, SharedColumns},
+    util::{bool_check, pow_of_two, Expr},
+};
+
+/// Shift or rotation of an N_BITS-bit value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShiftOp {
+    /// Shift left, dropping the bits shifted past N_BITS.
+    Shl,
+    /// Shift right, dropping the bits shifted below 0.
+    Shr,
+    /// Rotate left within N_BITS.
+    Rotl,
+    /// Rotate right within N_BITS.
+    Rotr,
+}
+
+impl ShiftOp {
+    /// Returns the position bit i moves to when shifting n_bits bits by
+    /// amount, or None if the bit is dropped.
+    pub fn destination(&self, i: usize, amount: usize, n_bits: usize) -> Option<usize> {
+        match self {
+            ShiftOp::Shl => Some(i + amount).filter(|dest| *dest < n_bits),
+            ShiftOp::Shr => i.checked_sub(amount),
+            ShiftOp::Rotl => Some((i + amount) % n_bits),
+            ShiftOp::Rotr => Some((i + n_bits - amount % n_bits) % n_bits),
+        }
+    }
+}
+
+/// Instruction that the Shift chip needs to implement.
+pub trait ShiftInstruction<F: FieldExt> {
+    /// Copy the value and amount cells to the Shift chip's region, returning
+    /// the cell of the shifted value. amount must be below N_BITS.
+    fn shift(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+        amount: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error>;
+
+    /// Copy the value cell to the Shift chip's region and shift it by a
+    /// constant amount, returning the cell of the shifted value.
+    fn shift_by_constant(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+        amount: usize,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the Shift chip.
+#[derive(Clone, Copy, Debug)]
+pub struct ShiftConfig<const N_BITS: usize> {
+    /// The operation of the chip.
+    pub op: ShiftOp,
+    /// Denotes the amount and out columns, where out is value shifted by
+    /// amount.
+    pub advice: [Column<Advice>; 2],
+    /// Denotes the one-hot flags of the amount, flags[k] == 1 iff
+    /// amount == k.
+    pub flags: [Column<Advice>; N_BITS],
+    /// The bit decomposition of the value.
+    pub num2bits: Num2BitsConfig<N_BITS>,
+    /// Denotes the constant amounts of shift_by_constant.
+    pub constant: Column<Fixed>,
+    /// Denotes the rows holding a shift.
+    pub q_enable: Selector,
+}
+
+/// Chip that shifts or rotates an N_BITS-bit value by an amount below
+/// N_BITS. The value is decomposed into bits, the amount into one-hot flags,
+/// and out is the sum over every amount k of flags[k] times the bits
+/// recomposed at their destinations for k.
+#[derive(Clone, Debug)]
+pub struct ShiftChip<F, const N_BITS: usize> {
+    config: ShiftConfig<N_BITS>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N_BITS: usize> ShiftChip<F, N_BITS> {
+    /// Configures the Shift chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        op: ShiftOp,
+        advice: [Column<Advice>; 2],
+        num2bits: Num2BitsConfig<N_BITS>,
+        constant: Column<Fixed>,
+    ) -> ShiftConfig<N_BITS> {
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        meta.enable_constant(constant);
+        let flags = [(); N_BITS].map(|_| meta.advice_column());
+        let q_enable = meta.selector();
+
+        meta.create_gate("shift gate", |meta| {
+            let q_enable = meta.query_selector(q_enable);
+            let [amount, out] = advice.map(|c| meta.query_advice(c, Rotation::cur()));
+            let flags = flags.map(|c| meta.query_advice(c, Rotation::cur()));
+            let bits = num2bits.bits.map(|c| meta.query_advice(c, Rotation::cur()));
+
+            let mut check_one_hot = -1.expr();
+            let mut check_amount = amount;
+            let mut check_out = out;
+            for (k, flag) in flags.iter().enumerate() {
+                check_one_hot = check_one_hot + flag.clone();
+                check_amount = check_amount - flag.clone() * k.expr();
+                let shifted = bits.iter().enumerate().fold(0.expr(), |acc, (i, bit)| {
+                    match op.destination(i, k, N_BITS) {
+                        Some(dest) => acc + bit.clone() * pow_of_two::<F>(dest),
+                        None => acc,
+                    }
+                });
+                check_out = check_out - flag.clone() * shifted;
+            }
+
+            [check_one_hot, check_amount, check_out]
+                .into_iter()
+                .chain(flags.into_iter().map(bool_check))
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        ShiftConfig {
+            op,
+            advice,
+            flags,
+            num2bits,
+            constant,
+            q_enable,
+        }
+    }
+
+    /// Constructs a Shift chip given a config.
+    pub fn construct(config: ShiftConfig<N_BITS>) -> ShiftChip<F, N_BITS> {
+        ShiftChip {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    // assigns the bits, flags and out of value shifted by the amount cell
+    fn assign_shift(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+        amount: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+        config.q_enable.enable(region, offset)?;
+
+        let bit_cells =
+            Num2BitsChip::<F, N_BITS>::construct(config.num2bits).assign(region, offset, value)?;
+
+        // an amount of N_BITS or more sets no flag, which fails the gate
+        let amount = amount.value().map(|amount| {
+            let repr = amount.to_repr();
+            let bytes = repr.as_ref();
+            if bytes[8..].iter().any(|byte| *byte != 0) {
+                usize::MAX
+            } else {
+                u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize
+            }
+        });
+        for (k, flag) in config.flags.iter().enumerate() {
+            region.assign_advice(
+                || format!("shift chip: flag {}", k),
+                *flag,
+                offset,
+                || amount.map(|amount| F::from((amount == k) as u64)),
+            )?;
+        }
+
+        let bits: Value<Vec<F>> = bit_cells.iter().map(|cell| cell.value().copied()).collect();
+        let out = bits.zip(amount).map(|(bits, amount)| {
+            if amount >= N_BITS {
+                return F::zero();
+            }
+            bits.iter()
+                .enumerate()
+                .filter(|(_, bit)| **bit == F::one())
+                .filter_map(|(i, _)| self.config.op.destination(i, amount, N_BITS))
+                .fold(F::zero(), |acc, dest| acc + pow_of_two::<F>(dest))
+        });
+        region.assign_advice(|| "shift chip: out", config.advice[1], offset, || out)
+    }
+}
+
+impl<F: FieldExt, const N_BITS: usize> ShiftInstruction<F> for ShiftChip<F, N_BITS> {
+    fn shift(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+        amount: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let amount = amount.copy_advice(
+            || "shift chip: amount",
+            region,
+            self.config.advice[0],
+            offset,
+        )?;
+        self.assign_shift(region, offset, value, &amount)
+    }
+
+    fn shift_by_constant(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: &AssignedCell<F, F>,
+        amount: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if amount >= N_BITS {
+            return Err(Error::Synthesis);
+        }
+        let amount = region.assign_advice_from_constant(
+            || "shift chip: amount",
+            self.config.advice[0],
+            offset,
+            F::from(amount as u64),
+        )?;
+        self.assign_shift(region, offset, value, &amount)
+    }
+}
+
+impl<F: FieldExt, const N_BITS: usize> Chip<F> for ShiftChip<F, N_BITS> {
+    type Config = ShiftConfig<N_BITS>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BITS: usize> Gadget<F> for ShiftChip<F, N_BITS> {
+    type Config = ShiftConfig<N_BITS>;
+    type Params = ShiftOp;
+    type Input = [AssignedCell<F, F>; 2];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        op: ShiftOp,
+    ) -> Self::Config {
+        let [value] = columns.advice::<1>(2);
+        let num2bits = Num2BitsChip::configure(meta, value, columns.advice(3));
+        ShiftChip::configure(meta, op, columns.advice(0), num2bits, columns.constants)
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [value, amount]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "shift gadget",
+            |mut region| ShiftChip::construct(*config).shift(&mut region, 0, &value, &amount),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ShiftChip, ShiftConfig, ShiftInstruction, ShiftOp};
+    use crate::gadgets::num_to_bits::Num2BitsChip;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        shift: [ShiftConfig<8>; 4],
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        value: F,
+        // the amount is witnessed if set, otherwise constant
+        amount: Option<F>,
+        constant_amount: usize,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                constant_amount: self.constant_amount,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+            let constant = meta.fixed_column();
+
+            let shift = [ShiftOp::Shl, ShiftOp::Shr, ShiftOp::Rotl, ShiftOp::Rotr].map(|op| {
+                let advice = [(); 2].map(|_| meta.advice_column());
+                let value = meta.advice_column();
+                let bits = [(); 8].map(|_| meta.advice_column());
+                let num2bits = Num2BitsChip::configure(meta, value, bits);
+                ShiftChip::configure(meta, op, advice, num2bits, constant)
+            });
+
+            Self::Config {
+                input,
+                instance,
+                shift,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let outs = layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    let value = region.assign_advice(
+                        || "value",
+                        config.input,
+                        0,
+                        || Value::known(self.value),
+                    )?;
+                    let amount = self
+                        .amount
+                        .map(|amount| {
+                            region.assign_advice(
+                                || "amount",
+                                config.input,
+                                1,
+                                || Value::known(amount),
+                            )
+                        })
+                        .transpose()?;
+
+                    config
+                        .shift
+                        .iter()
+                        .map(|shift| {
+                            let chip = ShiftChip::construct(*shift);
+                            match &amount {
+                                Some(amount) => chip.shift(&mut region, 0, &value, amount),
+                                None => chip.shift_by_constant(
+                                    &mut region,
+                                    0,
+                                    &value,
+                                    self.constant_amount,
+                                ),
+                            }
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                },
+            )?;
+
+            // the shl, shr, rotl and rotr outcomes are exposed in order
+            for (i, out) in outs.iter().enumerate() {
+                layouter.constrain_instance(out.cell(), config.instance, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_shift(
+        value: u64,
+        amount: Option<u64>,
+        constant_amount: usize,
+        outs: [u64; 4],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            value: Fp::from(value),
+            amount: amount.map(Fp::from),
+            constant_amount,
+        };
+        let instance = outs.map(Fp::from).to_vec();
+        let prover = MockProver::<Fp>::run(5, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn shift_and_rotate() {
+        // ok
+        assert_eq!(
+            try_shift(
+                0b1001_0110,
+                Some(3),
+                0,
+                [0b1011_0000, 0b0001_0010, 0b1011_0100, 0b1101_0010]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            try_shift(
+                0b1001_0110,
+                None,
+                3,
+                [0b1011_0000, 0b0001_0010, 0b1011_0100, 0b1101_0010]
+            ),
+            Ok(())
+        );
+        assert_eq!(try_shift(0b1001_0110, Some(0), 0, [0b1001_0110; 4]), Ok(()));
+        // error: wrong outcome
+        assert!(try_shift(
+            0b1001_0110,
+            Some(3),
+            0,
+            [0b1011_0000, 0b0001_0011, 0b1011_0100, 0b1101_0010]
+        )
+        .is_err());
+        // error: amount out of range
+        assert!(try_shift(0b1001_0110, Some(8), 0, [0; 4]).is_err());
+        // error: value wider than 8 bits
+        assert!(try_shift(0x100, Some(1), 0, [0; 4]).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+mod builder;
+mod sort_n;
+
+pub mod chunked;
+pub mod commit;
+pub mod dedup;
+pub mod lex;
+pub mod membership;
+pub mod merge;
+pub mod min_max;
+pub mod network;
+pub mod pairs;
+pub mod range_count;
+pub mod select_k;
+pub mod stable;
+pub mod top_k;
+pub mod word256;
+
+pub use builder::{SortCircuitBuilder, SortInstanceLayout};
+pub use sort_n::{
+    SortBatchCircuit, SortCells, SortNChip, SortNCircuit, SortNConfig, SortNParams, SortOptions,
+    SortOrder,
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Region},
+    plonk::{Advice, Column, Error, Instance},
+};
+
+/// Where a sort chip reads its inputs from: the instance column, the i-th
+/// input being on row i, as in the circuits of the chips, or the cells of
+/// other gadgets when the chip is assigned as a `Gadget`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SortInputs<'a, F: FieldExt> {
+    Instance(Column<Instance>),
+    Cells(&'a [AssignedCell<F, F>]),
+}
+
+impl<F: FieldExt> SortInputs<'_, F> {
+    /// Assigns the i-th input to the column at the offset.
+    pub(crate) fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        i: usize,
+        column: Column<Advice>,
+        offset: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        match self {
+            SortInputs::Instance(instance) => region.assign_advice_from_instance(
+                || format!("instance({})", i),
+                *instance,
+                i,
+                column,
+                offset,
+            ),
+            SortInputs::Cells(cells) => {
+                cells[i].copy_advice(|| format!("input[{}]", i), region, column, offset)
+            }
+        }
+    }
+}
+
+/// Constrains the cells that a chip witnessed privately to equal the input
+/// cells of its gadget, if any. It is called in the region of the chip, as
+/// MockProver cannot locate failures when a region assigns no cells.
+pub(crate) fn constrain_inputs<F: FieldExt>(
+    region: &mut Region<'_, F>,
+    inputs: &[AssignedCell<F, F>],
+    cells: &[AssignedCell<F, F>],
+) -> Result<(), Error> {
+    for (input, cell) in inputs.iter().zip(cells) {
+        region.constrain_equal(input.cell(), cell.cell())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        min_max::{MinMaxChip, MinMaxConfig},
+        select_k::{SelectKChip, SelectKConfig},
+        SortNChip, SortNConfig, SortOptions, SortOrder,
+    };
+    use crate::gadgets::{Gadget, SharedColumns};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F: FieldExt> {
+        columns: SharedColumns,
+        sort: SortNConfig<F, 4, 1>,
+        min_max: MinMaxConfig<F, 4, 1>,
+        select_k: SelectKConfig<F, 4, 2, 1>,
+    }
+
+    // the min and max of the sorted values and the second smallest value,
+    // wiring the sort gadget to the min_max one and the inputs to select_k
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: [F; 4],
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let columns = SharedColumns::configure(meta, 11);
+            let options = SortOptions {
+                order: SortOrder::Ascending,
+                allow_duplicates: true,
+                expose_indices: false,
+                signed: false,
+            };
+            let sort = <SortNChip<F, 4, 1> as Gadget<F>>::configure(meta, &columns, options);
+            let min_max = <MinMaxChip<F, 4, 1> as Gadget<F>>::configure(meta, &columns, ());
+            let select_k = <SelectKChip<F, 4, 2, 1> as Gadget<F>>::configure(meta, &columns, ());
+
+            Self::Config {
+                columns,
+                sort,
+                min_max,
+                select_k,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.columns.load(&mut layouter)?;
+
+            let values = config.columns.witness(
+                layouter.namespace(|| "witness"),
+                self.values.map(Value::known),
+            )?;
+            let sorted = <SortNChip<F, 4, 1> as Gadget<F>>::assign(
+                &config.sort,
+                layouter.namespace(|| "sort"),
+                values.clone(),
+            )?;
+            let (min, max) = <MinMaxChip<F, 4, 1> as Gadget<F>>::assign(
+                &config.min_max,
+                layouter.namespace(|| "min max"),
+                sorted.outputs,
+            )?;
+            let selected = <SelectKChip<F, 4, 2, 1> as Gadget<F>>::assign(
+                &config.select_k,
+                layouter.namespace(|| "select k"),
+                values,
+            )?;
+
+            for (row, cell) in [min, max, selected].iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.columns.instance, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn try_sort(values: [u64; 4], expected: [u64; 3]) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            values: values.map(Fp::from),
+        };
+        let instance = expected.map(Fp::from).to_vec();
+        let prover = MockProver::<Fp>::run(10, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn composed_sort_gadgets() {
+        // ok
+        assert_eq!(try_sort([5, 2, 9, 2], [2, 9, 2]), Ok(()));
+        assert_eq!(try_sort([7, 1, 3, 4], [1, 7, 3]), Ok(()));
+        // error
+        assert!(try_sort([5, 2, 9, 2], [2, 9, 5]).is_err());
+        assert!(try_sort([7, 1, 3, 4], [3, 7, 3]).is_err());
+        assert!(try_sort([7, 1, 3, 4], [1, 4, 3]).is_err());
+    }
+}
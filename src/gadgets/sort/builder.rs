@@ -0,0 +1,298 @@
+use halo2_proofs::{arithmetic::FieldExt, plonk::Error};
+use std::ops::Range;
+
+use super::{SortNCircuit, SortNParams, SortOptions, SortOrder};
+
+/// Where the public values of a SortNCircuit are on its instance column: the
+/// inputs, unless private, followed by the sorted outputs, followed by the
+/// input index of every output if exposed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortInstanceLayout {
+    /// The rows of the inputs, none if they are private.
+    pub inputs: Option<Range<usize>>,
+    /// The rows of the sorted outputs.
+    pub outputs: Range<usize>,
+    /// The rows of the input indices of the outputs, if exposed.
+    pub indices: Option<Range<usize>>,
+}
+
+impl SortInstanceLayout {
+    /// Returns the layout of the circuit of the shape, exposing the indices
+    /// or not.
+    pub fn new(params: SortNParams, expose_indices: bool) -> Self {
+        let n = params.n;
+        let inputs = (!params.private_inputs).then_some(0..n);
+        let start = inputs.as_ref().map_or(0, |rows| rows.end);
+        let outputs = start..start + n;
+        let indices = expose_indices.then(|| outputs.end..outputs.end + n);
+        SortInstanceLayout {
+            inputs,
+            outputs,
+            indices,
+        }
+    }
+
+    /// Returns the number of rows of the instance column.
+    pub fn rows(&self) -> usize {
+        self.indices.as_ref().unwrap_or(&self.outputs).end
+    }
+}
+
+/// Builder of a SortNCircuit along with its instance layout, e.g.
+/// `SortCircuitBuilder::new().elements(32).bytes(16).private_inputs()`.
+///
+/// The shape of a circuit is fixed by its const generics, so the circuit is
+/// built as the type the caller names, and building fails if the type is not
+/// of the shape of the builder. This lets a caller dispatching over sizes
+/// build every one of them from the same builder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortCircuitBuilder {
+    params: SortNParams,
+    options: SortOptions,
+}
+
+impl Default for SortCircuitBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SortCircuitBuilder {
+    /// Returns a builder of the circuit sorting no elements in ascending
+    /// order, without duplicates, to be given the number of elements.
+    pub fn new() -> Self {
+        SortCircuitBuilder {
+            params: SortNParams {
+                n: 0,
+                n_bytes: 8,
+                private_inputs: false,
+            },
+            options: SortOptions {
+                order: SortOrder::Ascending,
+                allow_duplicates: false,
+                expose_indices: false,
+                signed: false,
+            },
+        }
+    }
+
+    /// Sets the number of elements sorted.
+    pub fn elements(mut self, n: usize) -> Self {
+        self.params.n = n;
+        self
+    }
+
+    /// Sets the number of bytes of every element, 8 by default.
+    pub fn bytes(mut self, n_bytes: usize) -> Self {
+        self.params.n_bytes = n_bytes;
+        self
+    }
+
+    /// Keeps the inputs private, so that only the sorted outputs are public.
+    pub fn private_inputs(mut self) -> Self {
+        self.params.private_inputs = true;
+        self
+    }
+
+    /// Allows duplicated elements.
+    pub fn allow_duplicates(mut self) -> Self {
+        self.options.allow_duplicates = true;
+        self
+    }
+
+    /// Sorts the elements in descending order.
+    pub fn descending(mut self) -> Self {
+        self.options.order = SortOrder::Descending;
+        self
+    }
+
+    /// Exposes the input index of every sorted output.
+    pub fn expose_indices(mut self) -> Self {
+        self.options.expose_indices = true;
+        self
+    }
+
+    /// Compares the elements as two's complement integers.
+    pub fn signed(mut self) -> Self {
+        self.options.signed = true;
+        self
+    }
+
+    /// Returns the shape of the circuit.
+    pub fn params(&self) -> SortNParams {
+        self.params
+    }
+
+    /// Returns the options of the circuit.
+    pub fn options(&self) -> SortOptions {
+        self.options
+    }
+
+    /// Returns the instance layout of the circuit.
+    pub fn layout(&self) -> SortInstanceLayout {
+        SortInstanceLayout::new(self.params, self.options.expose_indices)
+    }
+
+    /// Builds the circuit without witnesses, e.g. for its keys.
+    #[allow(clippy::type_complexity)]
+    pub fn build<
+        F: FieldExt,
+        const N: usize,
+        const N_BYTES: usize,
+        const ALLOW_DUPLICATES: bool,
+        const DESCENDING: bool,
+        const EXPOSE_INDICES: bool,
+        const PRIVATE_INPUTS: bool,
+        const SIGNED: bool,
+    >(
+        &self,
+    ) -> Result<
+        (
+            SortNCircuit<
+                F,
+                N,
+                N_BYTES,
+                ALLOW_DUPLICATES,
+                DESCENDING,
+                EXPOSE_INDICES,
+                PRIVATE_INPUTS,
+                SIGNED,
+            >,
+            SortInstanceLayout,
+        ),
+        Error,
+    > {
+        self.build_with(&[F::zero(); N])
+    }
+
+    /// Builds the circuit sorting the values, failing if the circuit is not
+    /// of the shape of the builder or if there are not N values. Every const
+    /// generic is checked: N, N_BYTES and PRIVATE_INPUTS against the params,
+    /// the others against the options.
+    #[allow(clippy::type_complexity)]
+    pub fn build_with<
+        F: FieldExt,
+        const N: usize,
+        const N_BYTES: usize,
+        const ALLOW_DUPLICATES: bool,
+        const DESCENDING: bool,
+        const EXPOSE_INDICES: bool,
+        const PRIVATE_INPUTS: bool,
+        const SIGNED: bool,
+    >(
+        &self,
+        values: &[F],
+    ) -> Result<
+        (
+            SortNCircuit<
+                F,
+                N,
+                N_BYTES,
+                ALLOW_DUPLICATES,
+                DESCENDING,
+                EXPOSE_INDICES,
+                PRIVATE_INPUTS,
+                SIGNED,
+            >,
+            SortInstanceLayout,
+        ),
+        Error,
+    > {
+        let circuit = SortNCircuit::with_options(self.params, self.options, values)?;
+        Ok((circuit, self.layout()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SortCircuitBuilder, SortInstanceLayout};
+    use crate::{gadgets::sort::SortNCircuit, testing};
+    use halo2_proofs::pasta::Fp;
+
+    #[test]
+    fn sort_layout() {
+        let builder = SortCircuitBuilder::new().elements(4);
+        assert_eq!(
+            builder.layout(),
+            SortInstanceLayout {
+                inputs: Some(0..4),
+                outputs: 4..8,
+                indices: None,
+            }
+        );
+        assert_eq!(builder.layout().rows(), 8);
+
+        let layout = builder.private_inputs().expose_indices().layout();
+        assert_eq!(
+            layout,
+            SortInstanceLayout {
+                inputs: None,
+                outputs: 0..4,
+                indices: Some(4..8),
+            }
+        );
+        assert_eq!(
+            layout,
+            SortNCircuit::<Fp, 4, 8, false, false, true, true>::layout()
+        );
+    }
+
+    #[test]
+    fn sort_builder() {
+        let values = [3, 1, 4, 2].map(Fp::from);
+        let builder = SortCircuitBuilder::new()
+            .elements(4)
+            .bytes(16)
+            .private_inputs();
+
+        // ok
+        let (circuit, layout): (SortNCircuit<Fp, 4, 16, false, false, false, true>, _) =
+            builder.build_with(&values).unwrap();
+        let instance = circuit.instance();
+        assert_eq!(instance.len(), layout.rows());
+        assert_eq!(instance, [1, 2, 3, 4].map(Fp::from));
+        assert_eq!(testing::verify(&circuit, vec![instance]), Ok(()));
+
+        let (circuit, _): (SortNCircuit<Fp, 4, 16, true, true, true>, _) =
+            SortCircuitBuilder::new()
+                .elements(4)
+                .bytes(16)
+                .allow_duplicates()
+                .descending()
+                .expose_indices()
+                .build_with(&[3, 1, 3, 2].map(Fp::from))
+                .unwrap();
+        let instance = circuit.instance();
+        assert_eq!(instance, [3, 1, 3, 2, 3, 3, 2, 1, 0, 2, 3, 1].map(Fp::from));
+        assert_eq!(testing::verify(&circuit, vec![instance]), Ok(()));
+
+        // error: the circuit is not of the shape of the builder, with N,
+        // N_BYTES, ALLOW_DUPLICATES, DESCENDING, EXPOSE_INDICES,
+        // PRIVATE_INPUTS and SIGNED off in turn
+        assert!(builder
+            .build::<Fp, 8, 16, false, false, false, true, false>()
+            .is_err());
+        assert!(builder
+            .build::<Fp, 4, 8, false, false, false, true, false>()
+            .is_err());
+        assert!(builder
+            .build::<Fp, 4, 16, true, false, false, true, false>()
+            .is_err());
+        assert!(builder
+            .build::<Fp, 4, 16, false, true, false, true, false>()
+            .is_err());
+        assert!(builder
+            .build::<Fp, 4, 16, false, false, true, true, false>()
+            .is_err());
+        assert!(builder
+            .build::<Fp, 4, 16, false, false, false, false, false>()
+            .is_err());
+        assert!(builder
+            .build::<Fp, 4, 16, false, false, false, true, true>()
+            .is_err());
+        // error: the number of values is not the number of elements
+        assert!(builder
+            .build_with::<Fp, 4, 16, false, false, false, true, false>(&values[..3])
+            .is_err());
+    }
+}
@@ -0,0 +1,386 @@
+use super::{
+    network::{merge_comparators, CompareSwapChip, CompareSwapConfig},
+    SortNChip, SortNConfig, SortOrder,
+};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for sorting any multiple of CHUNK elements, each compared as an
+/// N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct ChunkedSortConfig<F: FieldExt, const CHUNK: usize, const N_BYTES: usize> {
+    /// Config of the chip sorting every chunk.
+    pub chunk_config: SortNConfig<F, CHUNK, N_BYTES>,
+    /// Config of the compare-and-swap rows merging the sorted chunks.
+    pub merge_config: CompareSwapConfig<F, N_BYTES>,
+    // the merged outputs, one per row
+    pub merged: Column<Advice>,
+
+    // adjacent comparison of the merged outputs
+    merge_selector: Selector,
+    merge_lt_config: LtConfig<F, N_BYTES>,
+}
+
+/// Chip that sorts a large array by sorting chunks of CHUNK elements with a
+/// SortNChip and merging the sorted chunks into a single column. The chunks
+/// only take CHUNK columns and the merge one compare-and-swap row per
+/// comparator, so the number of columns does not grow with the length of
+/// the array.
+///
+/// The merge is a network of comparators that only depends on the length,
+/// rather than copies of the chunk outputs in the merged order: copies are
+/// fixed at keygen, so they cannot depend on the witness.
+#[derive(Debug, Clone)]
+pub struct ChunkedSortChip<F: FieldExt, const CHUNK: usize, const N_BYTES: usize> {
+    config: ChunkedSortConfig<F, CHUNK, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const CHUNK: usize, const N_BYTES: usize> ChunkedSortChip<F, CHUNK, N_BYTES> {
+    pub fn construct(config: ChunkedSortConfig<F, CHUNK, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; CHUNK],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        u8_table: U8Table,
+    ) -> ChunkedSortConfig<F, CHUNK, N_BYTES> {
+        Self::configure_with(
+            meta_cs,
+            advice,
+            instance,
+            fixed,
+            order,
+            allow_duplicates,
+            false,
+            u8_table,
+        )
+    }
+
+    // Configures the chip, the chunks witnessing their inputs privately if
+    // private_inputs is set rather than reading them from the instance.
+    #[allow(clippy::too_many_arguments)]
+    fn configure_with(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; CHUNK],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        private_inputs: bool,
+        u8_table: U8Table,
+    ) -> ChunkedSortConfig<F, CHUNK, N_BYTES> {
+        let chunk_config = SortNChip::configure(
+            meta_cs,
+            advice,
+            instance,
+            fixed,
+            order,
+            allow_duplicates,
+            false,
+            private_inputs,
+            false,
+            u8_table,
+        );
+        // the compare-and-swap rows need four wire columns, one lt column
+        // and N_BYTES diff columns, which may be more than the CHUNK columns
+        let mut advice_vec = advice.to_vec();
+        for _i in CHUNK..N_BYTES + 5 {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        let merge_config = CompareSwapChip::configure(
+            meta_cs,
+            advice_vec[..4].try_into().unwrap(),
+            advice_vec[4],
+            advice_vec[5..N_BYTES + 5].try_into().unwrap(),
+            order,
+            u8_table,
+        );
+
+        let merged = advice[0];
+        let merge_selector = meta_cs.complex_selector();
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let (lhs, rhs) = order.lt_operands(allow_duplicates, Rotation::cur(), Rotation::next());
+        let merge_lt_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(merge_selector),
+            |meta| meta.query_advice(merged, lhs),
+            |meta| meta.query_advice(merged, rhs),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        let expected_lt = if allow_duplicates {
+            Expression::Constant(F::zero())
+        } else {
+            Expression::Constant(F::one())
+        };
+        meta_cs.create_gate("chunkedSort merge", |meta_vc| {
+            // merged | lt  | diff ..          | selectors
+            // o0       lt0   diff0_0 ..         merge_selector
+            // o1       lt1   diff1_0 ..         merge_selector
+            // ...
+            // o(L-1)
+            let s = meta_vc.query_selector(merge_selector);
+            vec![s * (merge_lt_config.is_lt(meta_vc, None) - expected_lt)]
+        });
+
+        ChunkedSortConfig {
+            chunk_config,
+            merge_config,
+            merged,
+            merge_selector,
+            merge_lt_config,
+        }
+    }
+
+    /// Assigns the inputs read from the instance column, whose values are
+    /// `values`, and returns the sorted output cells. The number of values
+    /// has to be a multiple of CHUNK.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        values: &[F],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        self.assign_from(layouter, values, &[])
+    }
+
+    // Like assign, but constrains the private inputs of the chunks to equal
+    // the cells of a gadget input, if any.
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[F],
+        inputs: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert_eq!(values.len() % CHUNK, 0, "values must fill whole chunks");
+        let order = self.config.chunk_config.order;
+        let chunk_chip = SortNChip::construct(self.config.chunk_config.clone());
+
+        // sorted chunks
+        let mut chunk_cells = Vec::with_capacity(values.len());
+        let mut chunk_values = Vec::with_capacity(values.len());
+        for (c, chunk) in values.chunks(CHUNK).enumerate() {
+            let (in_indices, sorted) = chunk_chip.sort_indices(chunk.try_into().unwrap());
+            let cells = chunk_chip.assign_cells_from(
+                layouter.namespace(|| format!("chunk {}", c)),
+                c * CHUNK,
+                in_indices,
+                sorted,
+                inputs.get(c * CHUNK..(c + 1) * CHUNK).unwrap_or(&[]),
+            )?;
+            chunk_cells.extend(cells.outputs);
+            chunk_values.extend(sorted);
+        }
+
+        // pairwise merges of the sorted chunks
+        let merge_chip = CompareSwapChip::construct(self.config.merge_config.clone());
+        let comparators = merge_comparators(vec![CHUNK; values.len() / CHUNK]);
+        let (wires, merged_values) = layouter.assign_region(
+            || "merge network",
+            |mut region| {
+                let mut wires = chunk_cells.clone();
+                let mut merged_values = chunk_values.clone();
+                merge_chip.assign(&mut region, 0, &mut wires, &mut merged_values, &comparators)?;
+                Ok((wires, merged_values))
+            },
+        )?;
+
+        layouter.assign_region(
+            || "merge",
+            |mut region| {
+                let mut merged_cells = Vec::with_capacity(values.len());
+                for (row, wire) in wires.iter().enumerate() {
+                    merged_cells.push(wire.copy_advice(
+                        || format!("merged[{}]", row),
+                        &mut region,
+                        self.config.merged,
+                        row,
+                    )?);
+                }
+
+                let lt_chip = LtChip::construct(self.config.merge_lt_config);
+                for row in 0..merged_values.len() - 1 {
+                    self.config.merge_selector.enable(&mut region, row)?;
+                    let (lhs, rhs) = order.lt_operands(
+                        self.config.chunk_config.allow_duplicates,
+                        merged_values[row],
+                        merged_values[row + 1],
+                    );
+                    lt_chip.assign(&mut region, row, lhs, rhs)?;
+                }
+                Ok(merged_cells)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.chunk_config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const CHUNK: usize, const N_BYTES: usize> Gadget<F>
+    for ChunkedSortChip<F, CHUNK, N_BYTES>
+{
+    type Config = ChunkedSortConfig<F, CHUNK, N_BYTES>;
+    /// The order of the outputs and whether they may repeat.
+    type Params = (SortOrder, bool);
+    /// The cells of the inputs, a multiple of CHUNK of them.
+    type Input = Vec<AssignedCell<F, F>>;
+    /// The sorted output cells.
+    type Output = Vec<AssignedCell<F, F>>;
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, allow_duplicates): (SortOrder, bool),
+    ) -> Self::Config {
+        ChunkedSortChip::configure_with(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            order,
+            allow_duplicates,
+            true,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let values: Vec<_> = input.iter().map(known_value).collect();
+        // the chunks witness their private inputs, which are the input cells
+        ChunkedSortChip::construct(config.clone()).assign_from(layouter, &values, &input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChunkedSortChip, ChunkedSortConfig, SortOrder};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct ChunkedSortCircuit<F> {
+        values: Vec<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for ChunkedSortCircuit<F> {
+        type Config = ChunkedSortConfig<F, 4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                values: vec![F::zero(); self.values.len()],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let u8_table = U8Table::configure(meta);
+            ChunkedSortChip::configure(
+                meta,
+                advice,
+                instance,
+                fixed,
+                SortOrder::Ascending,
+                true,
+                u8_table,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.chunk_config.u8_table.load(&mut layouter)?;
+            let chip = ChunkedSortChip::construct(config);
+
+            let merged_cells = chip.assign(layouter.namespace(|| "all"), &self.values)?;
+
+            for (i, cell) in merged_cells.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), cell, i + self.values.len())?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_chunked_sort(values: Vec<u64>, sorted: Vec<u64>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = ChunkedSortCircuit {
+            values: values.iter().map(|v| Fp::from(*v)).collect(),
+        };
+        let instance = values.iter().chain(sorted.iter()).map(|v| Fp::from(*v));
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn chunked_sort() {
+        // ok
+        assert_eq!(
+            try_chunked_sort(
+                vec![9, 3, 7, 1, 12, 2, 8, 5, 4, 11, 6, 10],
+                vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            try_chunked_sort(vec![5, 1, 5, 1, 1, 5, 1, 5], vec![1, 1, 1, 1, 5, 5, 5, 5]),
+            Ok(())
+        );
+        // error: every chunk is sorted, but they are not merged
+        assert!(try_chunked_sort(
+            vec![9, 3, 7, 1, 12, 2, 8, 5, 4, 11, 6, 10],
+            vec![1, 3, 7, 9, 2, 5, 8, 12, 4, 6, 10, 11]
+        )
+        .is_err());
+        // error
+        assert!(try_chunked_sort(
+            vec![9, 3, 7, 1, 12, 2, 8, 5, 4, 11, 6, 10],
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 12, 11]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    pasta::Fp,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use super::{SortNChip, SortNConfig, SortOrder};
+use crate::table::U8Table;
+
+/// Config of the SortCommitCircuit.
+#[derive(Debug, Clone)]
+pub struct SortCommitConfig<const N: usize, const N_BYTES: usize> {
+    sort: SortNConfig<Fp, N, N_BYTES>,
+    poseidon: Pow5Config<Fp, 3, 2>,
+    /// Denotes the private blinding factor of the commitment.
+    blinding: Column<Advice>,
+    /// Denotes the commitment, the only public input.
+    commitment: Column<Instance>,
+}
+
+/// Circuit sorting N private values in ascending order, whose only public
+/// input is the Poseidon commitment to the values and the sorted values,
+/// H(H(values), H(sorted), blinding), rather than the 2N values themselves,
+/// so that the calldata and the verifier cost stay constant in N. The
+/// private blinding factor keeps a guess of the values from being checked
+/// against the commitment, and the sort chip range checks the values to
+/// N_BYTES bytes.
+#[derive(Debug, Clone)]
+pub struct SortCommitCircuit<const N: usize, const N_BYTES: usize> {
+    pub values: [Fp; N],
+    /// The blinding factor, drawn at random for every commitment.
+    pub blinding: Fp,
+}
+
+impl<const N: usize, const N_BYTES: usize> Default for SortCommitCircuit<N, N_BYTES> {
+    fn default() -> Self {
+        Self {
+            values: [Fp::from(0); N],
+            blinding: Fp::from(0),
+        }
+    }
+}
+
+/// Returns H(H(values), H(sorted), blinding), the public input of a
+/// SortCommitCircuit.
+pub fn commitment<const N: usize>(values: [Fp; N], blinding: Fp) -> Fp {
+    let (_, sorted) = SortOrder::Ascending.sort_indices(values);
+    let hash_values = poseidon::Hash::<_, P128Pow5T3, ConstantLength<N>, 3, 2>::init();
+    let hash_sorted = poseidon::Hash::<_, P128Pow5T3, ConstantLength<N>, 3, 2>::init();
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<3>, 3, 2>::init().hash([
+        hash_values.hash(values),
+        hash_sorted.hash(sorted),
+        blinding,
+    ])
+}
+
+/// Returns the Poseidon hash of the cells.
+fn hash<const L: usize>(
+    config: &Pow5Config<Fp, 3, 2>,
+    mut layouter: impl Layouter<Fp>,
+    message: [AssignedCell<Fp, Fp>; L],
+) -> Result<AssignedCell<Fp, Fp>, Error> {
+    let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<L>, 3, 2>::init(
+        Pow5Chip::construct(config.clone()),
+        layouter.namespace(|| "init"),
+    )?;
+    hasher.hash(layouter.namespace(|| "hash"), message)
+}
+
+impl<const N: usize, const N_BYTES: usize> Circuit<Fp> for SortCommitCircuit<N, N_BYTES> {
+    type Config = SortCommitConfig<N, N_BYTES>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        let advice = [(); N].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let fixed = meta.fixed_column();
+        let u8_table = U8Table::configure(meta);
+        let sort = SortNChip::configure(
+            meta,
+            advice,
+            instance,
+            fixed,
+            SortOrder::Ascending,
+            false,
+            false,
+            true,
+            false,
+            u8_table,
+        );
+
+        let state = [(); 3].map(|_| meta.advice_column());
+        let partial_sbox = meta.advice_column();
+        let rc_a = [(); 3].map(|_| meta.fixed_column());
+        let rc_b = [(); 3].map(|_| meta.fixed_column());
+        meta.enable_constant(rc_b[0]);
+        let poseidon = Pow5Chip::configure::<P128Pow5T3>(meta, state, partial_sbox, rc_a, rc_b);
+
+        let blinding = advice[0];
+        let commitment = meta.instance_column();
+        meta.enable_equality(commitment);
+
+        SortCommitConfig {
+            sort,
+            poseidon,
+            blinding,
+            commitment,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        config.sort.u8_table.load(&mut layouter)?;
+        let sort_chip = SortNChip::construct(config.sort);
+        let (in_indices, values) = sort_chip.sort_indices(self.values);
+        let cells = sort_chip.assign_cells(layouter.namespace(|| "sort"), 0, in_indices, values)?;
+
+        let blinding = layouter.assign_region(
+            || "blinding",
+            |mut region| {
+                region.assign_advice(
+                    || "blinding",
+                    config.blinding,
+                    0,
+                    || Value::known(self.blinding),
+                )
+            },
+        )?;
+
+        let hash_inputs = hash(
+            &config.poseidon,
+            layouter.namespace(|| "hash inputs"),
+            cells.inputs,
+        )?;
+        let hash_outputs = hash(
+            &config.poseidon,
+            layouter.namespace(|| "hash outputs"),
+            cells.outputs,
+        )?;
+        let commitment = hash(
+            &config.poseidon,
+            layouter.namespace(|| "commitment"),
+            [hash_inputs, hash_outputs, blinding],
+        )?;
+        layouter.constrain_instance(commitment.cell(), config.commitment, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{commitment, SortCommitCircuit};
+    use crate::testing;
+    use halo2_proofs::{arithmetic::FieldExt, pasta::Fp};
+
+    #[test]
+    fn sort_commit() {
+        let values = [3, 1, 4, 2].map(Fp::from);
+        let blinding = Fp::from(1234);
+        let circuit = SortCommitCircuit::<4, 8> { values, blinding };
+
+        // ok
+        assert_eq!(
+            testing::verify(&circuit, vec![vec![], vec![commitment(values, blinding)]]),
+            Ok(())
+        );
+        // error: the commitment to other values
+        let other = commitment([3, 1, 4, 5].map(Fp::from), blinding);
+        assert!(testing::verify(&circuit, vec![vec![], vec![other]]).is_err());
+        // error: the commitment under another blinding factor
+        let other = commitment(values, Fp::from(0));
+        assert!(testing::verify(&circuit, vec![vec![], vec![other]]).is_err());
+        // error: a value wider than 8 bytes
+        let values = [3, 1, 4, 1 << 64].map(Fp::from_u128);
+        let circuit = SortCommitCircuit::<4, 8> { values, blinding };
+        assert!(
+            testing::verify(&circuit, vec![vec![], vec![commitment(values, blinding)]]).is_err()
+        );
+    }
+}
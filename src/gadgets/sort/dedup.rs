@@ -0,0 +1,504 @@
+use super::SortOrder;
+use crate::{
+    constrain_if,
+    constraint_builder::BaseConstraintBuilder,
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::{bool_check, is_zero, not, one_hot, sum, Expr},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for deduplicating N sorted elements, each compared as an
+/// N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct DedupConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // the sorted inputs, whether every input is kept, the deduplicated
+    // outputs and whether every output is padding
+    pub advice: [Column<Advice>; 4],
+    /// The order of the sorted inputs.
+    pub order: SortOrder,
+    /// The value that the outputs are padded with.
+    pub sentinel: F,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // inverse of the difference to the previous input, the running counts
+    // of the kept inputs and of the outputs that are not padding, and the
+    // one-hot selection of the input of every output
+    diff_inv: Column<Advice>,
+    kept_count: Column<Advice>,
+    out_count: Column<Advice>,
+    selected: [Column<Advice>; N],
+    first_selector: Selector,
+    step_selector: Selector,
+    row_selector: Selector,
+    lt_selector: Selector,
+    last_selector: Selector,
+    lt_config: LtConfig<F, N_BYTES>,
+}
+
+/// Chip that deduplicates N sorted elements. An input is kept iff it differs
+/// from the previous one, the kept inputs are compacted into strictly ordered
+/// outputs and the remaining outputs are padded with a sentinel.
+///
+/// Every output that is not padding selects an input by a one-hot row. As the
+/// outputs are strictly ordered and as many as the kept inputs, they are
+/// exactly the distinct inputs, whichever of equal inputs they select.
+#[derive(Debug, Clone)]
+pub struct DedupChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: DedupConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> DedupChip<F, N, N_BYTES> {
+    pub fn construct(config: DedupConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        order: SortOrder,
+        sentinel: F,
+        u8_table: U8Table,
+    ) -> DedupConfig<F, N, N_BYTES> {
+        assert!(N > 1, "dedup needs at least two elements");
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let [sorted, keep, out, pad] = advice;
+        let diff_inv = meta_cs.advice_column();
+        let kept_count = meta_cs.advice_column();
+        let out_count = meta_cs.advice_column();
+        meta_cs.enable_equality(out_count);
+        let selected = [(); N].map(|_| meta_cs.advice_column());
+        let first_selector = meta_cs.selector();
+        let step_selector = meta_cs.selector();
+        let row_selector = meta_cs.selector();
+        let lt_selector = meta_cs.complex_selector();
+        let last_selector = meta_cs.selector();
+
+        // out < next out, only checked if the next out is not padding
+        let (lhs, rhs) = order.lt_operands(false, Rotation::cur(), Rotation::next());
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let lt_config = LtChip::configure(
+            meta_cs,
+            |meta| {
+                meta.query_selector(lt_selector)
+                    * (1.expr() - meta.query_advice(pad, Rotation::next()))
+            },
+            |meta| meta.query_advice(out, lhs),
+            |meta| meta.query_advice(out, rhs),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        // The selectors are in gates of their own, as the first row has no
+        // previous row and the last no next one to query.
+        //
+        // sorted | keep | out | pad | diff_inv | kept_count | out_count | lt ..
+        // s0       1      o0    0                k0           c0          lt0
+        // s1       k1     o1    p1    inv1       k0 + k1      c0 + !p1    lt1
+        // ...
+        // s(N-1)   ...                           kept         count
+        meta_cs.create_gate("dedup first", |meta_vc| {
+            // the first input is always kept
+            let first = meta_vc.query_selector(first_selector);
+            let keep_cur = meta_vc.query_advice(keep, Rotation::cur());
+            let kept_cur = meta_vc.query_advice(kept_count, Rotation::cur());
+            let count_cur = meta_vc.query_advice(out_count, Rotation::cur());
+            let not_pad = not::expr(meta_vc.query_advice(pad, Rotation::cur()));
+            vec![
+                first.clone() * (keep_cur.clone() - 1.expr()),
+                first.clone() * (kept_cur - keep_cur),
+                first * (count_cur - not_pad),
+            ]
+        });
+
+        meta_cs.create_gate("dedup step", |meta_vc| {
+            let step = meta_vc.query_selector(step_selector);
+            let s_cur = meta_vc.query_advice(sorted, Rotation::cur());
+            let s_prev = meta_vc.query_advice(sorted, Rotation::prev());
+            let keep_cur = meta_vc.query_advice(keep, Rotation::cur());
+            let pad_cur = meta_vc.query_advice(pad, Rotation::cur());
+            let pad_prev = meta_vc.query_advice(pad, Rotation::prev());
+            let diff_inv = meta_vc.query_advice(diff_inv, Rotation::cur());
+            let kept_cur = meta_vc.query_advice(kept_count, Rotation::cur());
+            let kept_prev = meta_vc.query_advice(kept_count, Rotation::prev());
+            let count_cur = meta_vc.query_advice(out_count, Rotation::cur());
+            let count_prev = meta_vc.query_advice(out_count, Rotation::prev());
+            let s_diff = s_cur - s_prev;
+            let not_pad = 1.expr() - pad_cur.clone();
+
+            vec![
+                // an input is kept iff it differs from the previous one
+                step.clone() * bool_check(keep_cur.clone()),
+                constrain_if!(step, not::expr(keep_cur.clone()) => s_diff.clone()),
+                constrain_if!(step, keep_cur => is_zero::expr(s_diff, diff_inv)),
+                step.clone() * (kept_cur - kept_prev - keep_cur),
+                step.clone() * (count_cur - count_prev - not_pad),
+                // padding is a suffix of the outputs
+                constrain_if!(step, pad_prev => not::expr(pad_cur)),
+            ]
+        });
+
+        meta_cs.create_gate("dedup row", |meta_vc| {
+            // padding outputs are the sentinel
+            let row = meta_vc.query_selector(row_selector);
+            let out_cur = meta_vc.query_advice(out, Rotation::cur());
+            let pad_cur = meta_vc.query_advice(pad, Rotation::cur());
+            vec![
+                row.clone() * bool_check(pad_cur.clone()),
+                constrain_if!(row, pad_cur => out_cur - Expression::Constant(sentinel)),
+            ]
+        });
+
+        meta_cs.create_gate("dedup order", |meta_vc| {
+            // adjacent outputs are strictly ordered
+            let lt = meta_vc.query_selector(lt_selector);
+            let pad_next = meta_vc.query_advice(pad, Rotation::next());
+            vec![
+                constrain_if!(lt, not::expr(pad_next) => lt_config.is_lt(meta_vc, None) - 1.expr()),
+            ]
+        });
+
+        meta_cs.create_gate("dedup last", |meta_vc| {
+            // every kept input is an output
+            let last = meta_vc.query_selector(last_selector);
+            let kept_cur = meta_vc.query_advice(kept_count, Rotation::cur());
+            let count_cur = meta_vc.query_advice(out_count, Rotation::cur());
+            vec![last * (kept_cur - count_cur)]
+        });
+
+        // The outputs are selected rather than copied from the kept inputs, as
+        // copies are fixed at keygen.
+        meta_cs.create_gate("dedup selection", |meta_vc| {
+            // sel_i on the row of out_i selects the input of out_i, if it is
+            // not padding
+            let first = meta_vc.query_selector(first_selector);
+            let inputs: Vec<_> = (0..N)
+                .map(|row| meta_vc.query_advice(sorted, Rotation(row as i32)))
+                .collect();
+
+            let mut cb = BaseConstraintBuilder::new(3);
+            for i in 0..N {
+                let rotation = Rotation(i as i32);
+                let out = meta_vc.query_advice(out, rotation);
+                let not_pad = not::expr(meta_vc.query_advice(pad, rotation));
+                let sel: Vec<_> = selected
+                    .iter()
+                    .map(|column| meta_vc.query_advice(*column, rotation))
+                    .collect();
+                for bit in &sel {
+                    cb.require_boolean("sel is boolean", bit.clone());
+                }
+                cb.require_equal(
+                    "sel is one-hot unless padding",
+                    sum::expr(&sel),
+                    not_pad.clone(),
+                );
+                cb.condition(not_pad, |cb| {
+                    cb.require_equal(
+                        "out is the selected input",
+                        out,
+                        one_hot::select(&sel, &inputs),
+                    );
+                });
+            }
+            cb.gate(first)
+        });
+
+        DedupConfig {
+            advice,
+            order,
+            sentinel,
+            u8_table,
+            diff_inv,
+            kept_count,
+            out_count,
+            selected,
+            first_selector,
+            step_selector,
+            row_selector,
+            lt_selector,
+            last_selector,
+            lt_config,
+        }
+    }
+
+    /// Deduplicates the sorted cells, given along with their values, and
+    /// returns the padded output cells and the cell of the number of outputs
+    /// that are not padding.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sorted: &[(AssignedCell<F, F>, F); N],
+    ) -> Result<([AssignedCell<F, F>; N], AssignedCell<F, F>), Error> {
+        let [sorted_column, keep_column, out_column, pad_column] = self.config.advice;
+        let keep: Vec<bool> = (0..N)
+            .map(|i| i == 0 || sorted[i].1 != sorted[i - 1].1)
+            .collect();
+        let kept: Vec<_> = (0..N).filter(|i| keep[*i]).collect();
+
+        layouter.assign_region(
+            || "dedup",
+            |mut region| {
+                self.config.first_selector.enable(&mut region, 0)?;
+                self.config.last_selector.enable(&mut region, N - 1)?;
+
+                // inputs and whether they are kept
+                let (mut kept_count, mut out_count) = (0, 0);
+                let mut out_count_cell = None;
+                for (i, (cell, value)) in sorted.iter().enumerate() {
+                    self.config.row_selector.enable(&mut region, i)?;
+                    cell.copy_advice(|| format!("sorted[{}]", i), &mut region, sorted_column, i)?;
+                    region.assign_advice(
+                        || format!("keep[{}]", i),
+                        keep_column,
+                        i,
+                        || Value::known(F::from(keep[i] as u64)),
+                    )?;
+                    if i > 0 {
+                        self.config.step_selector.enable(&mut region, i)?;
+                        let diff_inv = is_zero::value_inv(*value - sorted[i - 1].1);
+                        region.assign_advice(
+                            || format!("diff inv[{}]", i),
+                            self.config.diff_inv,
+                            i,
+                            || Value::known(diff_inv),
+                        )?;
+                    }
+                    kept_count += keep[i] as u64;
+                    out_count += (i < kept.len()) as u64;
+                    region.assign_advice(
+                        || format!("kept count[{}]", i),
+                        self.config.kept_count,
+                        i,
+                        || Value::known(F::from(kept_count)),
+                    )?;
+                    out_count_cell = Some(region.assign_advice(
+                        || format!("out count[{}]", i),
+                        self.config.out_count,
+                        i,
+                        || Value::known(F::from(out_count)),
+                    )?);
+                }
+
+                // compacted outputs, padded with the sentinel
+                let mut out_cells = Vec::with_capacity(N);
+                let mut out_values = Vec::with_capacity(N);
+                for i in 0..N {
+                    let is_pad = i >= kept.len();
+                    region.assign_advice(
+                        || format!("pad[{}]", i),
+                        pad_column,
+                        i,
+                        || Value::known(F::from(is_pad as u64)),
+                    )?;
+                    let out_value = if is_pad {
+                        self.config.sentinel
+                    } else {
+                        sorted[kept[i]].1
+                    };
+                    out_cells.push(region.assign_advice(
+                        || format!("out[{}]", i),
+                        out_column,
+                        i,
+                        || Value::known(out_value),
+                    )?);
+                    out_values.push(out_value);
+                    for (j, column) in self.config.selected.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("out[{}] one-hot[{}]", i, j),
+                            *column,
+                            i,
+                            || Value::known(F::from((!is_pad && j == kept[i]) as u64)),
+                        )?;
+                    }
+                }
+
+                // strict order of the outputs
+                let lt_chip = LtChip::construct(self.config.lt_config);
+                for i in 0..N - 1 {
+                    self.config.lt_selector.enable(&mut region, i)?;
+                    let (lhs, rhs) =
+                        self.config
+                            .order
+                            .lt_operands(false, out_values[i], out_values[i + 1]);
+                    lt_chip.assign(&mut region, i, lhs, rhs)?;
+                }
+
+                Ok((out_cells.try_into().unwrap(), out_count_cell.unwrap()))
+            },
+        )
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F> for DedupChip<F, N, N_BYTES> {
+    type Config = DedupConfig<F, N, N_BYTES>;
+    /// The order of the sorted inputs and the sentinel of the padding.
+    type Params = (SortOrder, F);
+    /// The cells of the sorted inputs.
+    type Input = [AssignedCell<F, F>; N];
+    /// The padded output cells and the cell of the number of outputs that
+    /// are not padding.
+    type Output = ([AssignedCell<F, F>; N], AssignedCell<F, F>);
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, sentinel): (SortOrder, F),
+    ) -> Self::Config {
+        DedupChip::configure(meta, columns.advice(0), order, sentinel, columns.u8_table)
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let sorted = input.map(|cell| {
+            let value = known_value(&cell);
+            (cell, value)
+        });
+        DedupChip::construct(config.clone()).assign(layouter, &sorted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DedupChip, DedupConfig, SortOrder};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct DedupCircuitConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        dedup: DedupConfig<F, 5, 8>,
+    }
+
+    #[derive(Default)]
+    struct DedupCircuit<F> {
+        sorted: [F; 5],
+    }
+
+    impl<F: FieldExt> Circuit<F> for DedupCircuit<F> {
+        type Config = DedupCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let u8_table = U8Table::configure(meta);
+            DedupCircuitConfig {
+                input,
+                instance,
+                dedup: DedupChip::configure(
+                    meta,
+                    advice,
+                    SortOrder::Ascending,
+                    F::zero(),
+                    u8_table,
+                ),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.dedup.u8_table.load(&mut layouter)?;
+            let chip = DedupChip::construct(config.dedup);
+
+            // the sorted inputs are read from the instance column
+            let sorted = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let mut cells = vec![];
+                    for (i, value) in self.sorted.iter().enumerate() {
+                        let cell = region.assign_advice_from_instance(
+                            || format!("instance({})", i),
+                            config.instance,
+                            i,
+                            config.input,
+                            i,
+                        )?;
+                        cells.push((cell, *value));
+                    }
+                    Ok(cells.try_into().unwrap())
+                },
+            )?;
+
+            let (out_cells, count) = chip.assign(layouter.namespace(|| "dedup"), &sorted)?;
+
+            for (i, cell) in out_cells.iter().chain([count].iter()).enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i + 5)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_dedup(
+        sorted: [u64; 5],
+        deduped: [u64; 5],
+        count: u64,
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = DedupCircuit {
+            sorted: sorted.map(Fp::from),
+        };
+        let instance = sorted
+            .into_iter()
+            .chain(deduped)
+            .chain([count])
+            .map(Fp::from);
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn dedup_sorted() {
+        // ok
+        assert_eq!(try_dedup([1, 1, 3, 3, 3], [1, 3, 0, 0, 0], 2), Ok(()));
+        assert_eq!(try_dedup([1, 2, 3, 4, 5], [1, 2, 3, 4, 5], 5), Ok(()));
+        assert_eq!(try_dedup([7, 7, 7, 7, 7], [7, 0, 0, 0, 0], 1), Ok(()));
+        // error
+        assert!(try_dedup([1, 1, 3, 3, 3], [1, 1, 3, 0, 0], 3).is_err());
+        assert!(try_dedup([1, 1, 3, 3, 3], [1, 3, 0, 0, 0], 3).is_err());
+        assert!(try_dedup([1, 1, 3, 3, 3], [1, 0, 0, 0, 0], 1).is_err());
+    }
+}
@@ -0,0 +1,475 @@
+use super::{SortInputs, SortOrder};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::{is_zero, one_hot},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for sorting N rows lexicographically by a (primary, secondary) key,
+/// both compared as N_BYTES-byte values.
+#[derive(Debug, Clone)]
+pub struct SortLexConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // N primary keys, N secondary keys and their sorted rows on the first four rows
+    pub advice: [Column<Advice>; N],
+    pub master_selector: Selector,
+    pub instance: Column<Instance>,
+    /// The order of the sorted rows.
+    pub order: SortOrder,
+    /// If set, rows may share both the primary and the secondary key.
+    pub allow_duplicates: bool,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // N - 1 adjacent comparisons of the primary keys, of the secondary keys
+    // and the inverse of the primary key difference for the equality check
+    lt_selectors: Vec<Selector>,
+    primary_lt_configs: Vec<LtConfig<F, N_BYTES>>,
+    secondary_lt_configs: Vec<LtConfig<F, N_BYTES>>,
+    primary_diff_inv: Column<Advice>,
+}
+
+/// Chip that sorts rows by a primary key, breaking ties by a secondary key.
+#[derive(Debug, Clone)]
+pub struct SortLexChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: SortLexConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> SortLexChip<F, N, N_BYTES> {
+    pub fn construct(config: SortLexConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        u8_table: U8Table,
+    ) -> SortLexConfig<F, N, N_BYTES> {
+        assert!(N > 1, "sortLex needs at least two elements");
+        assert!(N_BYTES < 32, "compared keys must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        meta_cs.enable_constant(fixed);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let master_selector = meta_cs.selector();
+        let mut lt_selectors = Vec::with_capacity(N - 1);
+        for _i in 0..N - 1 {
+            lt_selectors.push(meta_cs.complex_selector());
+        }
+
+        // the lt rows need two lt columns, 2 * N_BYTES diff columns and the
+        // inverse column
+        let mut advice_vec = advice.to_vec();
+        for _i in N..2 * N_BYTES + 3 {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        let primary_diff: Vec<_> = advice_vec[1..N_BYTES + 1].to_vec();
+        let secondary_diff: Vec<_> = advice_vec[N_BYTES + 2..2 * N_BYTES + 2].to_vec();
+        let primary_diff_inv = advice_vec[2 * N_BYTES + 2];
+
+        let mut primary_lt_configs = Vec::with_capacity(N - 1);
+        let mut secondary_lt_configs = Vec::with_capacity(N - 1);
+        for i in 0..N - 1 {
+            // the sorted primary keys are on row 2, the sorted secondary keys
+            // on row 3 and the lt rows start on row 4
+            let (lhs, rhs) = order.lt_operands(false, advice_vec[i], advice_vec[i + 1]);
+            primary_lt_configs.push(LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(lhs, Rotation(-2 - i as i32)),
+                |meta| meta.query_advice(rhs, Rotation(-2 - i as i32)),
+                advice_vec[0],
+                primary_diff.clone().try_into().unwrap(),
+                u8_table,
+            ));
+            let (lhs, rhs) = order.lt_operands(allow_duplicates, advice_vec[i], advice_vec[i + 1]);
+            secondary_lt_configs.push(LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(lhs, Rotation(-1 - i as i32)),
+                |meta| meta.query_advice(rhs, Rotation(-1 - i as i32)),
+                advice_vec[N_BYTES + 1],
+                secondary_diff.clone().try_into().unwrap(),
+                u8_table,
+            ));
+        }
+
+        let mut lt_constraints = Vec::with_capacity(2 * (N - 1));
+        meta_cs.create_gate("sortLex", |meta_vc| {
+            //  0  |  1  | ... | N-1    | selectors
+            // p0    p1    ...   p(N-1)  master
+            // s0    s1    ...   s(N-1)
+            // op0   op1   ...   op(N-1)
+            // os0   os1   ...   os(N-1)
+            // plt0 pdiff0_0 .. slt0 sdiff0_0 .. pinv0    lt_selectors[0]
+            // ...
+            // plt(N-2) pdiff(N-2)_0 ..                   lt_selectors[N-2]
+            let s = meta_vc.query_selector(master_selector);
+            let one = Expression::Constant(F::one());
+
+            for (i, (primary_lt, secondary_lt)) in primary_lt_configs
+                .iter()
+                .zip(secondary_lt_configs.iter())
+                .enumerate()
+            {
+                let rotation = Rotation(i as i32 + 4);
+                let primary_lt = primary_lt.is_lt(meta_vc, Some(rotation));
+                let secondary_lt = secondary_lt.is_lt(meta_vc, Some(rotation));
+                let secondary_ordered = if allow_duplicates {
+                    one.clone() - secondary_lt
+                } else {
+                    secondary_lt
+                };
+
+                // primary_eq == 1 iff the adjacent primary keys are equal
+                let primary_cur = meta_vc.query_advice(advice[i], Rotation(2));
+                let primary_next = meta_vc.query_advice(advice[i + 1], Rotation(2));
+                let primary_diff = primary_next - primary_cur;
+                let diff_inv = meta_vc.query_advice(primary_diff_inv, rotation);
+                let primary_eq = one.clone() - primary_diff.clone() * diff_inv;
+
+                lt_constraints.push(s.clone() * primary_diff * primary_eq.clone());
+                lt_constraints
+                    .push(s.clone() * (primary_lt + primary_eq * secondary_ordered - one.clone()));
+            }
+            lt_constraints
+        });
+
+        // The sorted rows are selected by a permutation matrix rather than
+        // copied from the inputs, as copies are fixed at keygen.
+        meta_cs.create_gate("sortLex permutation", |meta_vc| {
+            // sel0_0    sel0_1 ...    sel0_(N-1)       row N + 3
+            // ...
+            // sel(N-1)_0 ...          sel(N-1)_(N-1)   row 2N + 2
+            // where sel_i selects the input row of the sorted row i
+            let s = meta_vc.query_selector(master_selector);
+            let query_row = |meta_vc: &mut VirtualCells<F>, row: usize| -> Vec<_> {
+                advice
+                    .iter()
+                    .map(|column| meta_vc.query_advice(*column, Rotation(row as i32)))
+                    .collect()
+            };
+            let primary = query_row(meta_vc, 0);
+            let secondary = query_row(meta_vc, 1);
+            let sorted_primary = query_row(meta_vc, 2);
+            let sorted_secondary = query_row(meta_vc, 3);
+            let matrix: Vec<_> = (0..N).map(|i| query_row(meta_vc, N + 3 + i)).collect();
+
+            let mut constraints = one_hot::permutation(&matrix);
+            for (i, sel) in matrix.iter().enumerate() {
+                constraints.push(sorted_primary[i].clone() - one_hot::select(sel, &primary));
+                constraints.push(sorted_secondary[i].clone() - one_hot::select(sel, &secondary));
+            }
+            constraints
+                .into_iter()
+                .map(|constraint| s.clone() * constraint)
+                .collect::<Vec<_>>()
+        });
+
+        SortLexConfig {
+            advice,
+            master_selector,
+            instance,
+            order,
+            allow_duplicates,
+            u8_table,
+            lt_selectors,
+            primary_lt_configs,
+            secondary_lt_configs,
+            primary_diff_inv,
+        }
+    }
+
+    /// Assigns the rows read from the instance column (primary keys on rows
+    /// 0..N, secondary keys on rows N..2N) and returns the sorted (primary,
+    /// secondary) cells.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        in_indices: [usize; N],
+        keys: [(F, F); N],
+    ) -> Result<([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]), Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, inputs, in_indices, keys)
+    }
+
+    // Assigns the rows read from the inputs, primary keys 0..N and secondary
+    // keys N..2N.
+    #[allow(clippy::type_complexity)]
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: SortInputs<F>,
+        in_indices: [usize; N],
+        keys: [(F, F); N],
+    ) -> Result<([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]), Error> {
+        layouter.assign_region(
+            || "sort lex",
+            |mut region| {
+                self.config.master_selector.enable(&mut region, 0)?;
+
+                // unsorted keys
+                let mut in_primary = Vec::with_capacity(N);
+                let mut in_secondary = Vec::with_capacity(N);
+                for (i, column) in self.config.advice.iter().enumerate() {
+                    in_primary.push(inputs.assign(&mut region, i, *column, 0)?);
+                    in_secondary.push(inputs.assign(&mut region, N + i, *column, 1)?);
+                }
+
+                // sorted keys and the permutation matrix selecting them
+                let mut out_primary = Vec::with_capacity(N);
+                let mut out_secondary = Vec::with_capacity(N);
+                for (i, in_index) in in_indices.iter().enumerate() {
+                    let (primary, secondary) = (&in_primary[*in_index], &in_secondary[*in_index]);
+                    out_primary.push(region.assign_advice(
+                        || format!("sort primary out[{}]", i),
+                        self.config.advice[i],
+                        2,
+                        || primary.value().copied(),
+                    )?);
+                    out_secondary.push(region.assign_advice(
+                        || format!("sort secondary out[{}]", i),
+                        self.config.advice[i],
+                        3,
+                        || secondary.value().copied(),
+                    )?);
+                    for (j, column) in self.config.advice.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("sort row[{}] one-hot[{}]", i, j),
+                            *column,
+                            N + 3 + i,
+                            || Value::known(F::from((j == *in_index) as u64)),
+                        )?;
+                    }
+                }
+
+                // lt chips and primary key equality
+                for (i, lt_selector) in self.config.lt_selectors.iter().enumerate() {
+                    lt_selector.enable(&mut region, i + 4)?;
+                }
+                for i in 0..N - 1 {
+                    let ((primary_cur, secondary_cur), (primary_next, secondary_next)) =
+                        (keys[i], keys[i + 1]);
+
+                    let lt_chip = LtChip::construct(self.config.primary_lt_configs[i]);
+                    let (lhs, rhs) =
+                        self.config
+                            .order
+                            .lt_operands(false, primary_cur, primary_next);
+                    lt_chip.assign(&mut region, i + 4, lhs, rhs)?;
+
+                    let lt_chip = LtChip::construct(self.config.secondary_lt_configs[i]);
+                    let (lhs, rhs) = self.config.order.lt_operands(
+                        self.config.allow_duplicates,
+                        secondary_cur,
+                        secondary_next,
+                    );
+                    lt_chip.assign(&mut region, i + 4, lhs, rhs)?;
+
+                    let primary_diff_inv = is_zero::value_inv(primary_next - primary_cur);
+                    region.assign_advice(
+                        || format!("primary diff inv[{}]", i),
+                        self.config.primary_diff_inv,
+                        i + 4,
+                        || Value::known(primary_diff_inv),
+                    )?;
+                }
+                Ok((
+                    out_primary.try_into().unwrap(),
+                    out_secondary.try_into().unwrap(),
+                ))
+            },
+        )
+    }
+
+    /// Sorts the (primary, secondary) keys lexicographically in the
+    /// configured order, returning the input index of every sorted row along
+    /// with the sorted keys.
+    pub fn sort_indices(&self, keys: [(F, F); N]) -> ([usize; N], [(F, F); N]) {
+        self.config.order.sort_indices(keys)
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F> for SortLexChip<F, N, N_BYTES> {
+    type Config = SortLexConfig<F, N, N_BYTES>;
+    /// The order of the rows and whether they may repeat.
+    type Params = (SortOrder, bool);
+    /// The cells of the primary and of the secondary keys.
+    type Input = ([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]);
+    /// The cells of the sorted primary and secondary keys.
+    type Output = ([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]);
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, allow_duplicates): (SortOrder, bool),
+    ) -> Self::Config {
+        SortLexChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            order,
+            allow_duplicates,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        (primary, secondary): Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let chip = SortLexChip::construct(config.clone());
+        let keys = std::array::from_fn(|i| (known_value(&primary[i]), known_value(&secondary[i])));
+        let (in_indices, sorted) = chip.sort_indices(keys);
+        let inputs: Vec<_> = primary.into_iter().chain(secondary).collect();
+        chip.assign_from(layouter, SortInputs::Cells(&inputs), in_indices, sorted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SortLexChip, SortLexConfig, SortOrder};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct SortLexCircuit<F> {
+        keys: [(F, F); 4],
+    }
+
+    impl<F: FieldExt> Circuit<F> for SortLexCircuit<F> {
+        type Config = SortLexConfig<F, 4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let u8_table = U8Table::configure(meta);
+            SortLexChip::configure(
+                meta,
+                advice,
+                instance,
+                fixed,
+                SortOrder::Ascending,
+                false,
+                u8_table,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = SortLexChip::construct(config);
+
+            let (in_indices, keys) = chip.sort_indices(self.keys);
+            let (out_primary, out_secondary) =
+                chip.assign(layouter.namespace(|| "all"), in_indices, keys)?;
+
+            for (i, cell) in out_primary.iter().chain(out_secondary.iter()).enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), cell, i + 8)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_sort_lex(
+        keys: [(u64, u64); 4],
+        sorted: [(u64, u64); 4],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = SortLexCircuit {
+            keys: keys.map(|(primary, secondary)| (Fp::from(primary), Fp::from(secondary))),
+        };
+        let instance = [keys, sorted]
+            .iter()
+            .flat_map(|keys| {
+                let primary = keys.iter().map(|(primary, _)| Fp::from(*primary));
+                let secondary = keys.iter().map(|(_, secondary)| Fp::from(*secondary));
+                primary.chain(secondary).collect::<Vec<_>>()
+            })
+            .collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn sort_by_primary_then_secondary_key() {
+        // ok
+        assert_eq!(
+            try_sort_lex(
+                [(5, 2), (3, 9), (5, 1), (3, 4)],
+                [(3, 4), (3, 9), (5, 1), (5, 2)]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort_lex(
+                [(4, 0), (3, 0), (2, 0), (1, 0)],
+                [(1, 0), (2, 0), (3, 0), (4, 0)]
+            ),
+            Ok(())
+        );
+        // error: ties not broken by the secondary key
+        assert!(try_sort_lex(
+            [(5, 2), (3, 9), (5, 1), (3, 4)],
+            [(3, 9), (3, 4), (5, 2), (5, 1)]
+        )
+        .is_err());
+        // error: duplicate rows in strict mode
+        assert!(try_sort_lex(
+            [(5, 2), (3, 9), (5, 2), (3, 4)],
+            [(3, 4), (3, 9), (5, 2), (5, 2)]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,699 @@
+use super::{constrain_inputs, SortInputs, SortOrder};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::{bool_check, Expr},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for proving that a public value is in a private array of N sorted
+/// elements, each compared as an N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct MembershipConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // N sorted elements, the one-hot index of the value and the value on the
+    // first three rows
+    pub advice: [Column<Advice>; N],
+    pub master_selector: Selector,
+    pub instance: Column<Instance>,
+    /// The order of the sorted elements.
+    pub order: SortOrder,
+    /// If set, adjacent elements may be equal.
+    pub allow_duplicates: bool,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // N - 1 adjacent comparisons
+    lt_selectors: Vec<Selector>,
+    lt_configs: Vec<LtConfig<F, N_BYTES>>,
+}
+
+/// Chip that proves a public value is an element of a private sorted array,
+/// by selecting the element at a witnessed one-hot index.
+#[derive(Debug, Clone)]
+pub struct MembershipChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: MembershipConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> MembershipChip<F, N, N_BYTES> {
+    pub fn construct(config: MembershipConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        u8_table: U8Table,
+    ) -> MembershipConfig<F, N, N_BYTES> {
+        assert!(N > 1, "membership needs at least two elements");
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let master_selector = meta_cs.selector();
+        let mut lt_selectors = Vec::with_capacity(N - 1);
+        for _i in 0..N - 1 {
+            lt_selectors.push(meta_cs.complex_selector());
+        }
+
+        let mut lt_configs = Vec::with_capacity(N - 1);
+        let mut advice_vec = advice.to_vec();
+        for _i in N..N_BYTES + 1 {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        let diff: Vec<_> = advice_vec[1..N_BYTES + 1].to_vec();
+        for i in 0..N - 1 {
+            // the elements are on row 0, the lt rows start on row 3
+            let (lhs, rhs) = order.lt_operands(allow_duplicates, advice_vec[i], advice_vec[i + 1]);
+            let lt_config: LtConfig<F, N_BYTES> = LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(lhs, Rotation(-3 - i as i32)),
+                |meta| meta.query_advice(rhs, Rotation(-3 - i as i32)),
+                advice_vec[0],
+                diff.clone().try_into().unwrap(),
+                u8_table,
+            );
+            lt_configs.push(lt_config);
+        }
+
+        let expected_lt = if allow_duplicates {
+            Expression::Constant(F::zero())
+        } else {
+            Expression::Constant(F::one())
+        };
+        meta_cs.create_gate("membership", |meta_vc| {
+            //  0  |  1  | ... | N-1    | selectors
+            // s0    s1    ...   s(N-1)  master
+            // sel0  sel1  ...   sel(N-1)
+            // x
+            // lt0   diff0_0 .. diff0_(N_BYTES-1)   lt_selectors[0]
+            // ...
+            // where sel is the one-hot index of x in s
+            let s = meta_vc.query_selector(master_selector);
+            let x = meta_vc.query_advice(advice[0], Rotation(2));
+
+            let mut constraints = Vec::with_capacity(2 * N + 1);
+            let mut sum = 0.expr();
+            let mut selected = 0.expr();
+            for column in advice.iter() {
+                let element = meta_vc.query_advice(*column, Rotation::cur());
+                let bit = meta_vc.query_advice(*column, Rotation::next());
+                constraints.push(s.clone() * bool_check(bit.clone()));
+                sum = sum + bit.clone();
+                selected = selected + bit * element;
+            }
+            constraints.push(s.clone() * (sum - 1.expr()));
+            constraints.push(s.clone() * (x - selected));
+
+            for (i, lt_config) in lt_configs.iter().enumerate() {
+                constraints.push(
+                    s.clone()
+                        * (lt_config.is_lt(meta_vc, Some(Rotation(i as i32 + 3)))
+                            - expected_lt.clone()),
+                );
+            }
+            constraints
+        });
+
+        MembershipConfig {
+            advice,
+            master_selector,
+            instance,
+            order,
+            allow_duplicates,
+            u8_table,
+            lt_configs,
+            lt_selectors,
+        }
+    }
+
+    /// Witnesses the private sorted elements, reads the value from instance
+    /// row `row`, and returns the element cells.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        sorted: [F; N],
+        x: F,
+        row: usize,
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, sorted, &[], x, inputs, row)
+    }
+
+    // Witnesses the private sorted elements, constrained to equal the cells
+    // of a gadget input if any, reads the value from the input `x_index`,
+    // and returns the element cells.
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sorted: [F; N],
+        sorted_cells: &[AssignedCell<F, F>],
+        x: F,
+        inputs: SortInputs<F>,
+        x_index: usize,
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        let index = sorted.iter().position(|element| *element == x);
+
+        layouter.assign_region(
+            || "membership",
+            |mut region| {
+                self.config.master_selector.enable(&mut region, 0)?;
+
+                let mut element_cells = Vec::with_capacity(N);
+                for (i, (column, element)) in self.config.advice.iter().zip(sorted).enumerate() {
+                    element_cells.push(region.assign_advice(
+                        || format!("element[{}]", i),
+                        *column,
+                        0,
+                        || Value::known(element),
+                    )?);
+                    region.assign_advice(
+                        || format!("one-hot[{}]", i),
+                        *column,
+                        1,
+                        || Value::known(F::from(index == Some(i))),
+                    )?;
+                }
+                inputs.assign(&mut region, x_index, self.config.advice[0], 2)?;
+
+                // lt chips
+                for (i, lt_selector) in self.config.lt_selectors.iter().enumerate() {
+                    lt_selector.enable(&mut region, i + 3)?;
+                }
+                for (i, lt_config) in self.config.lt_configs.iter().enumerate() {
+                    let lt_chip = LtChip::construct(*lt_config);
+                    let (lhs, rhs) = self.config.order.lt_operands(
+                        self.config.allow_duplicates,
+                        sorted[i],
+                        sorted[i + 1],
+                    );
+                    lt_chip.assign(&mut region, i + 3, lhs, rhs)?;
+                }
+                constrain_inputs(&mut region, sorted_cells, &element_cells)?;
+                Ok(element_cells.try_into().unwrap())
+            },
+        )
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F>
+    for MembershipChip<F, N, N_BYTES>
+{
+    type Config = MembershipConfig<F, N, N_BYTES>;
+    /// The order of the elements and whether they may repeat.
+    type Params = (SortOrder, bool);
+    /// The cells of the sorted elements and of the value.
+    type Input = ([AssignedCell<F, F>; N], AssignedCell<F, F>);
+    type Output = ();
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, allow_duplicates): (SortOrder, bool),
+    ) -> Self::Config {
+        MembershipChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            order,
+            allow_duplicates,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        (sorted, x): Self::Input,
+    ) -> Result<(), Error> {
+        // the chip witnesses its private elements, which are the input cells
+        MembershipChip::construct(config.clone())
+            .assign_from(
+                layouter,
+                std::array::from_fn(|i| known_value(&sorted[i])),
+                &sorted,
+                known_value(&x),
+                SortInputs::Cells(&[x]),
+                0,
+            )
+            .map(|_| ())
+    }
+}
+
+/// Config for proving that a public value is not in a private array of N
+/// sorted elements, each compared as an N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct NonMembershipConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // N sorted elements, the one-hot gap of the value and the value with its
+    // neighbours on the first three rows
+    pub advice: [Column<Advice>; N],
+    pub master_selector: Selector,
+    pub instance: Column<Instance>,
+    /// The order of the sorted elements.
+    pub order: SortOrder,
+    /// If set, adjacent elements may be equal.
+    pub allow_duplicates: bool,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // the value and its neighbours, which may not fit in the N columns
+    neighbours: [Column<Advice>; 3],
+    // the two neighbour comparisons and N - 1 adjacent comparisons
+    lt_selectors: Vec<Selector>,
+    lt_configs: Vec<LtConfig<F, N_BYTES>>,
+}
+
+/// Chip that proves a public value is not an element of a private sorted
+/// array, by witnessing the gap between the two adjacent elements that the
+/// value strictly falls between. The gaps before the first and after the last
+/// element only have one neighbour.
+#[derive(Debug, Clone)]
+pub struct NonMembershipChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: NonMembershipConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> NonMembershipChip<F, N, N_BYTES> {
+    pub fn construct(config: NonMembershipConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        u8_table: U8Table,
+    ) -> NonMembershipConfig<F, N, N_BYTES> {
+        assert!(N > 1, "nonMembership needs at least two elements");
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let master_selector = meta_cs.selector();
+        let mut lt_selectors = Vec::with_capacity(N + 1);
+        for _i in 0..N + 1 {
+            lt_selectors.push(meta_cs.complex_selector());
+        }
+
+        let mut lt_configs = Vec::with_capacity(N + 1);
+        let mut advice_vec = advice.to_vec();
+        for _i in N..(N_BYTES + 1).max(3) {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        let neighbours: [Column<Advice>; 3] = advice_vec[..3].try_into().unwrap();
+        let [x, lo, hi] = neighbours;
+        let diff: Vec<_> = advice_vec[1..N_BYTES + 1].to_vec();
+        // lo < x on row 3, x < hi on row 4, then the elements from row 5
+        let mut operands = vec![
+            (order.lt_operands(false, lo, x), -1),
+            (order.lt_operands(false, x, hi), -2),
+        ];
+        for i in 0..N - 1 {
+            operands.push((
+                order.lt_operands(allow_duplicates, advice_vec[i], advice_vec[i + 1]),
+                -5 - i as i32,
+            ));
+        }
+        for (i, ((lhs, rhs), rotation)) in operands.into_iter().enumerate() {
+            let lt_config: LtConfig<F, N_BYTES> = LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(lhs, Rotation(rotation)),
+                |meta| meta.query_advice(rhs, Rotation(rotation)),
+                advice_vec[0],
+                diff.clone().try_into().unwrap(),
+                u8_table,
+            );
+            lt_configs.push(lt_config);
+        }
+
+        let expected_lt = if allow_duplicates {
+            Expression::Constant(F::zero())
+        } else {
+            Expression::Constant(F::one())
+        };
+        meta_cs.create_gate("nonMembership", |meta_vc| {
+            //  0  |  1  | ... | N-1    | selectors
+            // s0    s1    ...   s(N-1)  master
+            // gap0  gap1  ...   gap(N-1)
+            // x     lo    hi
+            // lt    diff ..                lt_selectors[0]
+            // lt    diff ..                lt_selectors[1]
+            // lt0   diff0_0 ..             lt_selectors[2]
+            // ...
+            // where gap_g is set if x falls right before s_g, and gap_N is
+            // 1 - sum(gap) if x falls after the last element
+            let s = meta_vc.query_selector(master_selector);
+            let x_lo = meta_vc.query_advice(lo, Rotation(2));
+            let x_hi = meta_vc.query_advice(hi, Rotation(2));
+
+            let mut constraints = Vec::with_capacity(2 * N + 4);
+            let mut sum = 0.expr();
+            let mut lo_expr = 0.expr();
+            let mut hi_expr = 0.expr();
+            let mut prev_element = None;
+            let mut first_gap = None;
+            for column in advice.iter() {
+                let element = meta_vc.query_advice(*column, Rotation::cur());
+                let gap = meta_vc.query_advice(*column, Rotation::next());
+                constraints.push(s.clone() * bool_check(gap.clone()));
+                sum = sum + gap.clone();
+                if let Some(prev_element) = prev_element {
+                    lo_expr = lo_expr + gap.clone() * prev_element;
+                }
+                hi_expr = hi_expr + gap.clone() * element.clone();
+                prev_element = Some(element);
+                first_gap.get_or_insert(gap);
+            }
+            let last_gap = 1.expr() - sum.clone();
+            lo_expr = lo_expr + last_gap.clone() * prev_element.unwrap();
+            constraints.push(s.clone() * bool_check(sum));
+            constraints.push(s.clone() * (x_lo - lo_expr));
+            constraints.push(s.clone() * (x_hi - hi_expr));
+
+            // lo < x unless x is before the first element, x < hi unless x
+            // is after the last element
+            constraints.push(
+                s.clone()
+                    * (1.expr() - first_gap.unwrap())
+                    * (lt_configs[0].is_lt(meta_vc, Some(Rotation(3))) - 1.expr()),
+            );
+            constraints.push(
+                s.clone()
+                    * (1.expr() - last_gap)
+                    * (lt_configs[1].is_lt(meta_vc, Some(Rotation(4))) - 1.expr()),
+            );
+
+            for (i, lt_config) in lt_configs[2..].iter().enumerate() {
+                constraints.push(
+                    s.clone()
+                        * (lt_config.is_lt(meta_vc, Some(Rotation(i as i32 + 5)))
+                            - expected_lt.clone()),
+                );
+            }
+            constraints
+        });
+
+        NonMembershipConfig {
+            advice,
+            master_selector,
+            instance,
+            order,
+            allow_duplicates,
+            u8_table,
+            neighbours,
+            lt_configs,
+            lt_selectors,
+        }
+    }
+
+    /// Witnesses the private sorted elements, reads the value from instance
+    /// row `row`, and returns the element cells.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        sorted: [F; N],
+        x: F,
+        row: usize,
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, sorted, &[], x, inputs, row)
+    }
+
+    // Witnesses the private sorted elements, constrained to equal the cells
+    // of a gadget input if any, reads the value from the input `x_index`,
+    // and returns the element cells.
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sorted: [F; N],
+        sorted_cells: &[AssignedCell<F, F>],
+        x: F,
+        inputs: SortInputs<F>,
+        x_index: usize,
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        let [x_column, lo_column, hi_column] = self.config.neighbours;
+        // the number of elements before x is the gap it falls into
+        let gap = sorted
+            .iter()
+            .filter(|element| self.config.order.precedes(*element, &x))
+            .count();
+        let lo = if gap > 0 { sorted[gap - 1] } else { F::zero() };
+        let hi = if gap < N { sorted[gap] } else { F::zero() };
+
+        layouter.assign_region(
+            || "non-membership",
+            |mut region| {
+                self.config.master_selector.enable(&mut region, 0)?;
+
+                let mut element_cells = Vec::with_capacity(N);
+                for (i, (column, element)) in self.config.advice.iter().zip(sorted).enumerate() {
+                    element_cells.push(region.assign_advice(
+                        || format!("element[{}]", i),
+                        *column,
+                        0,
+                        || Value::known(element),
+                    )?);
+                    region.assign_advice(
+                        || format!("gap[{}]", i),
+                        *column,
+                        1,
+                        || Value::known(F::from(gap == i)),
+                    )?;
+                }
+                inputs.assign(&mut region, x_index, x_column, 2)?;
+                region.assign_advice(|| "lo", lo_column, 2, || Value::known(lo))?;
+                region.assign_advice(|| "hi", hi_column, 2, || Value::known(hi))?;
+
+                // lt chips
+                for (i, lt_selector) in self.config.lt_selectors.iter().enumerate() {
+                    lt_selector.enable(&mut region, i + 3)?;
+                }
+                let mut operands = vec![
+                    self.config.order.lt_operands(false, lo, x),
+                    self.config.order.lt_operands(false, x, hi),
+                ];
+                for i in 0..N - 1 {
+                    operands.push(self.config.order.lt_operands(
+                        self.config.allow_duplicates,
+                        sorted[i],
+                        sorted[i + 1],
+                    ));
+                }
+                for (i, (lt_config, (lhs, rhs))) in
+                    self.config.lt_configs.iter().zip(operands).enumerate()
+                {
+                    let lt_chip = LtChip::construct(*lt_config);
+                    lt_chip.assign(&mut region, i + 3, lhs, rhs)?;
+                }
+                constrain_inputs(&mut region, sorted_cells, &element_cells)?;
+                Ok(element_cells.try_into().unwrap())
+            },
+        )
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F>
+    for NonMembershipChip<F, N, N_BYTES>
+{
+    type Config = NonMembershipConfig<F, N, N_BYTES>;
+    /// The order of the elements and whether they may repeat.
+    type Params = (SortOrder, bool);
+    /// The cells of the sorted elements and of the value.
+    type Input = ([AssignedCell<F, F>; N], AssignedCell<F, F>);
+    type Output = ();
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, allow_duplicates): (SortOrder, bool),
+    ) -> Self::Config {
+        NonMembershipChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            order,
+            allow_duplicates,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        (sorted, x): Self::Input,
+    ) -> Result<(), Error> {
+        // the chip witnesses its private elements, which are the input cells
+        NonMembershipChip::construct(config.clone())
+            .assign_from(
+                layouter,
+                std::array::from_fn(|i| known_value(&sorted[i])),
+                &sorted,
+                known_value(&x),
+                SortInputs::Cells(&[x]),
+                0,
+            )
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        MembershipChip, MembershipConfig, NonMembershipChip, NonMembershipConfig, SortOrder,
+    };
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct MembershipCircuit<F> {
+        sorted: [F; 4],
+        x: F,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MembershipCircuit<F> {
+        type Config = MembershipConfig<F, 4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let u8_table = U8Table::configure(meta);
+            MembershipChip::configure(meta, advice, instance, SortOrder::Ascending, true, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = MembershipChip::construct(config);
+            chip.assign(layouter.namespace(|| "all"), self.sorted, self.x, 0)?;
+            Ok(())
+        }
+    }
+
+    fn try_membership(sorted: [u64; 4], x: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = MembershipCircuit {
+            sorted: sorted.map(Fp::from),
+            x: Fp::from(x),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(x)]]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn membership() {
+        // ok
+        assert_eq!(try_membership([1, 3, 5, 7], 1), Ok(()));
+        assert_eq!(try_membership([1, 3, 5, 7], 5), Ok(()));
+        assert_eq!(try_membership([2, 2, 4, 4], 4), Ok(()));
+        // error: not an element
+        assert!(try_membership([1, 3, 5, 7], 4).is_err());
+        // error: not sorted
+        assert!(try_membership([3, 1, 5, 7], 5).is_err());
+    }
+
+    #[derive(Default)]
+    struct NonMembershipCircuit<F> {
+        sorted: [F; 4],
+        x: F,
+    }
+
+    impl<F: FieldExt> Circuit<F> for NonMembershipCircuit<F> {
+        type Config = NonMembershipConfig<F, 4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let u8_table = U8Table::configure(meta);
+            NonMembershipChip::configure(
+                meta,
+                advice,
+                instance,
+                SortOrder::Ascending,
+                true,
+                u8_table,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = NonMembershipChip::construct(config);
+            chip.assign(layouter.namespace(|| "all"), self.sorted, self.x, 0)?;
+            Ok(())
+        }
+    }
+
+    fn try_non_membership(sorted: [u64; 4], x: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = NonMembershipCircuit {
+            sorted: sorted.map(Fp::from),
+            x: Fp::from(x),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(x)]]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn non_membership() {
+        // ok
+        assert_eq!(try_non_membership([3, 5, 7, 9], 4), Ok(()));
+        assert_eq!(try_non_membership([3, 5, 7, 9], 1), Ok(()));
+        assert_eq!(try_non_membership([3, 5, 7, 9], 10), Ok(()));
+        assert_eq!(try_non_membership([2, 2, 6, 6], 4), Ok(()));
+        // error: an element
+        assert!(try_non_membership([3, 5, 7, 9], 3).is_err());
+        assert!(try_non_membership([3, 5, 7, 9], 7).is_err());
+        assert!(try_non_membership([3, 5, 7, 9], 9).is_err());
+        // error: not sorted
+        assert!(try_non_membership([3, 7, 5, 9], 6).is_err());
+    }
+}
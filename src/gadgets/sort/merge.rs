@@ -0,0 +1,318 @@
+use super::{
+    network::{merge_comparators, CompareSwapChip, CompareSwapConfig},
+    SortOrder,
+};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for merging two sorted arrays, each element compared as an
+/// N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct MergeConfig<F: FieldExt, const N_BYTES: usize> {
+    // the merged outputs, one per row
+    pub merged: Column<Advice>,
+    /// The order of both the inputs and the merged outputs.
+    pub order: SortOrder,
+    /// If set, adjacent merged outputs may be equal.
+    pub allow_duplicates: bool,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // the compare-and-swap rows merging the inputs, and the adjacent
+    // comparison of the merged outputs
+    swap_config: CompareSwapConfig<F, N_BYTES>,
+    merge_selector: Selector,
+    lt_config: LtConfig<F, N_BYTES>,
+}
+
+/// Chip that merges two arrays, already constrained to be sorted, into one
+/// sorted array. The inputs are merged by compare-and-swap rows, so the
+/// merged outputs are the multiset union of the inputs, and adjacent merged
+/// outputs are constrained to be ordered.
+#[derive(Debug, Clone)]
+pub struct MergeChip<F: FieldExt, const N_BYTES: usize> {
+    config: MergeConfig<F, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> MergeChip<F, N_BYTES> {
+    pub fn construct(config: MergeConfig<F, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        merged: Column<Advice>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        u8_table: U8Table,
+    ) -> MergeConfig<F, N_BYTES> {
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        meta_cs.enable_equality(merged);
+        let merge_selector = meta_cs.complex_selector();
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let (lhs, rhs) = order.lt_operands(allow_duplicates, Rotation::cur(), Rotation::next());
+        let lt_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(merge_selector),
+            |meta| meta.query_advice(merged, lhs),
+            |meta| meta.query_advice(merged, rhs),
+            lt,
+            diff,
+            u8_table,
+        );
+        // the compare-and-swap rows share the lt and diff columns
+        let swap = [
+            merged,
+            meta_cs.advice_column(),
+            meta_cs.advice_column(),
+            meta_cs.advice_column(),
+        ];
+        let swap_config = CompareSwapChip::configure(meta_cs, swap, lt, diff, order, u8_table);
+
+        let expected_lt = if allow_duplicates {
+            Expression::Constant(F::zero())
+        } else {
+            Expression::Constant(F::one())
+        };
+        meta_cs.create_gate("merge", |meta_vc| {
+            // merged | lt  | diff ..          | selectors
+            // o0       lt0   diff0_0 ..         merge_selector
+            // o1       lt1   diff1_0 ..         merge_selector
+            // ...
+            // o(L-1)
+            let s = meta_vc.query_selector(merge_selector);
+            vec![s * (lt_config.is_lt(meta_vc, None) - expected_lt)]
+        });
+
+        MergeConfig {
+            merged,
+            order,
+            allow_duplicates,
+            u8_table,
+            swap_config,
+            merge_selector,
+            lt_config,
+        }
+    }
+
+    /// Merges the sorted `lhs` and `rhs` cells, given along with their
+    /// values, and returns the merged output cells.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &[(AssignedCell<F, F>, F)],
+        rhs: &[(AssignedCell<F, F>, F)],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let swap_chip = CompareSwapChip::construct(self.config.swap_config.clone());
+        let comparators = merge_comparators(vec![lhs.len(), rhs.len()]);
+        let (wires, values) = layouter.assign_region(
+            || "merge network",
+            |mut region| {
+                let (mut wires, mut values): (Vec<_>, Vec<_>) =
+                    lhs.iter().chain(rhs.iter()).cloned().unzip();
+                swap_chip.assign(&mut region, 0, &mut wires, &mut values, &comparators)?;
+                Ok((wires, values))
+            },
+        )?;
+
+        layouter.assign_region(
+            || "merge",
+            |mut region| {
+                let mut merged_cells = Vec::with_capacity(wires.len());
+                for (row, cell) in wires.iter().enumerate() {
+                    merged_cells.push(cell.copy_advice(
+                        || format!("merged[{}]", row),
+                        &mut region,
+                        self.config.merged,
+                        row,
+                    )?);
+                }
+
+                let lt_chip = LtChip::construct(self.config.lt_config);
+                for row in 1..values.len() {
+                    self.config.merge_selector.enable(&mut region, row - 1)?;
+                    let (lhs, rhs) = self.config.order.lt_operands(
+                        self.config.allow_duplicates,
+                        values[row - 1],
+                        values[row],
+                    );
+                    lt_chip.assign(&mut region, row - 1, lhs, rhs)?;
+                }
+                Ok(merged_cells)
+            },
+        )
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for MergeChip<F, N_BYTES> {
+    type Config = MergeConfig<F, N_BYTES>;
+    /// The order of the inputs and outputs and whether outputs may repeat.
+    type Params = (SortOrder, bool);
+    /// The cells of the sorted lhs and rhs.
+    type Input = (Vec<AssignedCell<F, F>>, Vec<AssignedCell<F, F>>);
+    /// The merged output cells.
+    type Output = Vec<AssignedCell<F, F>>;
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, allow_duplicates): (SortOrder, bool),
+    ) -> Self::Config {
+        let [merged] = columns.advice::<1>(0);
+        MergeChip::configure(meta, merged, order, allow_duplicates, columns.u8_table)
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        (lhs, rhs): Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let with_values = |cells: Vec<AssignedCell<F, F>>| -> Vec<_> {
+            cells
+                .into_iter()
+                .map(|cell| {
+                    let value = known_value(&cell);
+                    (cell, value)
+                })
+                .collect()
+        };
+        MergeChip::construct(config.clone()).assign(layouter, &with_values(lhs), &with_values(rhs))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MergeChip, MergeConfig, SortOrder};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+    };
+
+    #[derive(Clone, Debug)]
+    struct MergeCircuitConfig<F: FieldExt> {
+        input: Column<Advice>,
+        instance: Column<Instance>,
+        merge: MergeConfig<F, 8>,
+    }
+
+    #[derive(Default)]
+    struct MergeCircuit<F> {
+        lhs: [F; 3],
+        rhs: [F; 4],
+    }
+
+    impl<F: FieldExt> Circuit<F> for MergeCircuit<F> {
+        type Config = MergeCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let input = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(input);
+            meta.enable_equality(instance);
+            let merged = meta.advice_column();
+            let u8_table = U8Table::configure(meta);
+            MergeCircuitConfig {
+                input,
+                instance,
+                merge: MergeChip::configure(meta, merged, SortOrder::Ascending, true, u8_table),
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.merge.u8_table.load(&mut layouter)?;
+            let chip = MergeChip::construct(config.merge);
+
+            // the sorted inputs are read from the instance column
+            let (lhs, rhs) = layouter.assign_region(
+                || "inputs",
+                |mut region| {
+                    let mut cells = vec![];
+                    for (i, value) in self.lhs.iter().chain(self.rhs.iter()).enumerate() {
+                        let cell = region.assign_advice_from_instance(
+                            || format!("instance({})", i),
+                            config.instance,
+                            i,
+                            config.input,
+                            i,
+                        )?;
+                        cells.push((cell, *value));
+                    }
+                    let rhs = cells.split_off(self.lhs.len());
+                    Ok((cells, rhs))
+                },
+            )?;
+
+            let merged_cells = chip.assign(layouter.namespace(|| "merge"), &lhs, &rhs)?;
+
+            for (i, cell) in merged_cells.iter().enumerate() {
+                layouter.constrain_instance(cell.cell(), config.instance, i + 7)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_merge(lhs: [u64; 3], rhs: [u64; 4], merged: [u64; 7]) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = MergeCircuit {
+            lhs: lhs.map(Fp::from),
+            rhs: rhs.map(Fp::from),
+        };
+        let instance = lhs
+            .iter()
+            .chain(rhs.iter())
+            .chain(merged.iter())
+            .map(|v| Fp::from(*v));
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn merge_sorted_arrays() {
+        // ok
+        assert_eq!(
+            try_merge([1, 4, 6], [2, 3, 7, 8], [1, 2, 3, 4, 6, 7, 8]),
+            Ok(())
+        );
+        assert_eq!(
+            try_merge([2, 2, 5], [1, 2, 5, 9], [1, 2, 2, 2, 5, 5, 9]),
+            Ok(())
+        );
+        // error: not interleaved
+        assert!(try_merge([1, 4, 6], [2, 3, 7, 8], [1, 4, 6, 2, 3, 7, 8]).is_err());
+        // error: not the union of the inputs
+        assert!(try_merge([1, 4, 6], [2, 3, 7, 8], [1, 2, 3, 4, 5, 7, 8]).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use super::SortInputs;
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::{bool_check, sum, Expr},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance, Selector, VirtualCells},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for the minimum and maximum of N elements, each compared as an
+/// N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct MinMaxConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // every element next to the minimum and the maximum
+    pub advice: [Column<Advice>; 3],
+    pub instance: Column<Instance>,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // whether every element is the selected minimum and maximum, which are
+    // one-hot over the N rows
+    min_selected: Column<Advice>,
+    max_selected: Column<Advice>,
+    first_selector: Selector,
+
+    // N comparisons against the minimum and against the maximum
+    selector: Selector,
+    min_lt_config: LtConfig<F, N_BYTES>,
+    max_lt_config: LtConfig<F, N_BYTES>,
+}
+
+/// Chip that constrains the minimum and the maximum of N elements. Both are
+/// selected among the inputs by a one-hot column, the minimum is at most
+/// every element and the maximum is at least every element.
+#[derive(Debug, Clone)]
+pub struct MinMaxChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: MinMaxConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> MinMaxChip<F, N, N_BYTES> {
+    pub fn construct(config: MinMaxConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        instance: Column<Instance>,
+        u8_table: U8Table,
+    ) -> MinMaxConfig<F, N, N_BYTES> {
+        assert!(N > 0, "minMax needs at least one element");
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let [value, min, max] = advice;
+        let min_selected = meta_cs.advice_column();
+        let max_selected = meta_cs.advice_column();
+        let first_selector = meta_cs.selector();
+        let selector = meta_cs.complex_selector();
+
+        // value < min
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let min_lt_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            |meta| meta.query_advice(min, Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+        // max < value
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let max_lt_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(max, Rotation::cur()),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        meta_cs.create_gate("minMax", |meta_vc| {
+            // value | min | max | min_selected | max_selected | selectors
+            // x0      min   max   smin0          smax0          selector, first
+            // ...
+            // x(N-1)  min   max   smin(N-1)      smax(N-1)      selector
+            let s = meta_vc.query_selector(selector);
+            let value = meta_vc.query_advice(value, Rotation::cur());
+            let min = meta_vc.query_advice(min, Rotation::cur());
+            let max = meta_vc.query_advice(max, Rotation::cur());
+            let min_selected = meta_vc.query_advice(min_selected, Rotation::cur());
+            let max_selected = meta_vc.query_advice(max_selected, Rotation::cur());
+            vec![
+                s.clone() * min_lt_config.is_lt(meta_vc, None),
+                s.clone() * max_lt_config.is_lt(meta_vc, None),
+                // the selected element is the minimum, or the maximum
+                s.clone() * bool_check(min_selected.clone()),
+                s.clone() * bool_check(max_selected.clone()),
+                s.clone() * min_selected * (value.clone() - min),
+                s * max_selected * (value - max),
+            ]
+        });
+
+        // The minimum and the maximum are selected rather than copied from
+        // the inputs, as copies are fixed at keygen.
+        meta_cs.create_gate("minMax selection", |meta_vc| {
+            let first = meta_vc.query_selector(first_selector);
+            let query_rows = |meta_vc: &mut VirtualCells<F>, column| -> Vec<_> {
+                (0..N)
+                    .map(|row| meta_vc.query_advice(column, Rotation(row as i32)))
+                    .collect()
+            };
+            let min_selected = query_rows(meta_vc, min_selected);
+            let max_selected = query_rows(meta_vc, max_selected);
+            vec![
+                first.clone() * (sum::expr(&min_selected) - 1.expr()),
+                first * (sum::expr(&max_selected) - 1.expr()),
+            ]
+        });
+
+        MinMaxConfig {
+            advice,
+            instance,
+            u8_table,
+            min_selected,
+            max_selected,
+            first_selector,
+            selector,
+            min_lt_config,
+            max_lt_config,
+        }
+    }
+
+    /// Assigns the inputs read from the instance column, whose values are
+    /// `values`, and returns the (min, max) cells.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        values: [F; N],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, inputs, values)
+    }
+
+    // Assigns the inputs, whose values are `values`.
+    #[allow(clippy::type_complexity)]
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: SortInputs<F>,
+        values: [F; N],
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let [value, min, max] = self.config.advice;
+        let mut min_index = 0;
+        let mut max_index = 0;
+        for (i, x) in values.iter().enumerate() {
+            if *x < values[min_index] {
+                min_index = i;
+            }
+            if *x > values[max_index] {
+                max_index = i;
+            }
+        }
+
+        layouter.assign_region(
+            || "min max",
+            |mut region| {
+                for i in 0..N {
+                    inputs.assign(&mut region, i, value, i)?;
+                }
+
+                // the minimum and the maximum on every row, the first ones
+                // assigned and the others copied from them
+                self.config.first_selector.enable(&mut region, 0)?;
+                let min_lt_chip = LtChip::construct(self.config.min_lt_config);
+                let max_lt_chip = LtChip::construct(self.config.max_lt_config);
+                let mut min_cells: Vec<AssignedCell<F, F>> = Vec::with_capacity(N);
+                let mut max_cells: Vec<AssignedCell<F, F>> = Vec::with_capacity(N);
+                for (i, x) in values.iter().enumerate() {
+                    self.config.selector.enable(&mut region, i)?;
+                    if i == 0 {
+                        min_cells.push(region.assign_advice(
+                            || "min[0]",
+                            min,
+                            0,
+                            || Value::known(values[min_index]),
+                        )?);
+                        max_cells.push(region.assign_advice(
+                            || "max[0]",
+                            max,
+                            0,
+                            || Value::known(values[max_index]),
+                        )?);
+                    } else {
+                        min_cells.push(min_cells[0].copy_advice(
+                            || format!("min[{}]", i),
+                            &mut region,
+                            min,
+                            i,
+                        )?);
+                        max_cells.push(max_cells[0].copy_advice(
+                            || format!("max[{}]", i),
+                            &mut region,
+                            max,
+                            i,
+                        )?);
+                    }
+                    region.assign_advice(
+                        || format!("min selected[{}]", i),
+                        self.config.min_selected,
+                        i,
+                        || Value::known(F::from((i == min_index) as u64)),
+                    )?;
+                    region.assign_advice(
+                        || format!("max selected[{}]", i),
+                        self.config.max_selected,
+                        i,
+                        || Value::known(F::from((i == max_index) as u64)),
+                    )?;
+                    min_lt_chip.assign(&mut region, i, *x, values[min_index])?;
+                    max_lt_chip.assign(&mut region, i, values[max_index], *x)?;
+                }
+
+                Ok((min_cells.swap_remove(0), max_cells.swap_remove(0)))
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F> for MinMaxChip<F, N, N_BYTES> {
+    type Config = MinMaxConfig<F, N, N_BYTES>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; N];
+    /// The cells of (min, max).
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        MinMaxChip::configure(meta, columns.advice(0), columns.instance, columns.u8_table)
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let values = std::array::from_fn(|i| known_value(&input[i]));
+        MinMaxChip::construct(config.clone()).assign_from(
+            layouter,
+            SortInputs::Cells(&input),
+            values,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MinMaxChip, MinMaxConfig};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct MinMaxCircuit<F> {
+        values: [F; 5],
+    }
+
+    impl<F: FieldExt> Circuit<F> for MinMaxCircuit<F> {
+        type Config = MinMaxConfig<F, 5, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let u8_table = U8Table::configure(meta);
+            MinMaxChip::configure(meta, advice, instance, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = MinMaxChip::construct(config);
+
+            let (min, max) = chip.assign(layouter.namespace(|| "all"), self.values)?;
+            chip.expose_public(layouter.namespace(|| "min"), &min, 5)?;
+            chip.expose_public(layouter.namespace(|| "max"), &max, 6)?;
+
+            Ok(())
+        }
+    }
+
+    fn try_min_max(values: [u64; 5], min: u64, max: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = MinMaxCircuit {
+            values: values.map(Fp::from),
+        };
+        let instance = values.into_iter().chain([min, max]).map(Fp::from);
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn min_max() {
+        // ok
+        assert_eq!(try_min_max([7, 1, 9, 3, 5], 1, 9), Ok(()));
+        assert_eq!(try_min_max([4, 4, 4, 4, 4], 4, 4), Ok(()));
+        // error
+        assert!(try_min_max([7, 1, 9, 3, 5], 3, 9).is_err());
+        assert!(try_min_max([7, 1, 9, 3, 5], 1, 7).is_err());
+        assert!(try_min_max([7, 1, 9, 3, 5], 0, 10).is_err());
+    }
+}
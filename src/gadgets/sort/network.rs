@@ -0,0 +1,558 @@
+use super::{SortInputs, SortOrder};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::select,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config of a compare-and-swap row, each wire compared as an N_BYTES-byte
+/// value.
+#[derive(Debug, Clone)]
+pub struct CompareSwapConfig<F: FieldExt, const N_BYTES: usize> {
+    /// The order the two swapped wires are put in.
+    pub order: SortOrder,
+
+    // the two wires (a, b), the two swapped wires (first, second) and the
+    // lt(a, b) check
+    swap: [Column<Advice>; 4],
+    swap_selector: Selector,
+    lt_config: LtConfig<F, N_BYTES>,
+}
+
+/// Chip that puts two wires in order, one row per comparator. Its rows can
+/// sort, or merge sorted runs of, wires assigned by any other chip, as the
+/// comparators only depend on the number of wires.
+#[derive(Debug, Clone)]
+pub struct CompareSwapChip<F: FieldExt, const N_BYTES: usize> {
+    config: CompareSwapConfig<F, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> CompareSwapChip<F, N_BYTES> {
+    pub fn construct(config: CompareSwapConfig<F, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the compare-and-swap rows on the four swap columns, the lt
+    /// column and the N_BYTES diff columns.
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        swap: [Column<Advice>; 4],
+        lt: Column<Advice>,
+        diff: [Column<Advice>; N_BYTES],
+        order: SortOrder,
+        u8_table: U8Table,
+    ) -> CompareSwapConfig<F, N_BYTES> {
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        for column in &swap {
+            meta_cs.enable_equality(*column);
+        }
+        let swap_selector = meta_cs.complex_selector();
+        let lt_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(swap_selector),
+            |meta| meta.query_advice(swap[0], Rotation::cur()),
+            |meta| meta.query_advice(swap[1], Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        meta_cs.create_gate("compareSwap", |meta_vc| {
+            //  a  |  b  | first | second | lt  | diff ..   | selectors
+            // a0    b0    first0  second0  lt0   diff0_0 ..  swap_selector
+            // ...
+            // where lt = a < b and (first, second) is (a, b) in order
+            let s = meta_vc.query_selector(swap_selector);
+            let a = meta_vc.query_advice(swap[0], Rotation::cur());
+            let b = meta_vc.query_advice(swap[1], Rotation::cur());
+            let first = meta_vc.query_advice(swap[2], Rotation::cur());
+            let second = meta_vc.query_advice(swap[3], Rotation::cur());
+            let lt = lt_config.is_lt(meta_vc, None);
+
+            let min = select::expr(lt, a.clone(), b.clone());
+            let expected_first = match order {
+                SortOrder::Ascending => min,
+                SortOrder::Descending => a.clone() + b.clone() - min,
+            };
+            vec![
+                s.clone() * (first.clone() - expected_first),
+                s * (first + second - a - b),
+            ]
+        });
+
+        CompareSwapConfig {
+            order,
+            swap,
+            swap_selector,
+            lt_config,
+        }
+    }
+
+    /// Assigns one compare-and-swap row per comparator from the offset on,
+    /// replacing the swapped wires, whose values are `values`, by the cells
+    /// of the swap.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        wires: &mut [AssignedCell<F, F>],
+        values: &mut [F],
+        comparators: &[(usize, usize)],
+    ) -> Result<(), Error> {
+        let lt_chip = LtChip::construct(self.config.lt_config);
+        for (row, (i, j)) in comparators.iter().copied().enumerate() {
+            let offset = offset + row;
+            self.config.swap_selector.enable(region, offset)?;
+
+            let (a, b) = (values[i], values[j]);
+            wires[i].copy_advice(
+                || format!("swap[{}] a", row),
+                region,
+                self.config.swap[0],
+                offset,
+            )?;
+            wires[j].copy_advice(
+                || format!("swap[{}] b", row),
+                region,
+                self.config.swap[1],
+                offset,
+            )?;
+            lt_chip.assign(region, offset, a, b)?;
+
+            let (first, second) = if self.config.order.precedes(&b, &a) {
+                (b, a)
+            } else {
+                (a, b)
+            };
+            wires[i] = region.assign_advice(
+                || format!("swap[{}] first", row),
+                self.config.swap[2],
+                offset,
+                || Value::known(first),
+            )?;
+            wires[j] = region.assign_advice(
+                || format!("swap[{}] second", row),
+                self.config.swap[3],
+                offset,
+                || Value::known(second),
+            )?;
+            values[i] = first;
+            values[j] = second;
+        }
+        Ok(())
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for CompareSwapChip<F, N_BYTES> {
+    type Config = CompareSwapConfig<F, N_BYTES>;
+    type Params = SortOrder;
+    type Input = [AssignedCell<F, F>; 2];
+    /// The cells of the two wires in order.
+    type Output = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        order: SortOrder,
+    ) -> Self::Config {
+        let [lt] = columns.advice::<1>(4);
+        CompareSwapChip::configure(
+            meta,
+            columns.advice(0),
+            lt,
+            columns.advice(5),
+            order,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let [first, second] = layouter.assign_region(
+            || "compare swap gadget",
+            |mut region| {
+                let mut wires = input.clone();
+                let mut values = std::array::from_fn::<_, 2, _>(|i| known_value(&input[i]));
+                CompareSwapChip::construct(config.clone()).assign(
+                    &mut region,
+                    0,
+                    &mut wires,
+                    &mut values,
+                    &[(0, 1)],
+                )?;
+                Ok(wires)
+            },
+        )?;
+        Ok((first, second))
+    }
+}
+
+/// Returns the comparators merging adjacent sorted runs of the given lengths
+/// pairwise until a single run is left. Every two runs are merged by
+/// Batcher's odd-even merge of the runs padded to the same power of two,
+/// the first run at the end of its half and the second at the start of its
+/// own. The padding precedes the first run and follows the second one, so
+/// the comparators of a padding wire never swap and are dropped.
+pub fn merge_comparators(mut runs: Vec<usize>) -> Vec<(usize, usize)> {
+    let mut comparators = vec![];
+    while runs.len() > 1 {
+        let mut start = 0;
+        let mut merged = vec![];
+        for pair in runs.chunks(2) {
+            if let [m, n] = *pair {
+                let half = m.max(n).next_power_of_two();
+                let mut padded = vec![];
+                odd_even_merge(0, 2 * half, 1, &mut padded);
+                for (i, j) in padded {
+                    if i >= half - m && j < half + n {
+                        comparators.push((start + i + m - half, start + j + m - half));
+                    }
+                }
+            }
+            let len = pair.iter().sum();
+            start += len;
+            merged.push(len);
+        }
+        runs = merged;
+    }
+    comparators
+}
+
+/// Pushes the comparators merging the two sorted halves of the n wires from
+/// lo on, n being a power of two, taking every r-th wire.
+fn odd_even_merge(lo: usize, n: usize, r: usize, comparators: &mut Vec<(usize, usize)>) {
+    let step = 2 * r;
+    if step < n {
+        odd_even_merge(lo, n, step, comparators);
+        odd_even_merge(lo + r, n, step, comparators);
+        comparators.extend((lo + r..lo + n - r).step_by(step).map(|i| (i, i + r)));
+    } else {
+        comparators.push((lo, lo + r));
+    }
+}
+
+/// Config for sorting N elements with a sorting network, each element
+/// compared as an N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct SortNetworkConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // N inputs on the first row
+    pub advice: [Column<Advice>; N],
+    pub instance: Column<Instance>,
+    /// The order of the sorted outputs.
+    pub order: SortOrder,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // one compare-and-swap per row
+    swap_config: CompareSwapConfig<F, N_BYTES>,
+}
+
+/// Chip that sorts N elements with Batcher's odd-even merge sorting network.
+/// Every comparator is a compare-and-swap gate, so the outputs are a
+/// permutation of the inputs by construction and the prover does not supply
+/// the sorted order.
+#[derive(Debug, Clone)]
+pub struct SortNetworkChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: SortNetworkConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> SortNetworkChip<F, N, N_BYTES> {
+    pub fn construct(config: SortNetworkConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        order: SortOrder,
+        u8_table: U8Table,
+    ) -> SortNetworkConfig<F, N, N_BYTES> {
+        assert!(N > 1, "sortNetwork needs at least two elements");
+
+        // the comparator rows need four wire columns, one lt column and
+        // N_BYTES diff columns, which may be more than the N input columns
+        let mut advice_vec = advice.to_vec();
+        for _i in N..N_BYTES + 5 {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        meta_cs.enable_equality(instance);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let swap_config = CompareSwapChip::configure(
+            meta_cs,
+            advice_vec[..4].try_into().unwrap(),
+            advice_vec[4],
+            advice_vec[5..N_BYTES + 5].try_into().unwrap(),
+            order,
+            u8_table,
+        );
+
+        SortNetworkConfig {
+            advice,
+            instance,
+            order,
+            u8_table,
+            swap_config,
+        }
+    }
+
+    /// Returns the (i, j) wires of every comparator of the network, in the
+    /// order that they are applied. This is Batcher's merge exchange (Knuth,
+    /// TAOCP 5.2.2 Algorithm M), which sorts any N.
+    pub fn comparators() -> Vec<(usize, usize)> {
+        let mut comparators = vec![];
+        let t = usize::BITS - (N - 1).leading_zeros();
+        let mut p = 1 << (t - 1);
+        while p > 0 {
+            let (mut q, mut r, mut d) = (1 << (t - 1), 0, p);
+            while d > 0 {
+                for i in 0..N - d {
+                    if i & p == r {
+                        comparators.push((i, i + d));
+                    }
+                }
+                d = q - p;
+                q >>= 1;
+                r = p;
+            }
+            p >>= 1;
+        }
+        comparators
+    }
+
+    /// Assigns the inputs read from the instance column, whose values are
+    /// `values`, and returns the sorted output cells.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        values: [F; N],
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, inputs, values)
+    }
+
+    // Assigns the inputs, whose values are `values`.
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: SortInputs<F>,
+        values: [F; N],
+    ) -> Result<[AssignedCell<F, F>; N], Error> {
+        layouter.assign_region(
+            || "sort network",
+            |mut region| {
+                // unsorted inputs
+                let mut wires = Vec::with_capacity(N);
+                for (i, column) in self.config.advice.iter().enumerate() {
+                    wires.push(inputs.assign(&mut region, i, *column, 0)?);
+                }
+
+                // compare-and-swap rows
+                let mut values = values;
+                let swap_chip = CompareSwapChip::construct(self.config.swap_config.clone());
+                swap_chip.assign(
+                    &mut region,
+                    1,
+                    &mut wires,
+                    &mut values,
+                    &Self::comparators(),
+                )?;
+                Ok(wires.try_into().unwrap())
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F>
+    for SortNetworkChip<F, N, N_BYTES>
+{
+    type Config = SortNetworkConfig<F, N, N_BYTES>;
+    type Params = SortOrder;
+    type Input = [AssignedCell<F, F>; N];
+    /// The cells of the sorted outputs.
+    type Output = [AssignedCell<F, F>; N];
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        order: SortOrder,
+    ) -> Self::Config {
+        SortNetworkChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            order,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let values = std::array::from_fn(|i| known_value(&input[i]));
+        SortNetworkChip::construct(config.clone()).assign_from(
+            layouter,
+            SortInputs::Cells(&input),
+            values,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{merge_comparators, SortNetworkChip, SortNetworkConfig, SortOrder};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct SortNetworkCircuit<F, const DESCENDING: bool> {
+        values: [F; 5],
+    }
+
+    impl<F: FieldExt, const DESCENDING: bool> Circuit<F> for SortNetworkCircuit<F, DESCENDING> {
+        type Config = SortNetworkConfig<F, 5, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let order = if DESCENDING {
+                SortOrder::Descending
+            } else {
+                SortOrder::Ascending
+            };
+            let u8_table = U8Table::configure(meta);
+            SortNetworkChip::configure(meta, advice, instance, order, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = SortNetworkChip::construct(config);
+
+            let output_cells = chip.assign(layouter.namespace(|| "all"), self.values)?;
+
+            for (i, cell) in output_cells.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), cell, i + 5)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_sort_network<const DESCENDING: bool>(
+        values: [u64; 5],
+        sorted: [u64; 5],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = SortNetworkCircuit::<Fp, DESCENDING> {
+            values: values.map(Fp::from),
+        };
+        let instance = values.iter().chain(sorted.iter()).map(|v| Fp::from(*v));
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn network_sorts_zero_one_inputs() {
+        // a network sorts every input iff it sorts every 0/1 input
+        fn sorts_all<const N: usize>() -> bool {
+            (0..1u32 << N).all(|bits| {
+                let mut values: [u32; N] = std::array::from_fn(|i| bits >> i & 1);
+                for (i, j) in SortNetworkChip::<Fp, N, 8>::comparators() {
+                    if values[j] < values[i] {
+                        values.swap(i, j);
+                    }
+                }
+                values.windows(2).all(|w| w[0] <= w[1])
+            })
+        }
+
+        assert!(sorts_all::<2>());
+        assert!(sorts_all::<3>());
+        assert!(sorts_all::<5>());
+        assert!(sorts_all::<8>());
+        assert!(sorts_all::<11>());
+    }
+
+    #[test]
+    fn sort_network() {
+        // ok
+        assert_eq!(
+            try_sort_network::<false>([3, 1, 4, 1, 5], [1, 1, 3, 4, 5]),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort_network::<true>([3, 1, 4, 1, 5], [5, 4, 3, 1, 1]),
+            Ok(())
+        );
+        // error
+        assert!(try_sort_network::<false>([3, 1, 4, 1, 5], [1, 3, 1, 4, 5]).is_err());
+        assert!(try_sort_network::<false>([3, 1, 4, 1, 5], [1, 1, 1, 4, 5]).is_err());
+        assert!(try_sort_network::<true>([3, 1, 4, 1, 5], [1, 1, 3, 4, 5]).is_err());
+    }
+
+    #[test]
+    fn merge_sorted_runs() {
+        for (run, runs) in [(1, 5), (3, 3), (4, 3), (5, 2)] {
+            let len = run * runs;
+            // every run sorted, the runs in reverse order
+            let mut wires: Vec<_> = (0..len).map(|i| (runs - i / run) * run + i % run).collect();
+            for (i, j) in merge_comparators(vec![run; runs]) {
+                if wires[j] < wires[i] {
+                    wires.swap(i, j);
+                }
+            }
+            assert!(wires.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use super::{SortInputs, SortOrder};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::one_hot,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for sorting N (key, value) pairs by their N_BYTES-byte keys.
+#[derive(Debug, Clone)]
+pub struct SortPairsConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // N keys, N values, N sorted keys and N sorted values on the first four rows
+    pub advice: [Column<Advice>; N],
+    pub master_selector: Selector,
+    pub instance: Column<Instance>,
+    /// The order of the sorted keys.
+    pub order: SortOrder,
+    /// If set, pairs may share the same key.
+    pub allow_duplicates: bool,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // N - 1 adjacent key comparisons
+    lt_selectors: Vec<Selector>,
+    lt_configs: Vec<LtConfig<F, N_BYTES>>,
+}
+
+/// Chip that sorts (key, value) pairs by key. The values are carried through
+/// the same permutation as the keys, selected by one permutation matrix, but
+/// only the keys are compared.
+#[derive(Debug, Clone)]
+pub struct SortPairsChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: SortPairsConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> SortPairsChip<F, N, N_BYTES> {
+    pub fn construct(config: SortPairsConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        u8_table: U8Table,
+    ) -> SortPairsConfig<F, N, N_BYTES> {
+        assert!(N > 1, "sortPairs needs at least two elements");
+        assert!(N_BYTES < 32, "compared keys must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        meta_cs.enable_constant(fixed);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let master_selector = meta_cs.selector();
+        let mut lt_selectors = Vec::with_capacity(N - 1);
+        for _i in 0..N - 1 {
+            lt_selectors.push(meta_cs.complex_selector());
+        }
+
+        let mut lt_configs = Vec::with_capacity(N - 1);
+        let mut advice_vec = advice.to_vec();
+        for _i in N..N_BYTES + 1 {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        let diff: Vec<_> = advice_vec[1..N_BYTES + 1].to_vec();
+        for i in 0..N - 1 {
+            // the sorted keys are on row 2, the lt rows start on row 4
+            let (lhs, rhs) = order.lt_operands(allow_duplicates, advice_vec[i], advice_vec[i + 1]);
+            let lt_config: LtConfig<F, N_BYTES> = LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(lhs, Rotation(-2 - i as i32)),
+                |meta| meta.query_advice(rhs, Rotation(-2 - i as i32)),
+                advice_vec[0],
+                diff.clone().try_into().unwrap(),
+                u8_table,
+            );
+            lt_configs.push(lt_config);
+        }
+
+        let expected_lt = if allow_duplicates {
+            Expression::Constant(F::zero())
+        } else {
+            Expression::Constant(F::one())
+        };
+        let mut lt_constraints = Vec::with_capacity(N - 1);
+        meta_cs.create_gate("sortPairs", |meta_vc| {
+            //  0  |  1  | ... | N-1    | selectors
+            // k0    k1    ...   k(N-1)  master
+            // v0    v1    ...   v(N-1)
+            // ok0   ok1   ...   ok(N-1)
+            // ov0   ov1   ...   ov(N-1)
+            // lt0   diff0_0 .. diff0_(N_BYTES-1)   lt_selectors[0]
+            // ...
+            // lt(N-2) diff(N-2)_0 ..                 lt_selectors[N-2]
+            let s = meta_vc.query_selector(master_selector);
+
+            for (i, lt_config) in lt_configs.iter().enumerate() {
+                lt_constraints.push(
+                    s.clone()
+                        * (lt_config.is_lt(meta_vc, Some(Rotation(i as i32 + 4)))
+                            - expected_lt.clone()),
+                );
+            }
+            lt_constraints
+        });
+
+        // The sorted pairs are selected by a permutation matrix rather than
+        // copied from the inputs, as copies are fixed at keygen.
+        meta_cs.create_gate("sortPairs permutation", |meta_vc| {
+            // sel0_0    sel0_1 ...    sel0_(N-1)       row N + 3
+            // ...
+            // sel(N-1)_0 ...          sel(N-1)_(N-1)   row 2N + 2
+            // where sel_i selects the input pair of the sorted pair i
+            let s = meta_vc.query_selector(master_selector);
+            let query_row = |meta_vc: &mut VirtualCells<F>, row: usize| -> Vec<_> {
+                advice
+                    .iter()
+                    .map(|column| meta_vc.query_advice(*column, Rotation(row as i32)))
+                    .collect()
+            };
+            let keys = query_row(meta_vc, 0);
+            let values = query_row(meta_vc, 1);
+            let sorted_keys = query_row(meta_vc, 2);
+            let sorted_values = query_row(meta_vc, 3);
+            let matrix: Vec<_> = (0..N).map(|i| query_row(meta_vc, N + 3 + i)).collect();
+
+            let mut constraints = one_hot::permutation(&matrix);
+            for (i, sel) in matrix.iter().enumerate() {
+                constraints.push(sorted_keys[i].clone() - one_hot::select(sel, &keys));
+                constraints.push(sorted_values[i].clone() - one_hot::select(sel, &values));
+            }
+            constraints
+                .into_iter()
+                .map(|constraint| s.clone() * constraint)
+                .collect::<Vec<_>>()
+        });
+
+        SortPairsConfig {
+            advice,
+            master_selector,
+            instance,
+            order,
+            allow_duplicates,
+            u8_table,
+            lt_configs,
+            lt_selectors,
+        }
+    }
+
+    /// Assigns the pairs read from the instance column (keys on rows 0..N,
+    /// values on rows N..2N) and returns the sorted (keys, values) cells.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        in_indices: [usize; N],
+        keys: [F; N],
+    ) -> Result<([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]), Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, inputs, in_indices, keys)
+    }
+
+    // Assigns the pairs read from the inputs, keys 0..N and values N..2N.
+    #[allow(clippy::type_complexity)]
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: SortInputs<F>,
+        in_indices: [usize; N],
+        keys: [F; N],
+    ) -> Result<([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]), Error> {
+        layouter.assign_region(
+            || "sort pairs",
+            |mut region| {
+                self.config.master_selector.enable(&mut region, 0)?;
+
+                // unsorted keys and values
+                let mut in_keys = Vec::with_capacity(N);
+                let mut in_values = Vec::with_capacity(N);
+                for (i, column) in self.config.advice.iter().enumerate() {
+                    in_keys.push(inputs.assign(&mut region, i, *column, 0)?);
+                    in_values.push(inputs.assign(&mut region, N + i, *column, 1)?);
+                }
+
+                // sorted keys and values, both permuted by the key order, and
+                // the permutation matrix selecting them
+                let mut out_keys = Vec::with_capacity(N);
+                let mut out_values = Vec::with_capacity(N);
+                for (i, in_index) in in_indices.iter().enumerate() {
+                    let (key, value) = (&in_keys[*in_index], &in_values[*in_index]);
+                    out_keys.push(region.assign_advice(
+                        || format!("sort key out[{}]", i),
+                        self.config.advice[i],
+                        2,
+                        || key.value().copied(),
+                    )?);
+                    out_values.push(region.assign_advice(
+                        || format!("sort value out[{}]", i),
+                        self.config.advice[i],
+                        3,
+                        || value.value().copied(),
+                    )?);
+                    for (j, column) in self.config.advice.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("sort pair[{}] one-hot[{}]", i, j),
+                            *column,
+                            N + 3 + i,
+                            || Value::known(F::from((j == *in_index) as u64)),
+                        )?;
+                    }
+                }
+
+                // lt chips
+                for (i, lt_selector) in self.config.lt_selectors.iter().enumerate() {
+                    lt_selector.enable(&mut region, i + 4)?;
+                }
+                for (i, lt_config) in self.config.lt_configs.iter().enumerate() {
+                    let lt_chip = LtChip::construct(*lt_config);
+                    let (lhs, rhs) = self.config.order.lt_operands(
+                        self.config.allow_duplicates,
+                        keys[i],
+                        keys[i + 1],
+                    );
+                    lt_chip.assign(&mut region, i + 4, lhs, rhs)?;
+                }
+                Ok((out_keys.try_into().unwrap(), out_values.try_into().unwrap()))
+            },
+        )
+    }
+
+    /// Sorts the keys in the configured order, returning the input index of
+    /// every sorted pair along with the sorted keys.
+    pub fn sort_indices(&self, keys: [F; N]) -> ([usize; N], [F; N]) {
+        self.config.order.sort_indices(keys)
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F> for SortPairsChip<F, N, N_BYTES> {
+    type Config = SortPairsConfig<F, N, N_BYTES>;
+    /// The order of the keys and whether they may repeat.
+    type Params = (SortOrder, bool);
+    /// The cells of the keys and of their values.
+    type Input = ([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]);
+    /// The cells of the sorted keys and of their values.
+    type Output = ([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]);
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, allow_duplicates): (SortOrder, bool),
+    ) -> Self::Config {
+        SortPairsChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            order,
+            allow_duplicates,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        (keys, values): Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let chip = SortPairsChip::construct(config.clone());
+        let (in_indices, sorted) =
+            chip.sort_indices(std::array::from_fn(|i| known_value(&keys[i])));
+        let inputs: Vec<_> = keys.into_iter().chain(values).collect();
+        chip.assign_from(layouter, SortInputs::Cells(&inputs), in_indices, sorted)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SortOrder, SortPairsChip, SortPairsConfig};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct SortPairsCircuit<F> {
+        keys: [F; 4],
+    }
+
+    impl<F: FieldExt> Circuit<F> for SortPairsCircuit<F> {
+        type Config = SortPairsConfig<F, 4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let u8_table = U8Table::configure(meta);
+            SortPairsChip::configure(
+                meta,
+                advice,
+                instance,
+                fixed,
+                SortOrder::Ascending,
+                true,
+                u8_table,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = SortPairsChip::construct(config);
+
+            let (in_indices, keys) = chip.sort_indices(self.keys);
+            let (out_keys, out_values) =
+                chip.assign(layouter.namespace(|| "all"), in_indices, keys)?;
+
+            for (i, cell) in out_keys.iter().chain(out_values.iter()).enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), cell, i + 8)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_sort_pairs(
+        pairs: [(u64, u64); 4],
+        sorted: [(u64, u64); 4],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = SortPairsCircuit {
+            keys: pairs.map(|(key, _)| Fp::from(key)),
+        };
+        let instance = [pairs, sorted]
+            .iter()
+            .flat_map(|pairs| {
+                let keys = pairs.iter().map(|(key, _)| Fp::from(*key));
+                let values = pairs.iter().map(|(_, value)| Fp::from(*value));
+                keys.chain(values).collect::<Vec<_>>()
+            })
+            .collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn sort_pairs_by_key() {
+        // ok
+        assert_eq!(
+            try_sort_pairs(
+                [(3, 30), (1, 10), (4, 40), (2, 20)],
+                [(1, 10), (2, 20), (3, 30), (4, 40)]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort_pairs(
+                [(7, 1), (5, 9), (7, 2), (5, 8)],
+                [(5, 9), (5, 8), (7, 1), (7, 2)]
+            ),
+            Ok(())
+        );
+        // error: values not carried along with their keys
+        assert!(try_sort_pairs(
+            [(3, 30), (1, 10), (4, 40), (2, 20)],
+            [(1, 30), (2, 10), (3, 40), (4, 20)]
+        )
+        .is_err());
+        // error: keys not sorted
+        assert!(try_sort_pairs(
+            [(3, 30), (1, 10), (4, 40), (2, 20)],
+            [(3, 30), (1, 10), (4, 40), (2, 20)]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,314 @@
+use super::{constrain_inputs, SortInputs};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::{and, not},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for counting the N private elements in a public range, each
+/// compared as an N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct RangeCountConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // every element, the range bounds and the running count of the elements
+    // in the range
+    pub advice: [Column<Advice>; 4],
+    pub instance: Column<Instance>,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // N counting rows, each comparing the element to both bounds
+    step_selector: Selector,
+    below_config: LtConfig<F, N_BYTES>,
+    above_config: LtConfig<F, N_BYTES>,
+}
+
+/// Chip that proves exactly c of N private elements lie in [lo, hi]. The
+/// bounds are read from the instance column, and the count is constrained
+/// by a running sum of the elements that are neither below lo nor above hi.
+#[derive(Debug, Clone)]
+pub struct RangeCountChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: RangeCountConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> RangeCountChip<F, N, N_BYTES> {
+    pub fn construct(config: RangeCountConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        u8_table: U8Table,
+    ) -> RangeCountConfig<F, N, N_BYTES> {
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        meta_cs.enable_constant(fixed);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let [value, lo, hi, count] = advice;
+        let step_selector = meta_cs.complex_selector();
+
+        // value < lo
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let below_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(step_selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            |meta| meta.query_advice(lo, Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+        // hi < value
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let above_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(step_selector),
+            |meta| meta.query_advice(hi, Rotation::cur()),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+
+        meta_cs.create_gate("rangeCount", |meta_vc| {
+            // value | lo | hi | count | selectors
+            //         lo   hi   0
+            // x0      lo   hi   c0      step_selector
+            // ...
+            // x(N-1)  lo   hi   c       step_selector
+            let s = meta_vc.query_selector(step_selector);
+            let below = below_config.is_lt(meta_vc, None);
+            let above = above_config.is_lt(meta_vc, None);
+            let count_step = meta_vc.query_advice(count, Rotation::cur())
+                - meta_vc.query_advice(count, Rotation::prev());
+
+            vec![s * (count_step - and::expr([not::expr(below), not::expr(above)]))]
+        });
+
+        RangeCountConfig {
+            advice,
+            instance,
+            u8_table,
+            step_selector,
+            below_config,
+            above_config,
+        }
+    }
+
+    /// Witnesses the private values, reads the bounds from instance rows 0
+    /// and 1, whose values are `lo` and `hi`, and returns the count cell.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        values: [F; N],
+        lo: F,
+        hi: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let bounds = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, bounds, values, &[], lo, hi)
+    }
+
+    // Witnesses the private values, constrained to equal the cells of a
+    // gadget input if any, reads the bounds from the inputs 0 and 1, and
+    // returns the count cell.
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bounds: SortInputs<F>,
+        values: [F; N],
+        inputs: &[AssignedCell<F, F>],
+        lo: F,
+        hi: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [value, lo_column, hi_column, count] = self.config.advice;
+
+        layouter.assign_region(
+            || "range count",
+            |mut region| {
+                let lo_cell = bounds.assign(&mut region, 0, lo_column, 0)?;
+                let hi_cell = bounds.assign(&mut region, 1, hi_column, 0)?;
+                let mut count_cell =
+                    region.assign_advice_from_constant(|| "count", count, 0, F::zero())?;
+
+                let below_chip = LtChip::construct(self.config.below_config);
+                let above_chip = LtChip::construct(self.config.above_config);
+                let mut in_range = 0u64;
+                let mut value_cells = Vec::with_capacity(N);
+                for (i, x) in values.iter().enumerate() {
+                    let offset = i + 1;
+                    self.config.step_selector.enable(&mut region, offset)?;
+                    value_cells.push(region.assign_advice(
+                        || format!("value[{}]", i),
+                        value,
+                        offset,
+                        || Value::known(*x),
+                    )?);
+                    lo_cell.copy_advice(|| format!("lo[{}]", i), &mut region, lo_column, offset)?;
+                    hi_cell.copy_advice(|| format!("hi[{}]", i), &mut region, hi_column, offset)?;
+                    below_chip.assign(&mut region, offset, *x, lo)?;
+                    above_chip.assign(&mut region, offset, hi, *x)?;
+
+                    in_range += (lo <= *x && *x <= hi) as u64;
+                    count_cell = region.assign_advice(
+                        || format!("count[{}]", i),
+                        count,
+                        offset,
+                        || Value::known(F::from(in_range)),
+                    )?;
+                }
+
+                constrain_inputs(&mut region, inputs, &value_cells)?;
+                Ok(count_cell)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F>
+    for RangeCountChip<F, N, N_BYTES>
+{
+    type Config = RangeCountConfig<F, N, N_BYTES>;
+    type Params = ();
+    /// The cells of the values and of the bounds [lo, hi].
+    type Input = ([AssignedCell<F, F>; N], [AssignedCell<F, F>; 2]);
+    /// The cell of the count of the values in [lo, hi].
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        RangeCountChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        (input, bounds): Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let values = std::array::from_fn(|i| known_value(&input[i]));
+        let [lo, hi] = [0, 1].map(|i| known_value(&bounds[i]));
+        // the chip witnesses its private values, which are the input cells
+        RangeCountChip::construct(config.clone()).assign_from(
+            layouter,
+            SortInputs::Cells(&bounds),
+            values,
+            &input,
+            lo,
+            hi,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RangeCountChip, RangeCountConfig};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct RangeCountCircuit<F> {
+        values: [F; 6],
+        lo: F,
+        hi: F,
+    }
+
+    impl<F: FieldExt> Circuit<F> for RangeCountCircuit<F> {
+        type Config = RangeCountConfig<F, 6, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let u8_table = U8Table::configure(meta);
+            RangeCountChip::configure(meta, advice, instance, fixed, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = RangeCountChip::construct(config);
+
+            let count = chip.assign(layouter.namespace(|| "all"), self.values, self.lo, self.hi)?;
+            chip.expose_public(layouter.namespace(|| "count"), &count, 2)?;
+
+            Ok(())
+        }
+    }
+
+    fn try_range_count(
+        values: [u64; 6],
+        lo: u64,
+        hi: u64,
+        count: u64,
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = RangeCountCircuit {
+            values: values.map(Fp::from),
+            lo: Fp::from(lo),
+            hi: Fp::from(hi),
+        };
+        let instance = vec![Fp::from(lo), Fp::from(hi), Fp::from(count)];
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn range_count() {
+        // ok
+        assert_eq!(try_range_count([1, 5, 3, 9, 7, 5], 3, 7, 4), Ok(()));
+        assert_eq!(try_range_count([1, 5, 3, 9, 7, 5], 10, 20, 0), Ok(()));
+        assert_eq!(try_range_count([1, 5, 3, 9, 7, 5], 0, 9, 6), Ok(()));
+        // error
+        assert!(try_range_count([1, 5, 3, 9, 7, 5], 3, 7, 3).is_err());
+        assert!(try_range_count([1, 5, 3, 9, 7, 5], 3, 7, 6).is_err());
+    }
+}
@@ -0,0 +1,385 @@
+use super::constrain_inputs;
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::{expr_from_bytes, value_to_bytes},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for selecting the K-th smallest of N private elements, each
+/// compared as an N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct SelectKConfig<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize> {
+    // every element, the selected value and the running counts of the
+    // elements that are smaller and at most the selected value
+    pub advice: [Column<Advice>; 4],
+    pub instance: Column<Instance>,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // the little-endian bytes of every element, range checking it
+    bytes: [Column<Advice>; N_BYTES],
+    // N counting rows and the two final count checks
+    step_selector: Selector,
+    below_selector: Selector,
+    reach_selector: Selector,
+    lt_config: LtConfig<F, N_BYTES>,
+    gt_config: LtConfig<F, N_BYTES>,
+}
+
+/// Chip that proves a value is the K-th smallest (1-based) of N private
+/// elements, without sorting them: fewer than K elements are smaller than
+/// the value and at least K elements are at most the value. With K =
+/// (N + 1) / 2 this is the median. Every element is range checked to
+/// N_BYTES bytes, as the comparisons are only sound within that range.
+#[derive(Debug, Clone)]
+pub struct SelectKChip<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize> {
+    config: SelectKConfig<F, N, K, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize>
+    SelectKChip<F, N, K, N_BYTES>
+{
+    pub fn construct(config: SelectKConfig<F, N, K, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        u8_table: U8Table,
+    ) -> SelectKConfig<F, N, K, N_BYTES> {
+        assert!(K > 0 && K <= N, "selectK needs 0 < K <= N");
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        meta_cs.enable_constant(fixed);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let [value, target, count_lt, count_le] = advice;
+        let step_selector = meta_cs.complex_selector();
+        let below_selector = meta_cs.complex_selector();
+        let reach_selector = meta_cs.complex_selector();
+
+        // value < target, on the counting rows and the final count checks
+        let lt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let lt_config = LtChip::configure(
+            meta_cs,
+            |meta| {
+                meta.query_selector(step_selector)
+                    + meta.query_selector(below_selector)
+                    + meta.query_selector(reach_selector)
+            },
+            |meta| meta.query_advice(value, Rotation::cur()),
+            |meta| meta.query_advice(target, Rotation::cur()),
+            lt,
+            diff,
+            u8_table,
+        );
+        // target < value, on the counting rows
+        let gt = meta_cs.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        let gt_config = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(step_selector),
+            |meta| meta.query_advice(target, Rotation::cur()),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            gt,
+            diff,
+            u8_table,
+        );
+
+        // the bytes of every element, on the counting rows
+        let bytes = [(); N_BYTES].map(|_| meta_cs.advice_column());
+        u8_table.range_check(meta_cs, |meta| meta.query_selector(step_selector), &bytes);
+
+        // The counting rows and the final count checks are gates of their
+        // own, as the latter have no element nor previous count to query.
+        meta_cs.create_gate("selectK", |meta_vc| {
+            // value | target | count_lt | count_le | bytes      | selectors
+            //         v        0          0
+            // x0      v        c_lt0      c_le0      bytes0       step_selector
+            // ...
+            // x(N-1)  v        c_lt(N-1)  c_le(N-1)  bytes(N-1)   step_selector
+            // c_lt    K                                           below_selector
+            // c_le    K                                           reach_selector
+            // where bytes_i are the N_BYTES little-endian bytes of x_i
+            let step = meta_vc.query_selector(step_selector);
+            let one = Expression::Constant(F::one());
+            let is_lt = lt_config.is_lt(meta_vc, None);
+            let is_gt = gt_config.is_lt(meta_vc, None);
+            let count_lt_step = meta_vc.query_advice(count_lt, Rotation::cur())
+                - meta_vc.query_advice(count_lt, Rotation::prev());
+            let count_le_step = meta_vc.query_advice(count_le, Rotation::cur())
+                - meta_vc.query_advice(count_le, Rotation::prev());
+            let value_bytes = bytes.map(|c| meta_vc.query_advice(c, Rotation::cur()));
+            let check_value =
+                meta_vc.query_advice(value, Rotation::cur()) - expr_from_bytes(&value_bytes);
+
+            vec![
+                step.clone() * check_value,
+                step.clone() * (count_lt_step - is_lt),
+                step * (count_le_step - (one - is_gt)),
+            ]
+        });
+
+        meta_cs.create_gate("selectK count", |meta_vc| {
+            let below = meta_vc.query_selector(below_selector);
+            let reach = meta_vc.query_selector(reach_selector);
+            let is_lt = lt_config.is_lt(meta_vc, None);
+
+            vec![
+                // count_lt < K
+                below * (is_lt.clone() - Expression::Constant(F::one())),
+                // count_le >= K
+                reach * is_lt,
+            ]
+        });
+
+        SelectKConfig {
+            advice,
+            instance,
+            u8_table,
+            bytes,
+            step_selector,
+            below_selector,
+            reach_selector,
+            lt_config,
+            gt_config,
+        }
+    }
+
+    /// Witnesses the private values and returns the cell of their K-th
+    /// smallest value.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        values: [F; N],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.assign_from(layouter, values, &[])
+    }
+
+    // Like assign, but constrains the private values to equal the cells of
+    // a gadget input, if any.
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: [F; N],
+        inputs: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let [value, target, count_lt, count_le] = self.config.advice;
+        let mut sorted = values;
+        sorted.sort();
+        let selected = sorted[K - 1];
+
+        layouter.assign_region(
+            || "select k",
+            |mut region| {
+                let selected_cell =
+                    region.assign_advice(|| "selected", target, 0, || Value::known(selected))?;
+                let mut count_lt_cell =
+                    region.assign_advice_from_constant(|| "count lt", count_lt, 0, F::zero())?;
+                let mut count_le_cell =
+                    region.assign_advice_from_constant(|| "count le", count_le, 0, F::zero())?;
+
+                // counting rows
+                let lt_chip = LtChip::construct(self.config.lt_config);
+                let gt_chip = LtChip::construct(self.config.gt_config);
+                let (mut lt_count, mut le_count) = (0u64, 0u64);
+                let mut value_cells = Vec::with_capacity(N);
+                for (i, x) in values.iter().enumerate() {
+                    let offset = i + 1;
+                    self.config.step_selector.enable(&mut region, offset)?;
+                    value_cells.push(region.assign_advice(
+                        || format!("value[{}]", i),
+                        value,
+                        offset,
+                        || Value::known(*x),
+                    )?);
+                    // elements wider than N_BYTES keep their low bytes, which
+                    // fails the gate
+                    for (j, (column, byte)) in self
+                        .config
+                        .bytes
+                        .iter()
+                        .zip(value_to_bytes::<F, N_BYTES>(x))
+                        .enumerate()
+                    {
+                        region.assign_advice(
+                            || format!("value[{}] byte {}", i, j),
+                            *column,
+                            offset,
+                            || Value::known(F::from(byte as u64)),
+                        )?;
+                    }
+                    selected_cell.copy_advice(
+                        || format!("selected[{}]", i),
+                        &mut region,
+                        target,
+                        offset,
+                    )?;
+                    lt_chip.assign(&mut region, offset, *x, selected)?;
+                    gt_chip.assign(&mut region, offset, selected, *x)?;
+
+                    lt_count += (*x < selected) as u64;
+                    le_count += (*x <= selected) as u64;
+                    count_lt_cell = region.assign_advice(
+                        || format!("count lt[{}]", i),
+                        count_lt,
+                        offset,
+                        || Value::known(F::from(lt_count)),
+                    )?;
+                    count_le_cell = region.assign_advice(
+                        || format!("count le[{}]", i),
+                        count_le,
+                        offset,
+                        || Value::known(F::from(le_count)),
+                    )?;
+                }
+
+                // final count checks
+                let k = F::from(K as u64);
+                for (offset, selector, count_cell, count) in [
+                    (N + 1, self.config.below_selector, &count_lt_cell, lt_count),
+                    (N + 2, self.config.reach_selector, &count_le_cell, le_count),
+                ] {
+                    selector.enable(&mut region, offset)?;
+                    count_cell.copy_advice(|| "count", &mut region, value, offset)?;
+                    region.assign_advice_from_constant(|| "k", target, offset, k)?;
+                    lt_chip.assign(&mut region, offset, F::from(count), k)?;
+                }
+
+                constrain_inputs(&mut region, inputs, &value_cells)?;
+                Ok(selected_cell)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize> Gadget<F>
+    for SelectKChip<F, N, K, N_BYTES>
+{
+    type Config = SelectKConfig<F, N, K, N_BYTES>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; N];
+    /// The cell of the K-th smallest input.
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        SelectKChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let values = std::array::from_fn(|i| known_value(&input[i]));
+        // the chip witnesses its private values, which are the input cells
+        SelectKChip::construct(config.clone()).assign_from(layouter, values, &input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SelectKChip, SelectKConfig};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct MedianCircuit<F> {
+        values: [F; 5],
+    }
+
+    impl<F: FieldExt> Circuit<F> for MedianCircuit<F> {
+        type Config = SelectKConfig<F, 5, 3, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let u8_table = U8Table::configure(meta);
+            SelectKChip::configure(meta, advice, instance, fixed, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = SelectKChip::construct(config);
+
+            let median = chip.assign(layouter.namespace(|| "all"), self.values)?;
+            chip.expose_public(layouter.namespace(|| "out"), &median, 0)?;
+
+            Ok(())
+        }
+    }
+
+    fn try_median(values: [u128; 5], median: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = MedianCircuit {
+            values: values.map(Fp::from_u128),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![vec![Fp::from(median)]]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn select_median() {
+        // ok
+        assert_eq!(try_median([7, 1, 9, 3, 5], 5), Ok(()));
+        assert_eq!(try_median([4, 9, 4, 1, 4], 4), Ok(()));
+        assert_eq!(try_median([2, 2, 8, 8, 8], 8), Ok(()));
+        // error
+        assert!(try_median([7, 1, 9, 3, 5], 3).is_err());
+        assert!(try_median([7, 1, 9, 3, 5], 7).is_err());
+        // error: an element wider than 8 bytes
+        assert!(try_median([7, 1, 9, 3, (1 << 64) + 5], 7).is_err());
+    }
+}
@@ -0,0 +1,1484 @@
+use super::{constrain_inputs, SortInstanceLayout};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig},
+        Gadget, SharedColumns,
+    },
+    named_constraints,
+    table::U8Table,
+    util::{bool_check, expr_from_bytes, one_hot, sign, sum, value_to_bytes, Expr},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+    },
+    poly::Rotation,
+};
+use rayon::prelude::*;
+use std::{cmp::Ordering, marker::PhantomData};
+
+/// The order that the sorted outputs are constrained to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest element first.
+    Ascending,
+    /// Largest element first.
+    Descending,
+}
+
+impl SortOrder {
+    /// Returns true if `a` has to be placed before `b`.
+    pub fn precedes<T: PartialOrd>(&self, a: &T, b: &T) -> bool {
+        match self {
+            SortOrder::Ascending => a < b,
+            SortOrder::Descending => a > b,
+        }
+    }
+
+    /// Sorts the values in this order, returning the input index of every
+    /// sorted output along with the sorted values. The sort is stable, and
+    /// parallel for large N.
+    pub fn sort_indices<T: PartialOrd + Sync, const N: usize>(
+        &self,
+        values: [T; N],
+    ) -> ([usize; N], [T; N]) {
+        let mut in_indices: [usize; N] = std::array::from_fn(|i| i);
+        in_indices.par_sort_by(|a, b| {
+            if self.precedes(&values[*a], &values[*b]) {
+                Ordering::Less
+            } else if self.precedes(&values[*b], &values[*a]) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+        let mut values = values.map(Some);
+        let sorted = in_indices.map(|i| values[i].take().unwrap());
+        (in_indices, sorted)
+    }
+
+    /// Returns the (lhs, rhs) operands of the Lt check between the adjacent
+    /// outputs `cur` and `next`. A strict order is checked as lt(lhs, rhs),
+    /// a non-strict one as !lt(lhs, rhs) with the operands swapped.
+    pub(super) fn lt_operands<T>(&self, allow_duplicates: bool, cur: T, next: T) -> (T, T) {
+        match (self, allow_duplicates) {
+            (SortOrder::Ascending, false) | (SortOrder::Descending, true) => (cur, next),
+            (SortOrder::Ascending, true) | (SortOrder::Descending, false) => (next, cur),
+        }
+    }
+}
+
+/// Config for sorting N elements, each compared as an N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct SortNConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // N inputs on the first row, N sorted outputs on the second row
+    pub advice: [Column<Advice>; N],
+    pub master_selector: Selector,
+    pub instance: Column<Instance>,
+    /// The order of the sorted outputs.
+    pub order: SortOrder,
+    /// If set, adjacent outputs only need to be ordered non-strictly (e.g.
+    /// out[i] <= out[i + 1]) so that duplicates are provable.
+    pub allow_duplicates: bool,
+    /// If set, the input index of every sorted output (the argsort), which
+    /// the permutation matrix constrains, is exposed with the sorted outputs.
+    pub expose_indices: bool,
+    /// If set, the inputs are private witnesses instead of being read from
+    /// the instance column, so that only the sorted outputs are public. They
+    /// are then range checked to N_BYTES bytes, as the comparisons are only
+    /// sound within that range.
+    pub private_inputs: bool,
+    /// If set, the values are two's complement N_BYTES-byte integers, so that
+    /// e.g. -1 (p - 1) precedes 0.
+    pub signed: bool,
+    /// The u8 table range checking the diff bytes of every comparison, which
+    /// the circuit loads once.
+    pub u8_table: U8Table,
+
+    // one comparison, enabled on the N - 1 rows comparing adjacent outputs
+    lt_selector: Selector,
+    lt_config: LtConfig<F, N_BYTES>,
+    // the adjacent outputs copied to the row of their comparison
+    lt_operands: [Column<Advice>; 2],
+    // one range check per private input, none if the inputs are public
+    range_selector: Option<Selector>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> SortNConfig<F, N, N_BYTES> {
+    /// Returns the offset that maps the values to the unsigned range of the
+    /// comparisons. Signed values are offset by 2^(8 * N_BYTES - 1); as the
+    /// offset cancels out in lhs - rhs, it only changes the witnesses.
+    pub fn offset(&self) -> F {
+        if self.signed {
+            sign::offset(8 * N_BYTES)
+        } else {
+            F::zero()
+        }
+    }
+}
+
+/// The cells of one assignment of the SortNChip.
+#[derive(Debug, Clone)]
+pub struct SortCells<F: FieldExt, const N: usize> {
+    /// The unsorted inputs.
+    pub inputs: [AssignedCell<F, F>; N],
+    /// The sorted outputs.
+    pub outputs: [AssignedCell<F, F>; N],
+    /// The input index of every output, if exposed.
+    pub indices: Option<[AssignedCell<F, F>; N]>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SortNChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: SortNConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> SortNChip<F, N, N_BYTES> {
+    pub fn construct(config: SortNConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        expose_indices: bool,
+        private_inputs: bool,
+        signed: bool,
+        u8_table: U8Table,
+    ) -> SortNConfig<F, N, N_BYTES> {
+        assert!(N > 1, "sortN needs at least two elements");
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+
+        meta_cs.enable_equality(instance);
+        meta_cs.enable_constant(fixed);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let master_selector = meta_cs.selector();
+        let lt_selector = meta_cs.complex_selector();
+
+        // the lt rows need one lt column, N_BYTES diff columns and the two
+        // compared outputs, which may be more than the N columns holding the
+        // elements
+        let mut advice_vec = advice.to_vec();
+        for _i in N..N_BYTES + 3 {
+            let column = meta_cs.advice_column();
+            meta_cs.enable_equality(column);
+            advice_vec.push(column);
+        }
+        let diff: [Column<Advice>; N_BYTES] = advice_vec[1..N_BYTES + 1].try_into().unwrap();
+        let lt_operands = [advice_vec[N_BYTES + 1], advice_vec[N_BYTES + 2]];
+        let lt_config: LtConfig<F, N_BYTES> = LtChip::configure(
+            meta_cs,
+            |meta| meta.query_selector(lt_selector),
+            |meta| meta.query_advice(lt_operands[0], Rotation::cur()),
+            |meta| meta.query_advice(lt_operands[1], Rotation::cur()),
+            advice_vec[0],
+            diff,
+            u8_table,
+        );
+
+        let expected_lt = if allow_duplicates {
+            Expression::Constant(F::zero())
+        } else {
+            Expression::Constant(F::one())
+        };
+        meta_cs.create_gate("sortN", |meta_vc| {
+            //  0  |  1  | ... | N-1    | ...    | ...     | selectors
+            // i0    i1    ...   i(N-1)                      master
+            // o0    o1    ...   o(N-1)
+            // lt0   diff0_0 ..           lhs0     rhs0      lt_selector
+            // ...
+            // lt(N-2) diff(N-2)_0 ..     lhs(N-2) rhs(N-2)  lt_selector
+            // where lhs_i and rhs_i are the outputs o_i and o(i+1), swapped
+            // as the order requires
+            let s = meta_vc.query_selector(lt_selector);
+            vec![s * (lt_config.is_lt(meta_vc, None) - expected_lt)]
+        });
+
+        // The outputs are constrained to be a permutation of the inputs by a
+        // permutation matrix, rather than by copying the input each output
+        // selects: copy constraints are fixed at keygen, so they cannot
+        // depend on the witness.
+        meta_cs.create_gate("sortN permutation", |meta_vc| {
+            //  0  |  1  | ... | N-1
+            // idx0  idx1  ...   idx(N-1)            row N + 1
+            // sel0_0 sel0_1 ... sel0_(N-1)          row N + 2
+            // ...
+            // sel(N-1)_0 ...    sel(N-1)_(N-1)      row 2N + 1
+            // where sel_i is the one-hot encoding of idx_i, every input is
+            // selected exactly once, and o_i == i_idx_i
+            let s = meta_vc.query_selector(master_selector);
+            let inputs: Vec<_> = advice
+                .iter()
+                .map(|column| meta_vc.query_advice(*column, Rotation::cur()))
+                .collect();
+
+            let mut constraints = Vec::with_capacity(N * (N + 4));
+            let mut column_sums = vec![0.expr(); N];
+            for (i, column) in advice.iter().enumerate() {
+                let output = meta_vc.query_advice(*column, Rotation::next());
+                let index = meta_vc.query_advice(*column, Rotation(N as i32 + 1));
+                let one_hot: Vec<_> = advice
+                    .iter()
+                    .map(|column| meta_vc.query_advice(*column, Rotation((N + 2 + i) as i32)))
+                    .collect();
+
+                for (j, bit) in one_hot.iter().enumerate() {
+                    constraints.extend(named_constraints![
+                        format!("sel{}_{} is boolean", i, j) =>
+                            s.clone() * bool_check(bit.clone()),
+                    ]);
+                    column_sums[j] = column_sums[j].clone() + bit.clone();
+                }
+                let index_expr = one_hot::index(&one_hot);
+                let output_expr = one_hot::select(&one_hot, &inputs);
+                constraints.extend(named_constraints![
+                    format!("sel{} selects one input", i) =>
+                        s.clone() * (sum::expr(&one_hot) - 1.expr()),
+                    format!("idx{} is the selected index", i) =>
+                        s.clone() * (index - index_expr),
+                    format!("o{} is the selected input", i) =>
+                        s.clone() * (output - output_expr),
+                ]);
+            }
+            for (j, column_sum) in column_sums.into_iter().enumerate() {
+                constraints.extend(named_constraints![
+                    format!("i{} is selected once", j) => s.clone() * (column_sum - 1.expr()),
+                ]);
+            }
+            constraints
+        });
+
+        // The private inputs are range checked by the bytes of input + offset
+        // in the diff columns, on the N rows after the permutation matrix.
+        // Public inputs are left to the verifier.
+        let range_selector = private_inputs.then(|| {
+            let range_selector = meta_cs.complex_selector();
+            u8_table.range_check(meta_cs, |meta| meta.query_selector(range_selector), &diff);
+            let offset = if signed {
+                sign::offset(8 * N_BYTES)
+            } else {
+                F::zero()
+            };
+            meta_cs.create_gate("sortN range", |meta_vc| {
+                //  0     |  1           | ... | N_BYTES   | selectors
+                // i0       bytes0_0       ...               range_selector
+                // ...
+                // i(N-1)   bytes(N-1)_0   ...               range_selector
+                // on rows 2N + 2 to 3N + 1, where bytes_i are the N_BYTES
+                // little-endian bytes of i_i + offset
+                let s = meta_vc.query_selector(range_selector);
+                let input = meta_vc.query_advice(advice[0], Rotation::cur());
+                let bytes = diff.map(|c| meta_vc.query_advice(c, Rotation::cur()));
+                vec![s * (input + Expression::Constant(offset) - expr_from_bytes(&bytes))]
+            });
+            range_selector
+        });
+
+        SortNConfig {
+            advice,
+            master_selector,
+            instance,
+            order,
+            allow_duplicates,
+            expose_indices,
+            private_inputs,
+            signed,
+            u8_table,
+            lt_selector,
+            lt_config,
+            lt_operands,
+            range_selector,
+        }
+    }
+
+    /// Assigns the inputs, read from the instance column or witnessed if they
+    /// are private, and returns the sorted output cells, along with the input
+    /// index cells of the outputs if the indices are exposed.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        in_indices: [usize; N],
+        values: [F; N],
+    ) -> Result<([AssignedCell<F, F>; N], Option<[AssignedCell<F, F>; N]>), Error> {
+        self.assign_at(layouter, 0, in_indices, values)
+    }
+
+    /// Like `assign`, but reads the public inputs from the instance column
+    /// starting at `instance_offset`, so that the chip can be assigned more
+    /// than once per circuit.
+    #[allow(clippy::type_complexity)]
+    pub fn assign_at(
+        &self,
+        layouter: impl Layouter<F>,
+        instance_offset: usize,
+        in_indices: [usize; N],
+        values: [F; N],
+    ) -> Result<([AssignedCell<F, F>; N], Option<[AssignedCell<F, F>; N]>), Error> {
+        self.assign_cells(layouter, instance_offset, in_indices, values)
+            .map(|cells| (cells.outputs, cells.indices))
+    }
+
+    /// Like `assign_at`, but also returns the input cells, e.g. to commit to
+    /// private inputs.
+    pub fn assign_cells(
+        &self,
+        layouter: impl Layouter<F>,
+        instance_offset: usize,
+        in_indices: [usize; N],
+        values: [F; N],
+    ) -> Result<SortCells<F, N>, Error> {
+        self.assign_cells_from(layouter, instance_offset, in_indices, values, &[])
+    }
+
+    // Like assign_cells, but constrains the private inputs to equal the
+    // cells of a gadget input, if any.
+    pub(crate) fn assign_cells_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        instance_offset: usize,
+        in_indices: [usize; N],
+        values: [F; N],
+        gadget_inputs: &[AssignedCell<F, F>],
+    ) -> Result<SortCells<F, N>, Error> {
+        // the witnesses of the comparisons of adjacent outputs, computed in
+        // parallel, and once rather than on every call of the region closure
+        let offset = self.config.offset();
+        let lt_witnesses: Vec<_> = (0..N - 1)
+            .into_par_iter()
+            .map(|i| {
+                let (lhs, rhs) = self.config.order.lt_operands(
+                    self.config.allow_duplicates,
+                    values[i] + offset,
+                    values[i + 1] + offset,
+                );
+                self.config.lt_config.witness(lhs, rhs)
+            })
+            .collect();
+
+        layouter.assign_region(
+            || "sort",
+            |mut region| {
+                self.config.master_selector.enable(&mut region, 0)?;
+
+                // unsorted inputs
+                let mut in_cells = Vec::with_capacity(N);
+                if self.config.private_inputs {
+                    let mut inputs = [F::zero(); N];
+                    for (value, in_index) in values.iter().zip(in_indices.iter()) {
+                        inputs[*in_index] = *value;
+                    }
+                    for (i, (column, input)) in
+                        self.config.advice.iter().zip(inputs.iter()).enumerate()
+                    {
+                        in_cells.push(region.assign_advice(
+                            || format!("input[{}]", i),
+                            *column,
+                            0,
+                            || Value::known(*input),
+                        )?);
+                    }
+                    constrain_inputs(&mut region, gadget_inputs, &in_cells)?;
+                } else {
+                    for (i, column) in self.config.advice.iter().enumerate() {
+                        in_cells.push(region.assign_advice_from_instance(
+                            || format!("instance({})", instance_offset + i),
+                            self.config.instance,
+                            instance_offset + i,
+                            *column,
+                            0,
+                        )?);
+                    }
+                }
+
+                // sorted outputs, tied to the inputs by the permutation matrix
+                let mut output_cells = Vec::with_capacity(N);
+                for (i, in_index) in in_indices.iter().enumerate() {
+                    let in_cell = &in_cells[*in_index];
+                    output_cells.push(region.assign_advice(
+                        || format!("sort out[{}]", i),
+                        self.config.advice[i],
+                        1,
+                        || in_cell.value().copied(),
+                    )?);
+                }
+
+                // lt chip, comparing every pair of adjacent outputs
+                let lt_chip = LtChip::construct(self.config.lt_config);
+                for (i, lt_witness) in lt_witnesses.iter().enumerate() {
+                    self.config.lt_selector.enable(&mut region, i + 2)?;
+                    let (lhs, rhs) = self.config.order.lt_operands(
+                        self.config.allow_duplicates,
+                        &output_cells[i],
+                        &output_cells[i + 1],
+                    );
+                    for (operand, column) in [lhs, rhs].iter().zip(self.config.lt_operands) {
+                        operand.copy_advice(|| format!("lt[{}]", i), &mut region, column, i + 2)?;
+                    }
+                    lt_chip.assign_witness(&mut region, i + 2, lt_witness)?;
+                }
+
+                let mut cells = SortCells {
+                    inputs: in_cells.try_into().unwrap(),
+                    outputs: output_cells.try_into().unwrap(),
+                    indices: None,
+                };
+
+                // argsort and its permutation matrix
+                let mut index_cells = Vec::with_capacity(N);
+                for (i, in_index) in in_indices.iter().enumerate() {
+                    index_cells.push(region.assign_advice(
+                        || format!("sort index[{}]", i),
+                        self.config.advice[i],
+                        N + 1,
+                        || Value::known(F::from(*in_index as u64)),
+                    )?);
+                    for (j, column) in self.config.advice.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("sort index[{}] one-hot[{}]", i, j),
+                            *column,
+                            N + 2 + i,
+                            || Value::known(F::from((j == *in_index) as u64)),
+                        )?;
+                    }
+                }
+                if self.config.expose_indices {
+                    cells.indices = Some(index_cells.try_into().unwrap());
+                }
+
+                // range checks of the private inputs; inputs wider than
+                // N_BYTES keep their low bytes, which fails the gate
+                if let Some(range_selector) = self.config.range_selector {
+                    for (i, in_cell) in cells.inputs.iter().enumerate() {
+                        let row = 2 * N + 2 + i;
+                        range_selector.enable(&mut region, row)?;
+                        in_cell.copy_advice(
+                            || format!("range input[{}]", i),
+                            &mut region,
+                            self.config.advice[0],
+                            row,
+                        )?;
+                        let bytes = in_cell
+                            .value()
+                            .map(|input| value_to_bytes::<F, N_BYTES>(&(*input + offset)));
+                        for (j, column) in self.config.lt_config.diff.iter().enumerate() {
+                            region.assign_advice(
+                                || format!("range input[{}] byte {}", i, j),
+                                *column,
+                                row,
+                                || bytes.map(|bytes| F::from(bytes[j] as u64)),
+                            )?;
+                        }
+                    }
+                }
+                Ok(cells)
+            },
+        )
+    }
+
+    /// Sorts the values in the configured order, returning the input index of
+    /// every sorted output along with the sorted values.
+    pub fn sort_indices(&self, values: [F; N]) -> ([usize; N], [F; N]) {
+        let offset = self.config.offset();
+        let (in_indices, _) = self.config.order.sort_indices(values.map(|v| v + offset));
+        (in_indices, in_indices.map(|i| values[i]))
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Chip<F> for SortNChip<F, N, N_BYTES> {
+    type Config = SortNConfig<F, N, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// The options a SortNChip is configured with as a gadget, see
+/// `SortNConfig`. The inputs of the gadget are always private cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortOptions {
+    pub order: SortOrder,
+    pub allow_duplicates: bool,
+    pub expose_indices: bool,
+    pub signed: bool,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F> for SortNChip<F, N, N_BYTES> {
+    type Config = SortNConfig<F, N, N_BYTES>;
+    type Params = SortOptions;
+    type Input = [AssignedCell<F, F>; N];
+    type Output = SortCells<F, N>;
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        options: SortOptions,
+    ) -> Self::Config {
+        SortNChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            options.order,
+            options.allow_duplicates,
+            options.expose_indices,
+            true,
+            options.signed,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let chip = SortNChip::construct(config.clone());
+        let values = std::array::from_fn(|i| known_value(&input[i]));
+        let (in_indices, values) = chip.sort_indices(values);
+        // the chip witnesses its private inputs, which are the input cells
+        chip.assign_cells_from(layouter, 0, in_indices, values, &input)
+    }
+}
+
+/// Circuit sorting N values into the order fixed by its const generics. The
+/// inputs are on instance rows 0..N, unless private, followed by the sorted
+/// outputs, and by their input indices if exposed.
+pub struct SortNCircuit<
+    F,
+    const N: usize,
+    const N_BYTES: usize,
+    const ALLOW_DUPLICATES: bool = false,
+    const DESCENDING: bool = false,
+    const EXPOSE_INDICES: bool = false,
+    const PRIVATE_INPUTS: bool = false,
+    const SIGNED: bool = false,
+> {
+    values: [F; N],
+    _marker: PhantomData<F>,
+}
+
+impl<
+        F: FieldExt,
+        const N: usize,
+        const N_BYTES: usize,
+        const ALLOW_DUPLICATES: bool,
+        const DESCENDING: bool,
+        const EXPOSE_INDICES: bool,
+        const PRIVATE_INPUTS: bool,
+        const SIGNED: bool,
+    > Default
+    for SortNCircuit<
+        F,
+        N,
+        N_BYTES,
+        ALLOW_DUPLICATES,
+        DESCENDING,
+        EXPOSE_INDICES,
+        PRIVATE_INPUTS,
+        SIGNED,
+    >
+{
+    fn default() -> Self {
+        Self {
+            values: [F::zero(); N],
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Shape of a SortNCircuit: the number of elements, the number of bytes of
+/// each element and whether the inputs are kept private.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortNParams {
+    pub n: usize,
+    pub n_bytes: usize,
+    pub private_inputs: bool,
+}
+
+impl<
+        F: FieldExt,
+        const N: usize,
+        const N_BYTES: usize,
+        const ALLOW_DUPLICATES: bool,
+        const DESCENDING: bool,
+        const EXPOSE_INDICES: bool,
+        const PRIVATE_INPUTS: bool,
+        const SIGNED: bool,
+    >
+    SortNCircuit<
+        F,
+        N,
+        N_BYTES,
+        ALLOW_DUPLICATES,
+        DESCENDING,
+        EXPOSE_INDICES,
+        PRIVATE_INPUTS,
+        SIGNED,
+    >
+{
+    /// Builds the circuit sorting `values`, failing if `params` is not the
+    /// shape of the circuit. The configuration of this halo2 version takes no
+    /// parameters, so the shape is fixed by the const generics and checked
+    /// here, which lets callers build every shape from the same params.
+    pub fn new(params: SortNParams, values: &[F]) -> Result<Self, Error> {
+        if params != Self::params() {
+            return Err(Error::Synthesis);
+        }
+        Ok(Self {
+            values: values.try_into().map_err(|_| Error::Synthesis)?,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Like `new`, but also fails if `options` are not the ones of the
+    /// circuit, see `SortCircuitBuilder`.
+    pub(super) fn with_options(
+        params: SortNParams,
+        options: SortOptions,
+        values: &[F],
+    ) -> Result<Self, Error> {
+        if options != Self::options() {
+            return Err(Error::Synthesis);
+        }
+        Self::new(params, values)
+    }
+
+    /// Returns the shape of the circuit.
+    pub fn params() -> SortNParams {
+        SortNParams {
+            n: N,
+            n_bytes: N_BYTES,
+            private_inputs: PRIVATE_INPUTS,
+        }
+    }
+
+    /// Returns the options of the circuit.
+    pub fn options() -> SortOptions {
+        SortOptions {
+            order: if DESCENDING {
+                SortOrder::Descending
+            } else {
+                SortOrder::Ascending
+            },
+            allow_duplicates: ALLOW_DUPLICATES,
+            expose_indices: EXPOSE_INDICES,
+            signed: SIGNED,
+        }
+    }
+
+    /// Returns where the public values of the circuit are on its instance
+    /// column.
+    pub fn layout() -> SortInstanceLayout {
+        SortInstanceLayout::new(Self::params(), EXPOSE_INDICES)
+    }
+
+    /// Returns the instance column proving the values of the circuit: the
+    /// inputs unless private, the sorted outputs and the input indices if
+    /// exposed, as laid out by `layout`.
+    pub fn instance(&self) -> Vec<F> {
+        let options = Self::options();
+        let offset = if SIGNED {
+            sign::offset(8 * N_BYTES)
+        } else {
+            F::zero()
+        };
+        let (in_indices, _) = options.order.sort_indices(self.values.map(|v| v + offset));
+
+        let layout = Self::layout();
+        let mut instance = vec![F::zero(); layout.rows()];
+        if let Some(rows) = layout.inputs {
+            instance[rows].copy_from_slice(&self.values);
+        }
+        for (row, in_index) in layout.outputs.zip(in_indices) {
+            instance[row] = self.values[in_index];
+        }
+        if let Some(rows) = layout.indices {
+            for (row, in_index) in rows.zip(in_indices) {
+                instance[row] = F::from(in_index as u64);
+            }
+        }
+        instance
+    }
+}
+
+impl<
+        F: FieldExt,
+        const N: usize,
+        const N_BYTES: usize,
+        const ALLOW_DUPLICATES: bool,
+        const DESCENDING: bool,
+        const EXPOSE_INDICES: bool,
+        const PRIVATE_INPUTS: bool,
+        const SIGNED: bool,
+    > Circuit<F>
+    for SortNCircuit<
+        F,
+        N,
+        N_BYTES,
+        ALLOW_DUPLICATES,
+        DESCENDING,
+        EXPOSE_INDICES,
+        PRIVATE_INPUTS,
+        SIGNED,
+    >
+{
+    type Config = SortNConfig<F, N, N_BYTES>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); N].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let fixed = meta.fixed_column();
+        let options = Self::options();
+        let u8_table = U8Table::configure(meta);
+        SortNChip::configure(
+            meta,
+            advice,
+            instance,
+            fixed,
+            options.order,
+            options.allow_duplicates,
+            options.expose_indices,
+            PRIVATE_INPUTS,
+            options.signed,
+            u8_table,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.u8_table.load(&mut layouter)?;
+        let chip = SortNChip::construct(config);
+
+        let (in_indices, values) = chip.sort_indices(self.values);
+
+        let (output_cells, index_cells) =
+            chip.assign(layouter.namespace(|| "all"), in_indices, values)?;
+
+        let layout = Self::layout();
+        for (output_cell, row) in output_cells.iter().zip(layout.outputs) {
+            chip.expose_public(layouter.namespace(|| "out"), output_cell, row)?;
+        }
+        if let (Some(index_cells), Some(rows)) = (index_cells, layout.indices) {
+            for (index_cell, row) in index_cells.iter().zip(rows) {
+                chip.expose_public(layouter.namespace(|| "index"), index_cell, row)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Circuit that sorts M arrays of N elements with one SortNChip. The inputs
+/// and sorted outputs of array m are on instance rows 2Nm..2Nm+N and
+/// 2Nm+N..2N(m+1).
+pub struct SortBatchCircuit<F, const N: usize, const N_BYTES: usize> {
+    arrays: Vec<[F; N]>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> SortBatchCircuit<F, N, N_BYTES> {
+    pub fn new(arrays: Vec<[F; N]>) -> Self {
+        Self { arrays }
+    }
+
+    /// Returns the instance column of the batch, i.e. the inputs and the
+    /// ascending sorted outputs of every array.
+    pub fn instance(&self) -> Vec<F> {
+        self.arrays
+            .iter()
+            .flat_map(|values| {
+                let (_, sorted) = SortOrder::Ascending.sort_indices(*values);
+                values
+                    .iter()
+                    .chain(sorted.iter())
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Circuit<F>
+    for SortBatchCircuit<F, N, N_BYTES>
+{
+    type Config = SortNConfig<F, N, N_BYTES>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        // the layout depends on the number of arrays
+        Self::new(vec![[F::zero(); N]; self.arrays.len()])
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); N].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let fixed = meta.fixed_column();
+        let u8_table = U8Table::configure(meta);
+        SortNChip::configure(
+            meta,
+            advice,
+            instance,
+            fixed,
+            SortOrder::Ascending,
+            false,
+            false,
+            false,
+            false,
+            u8_table,
+        )
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.u8_table.load(&mut layouter)?;
+        let chip = SortNChip::construct(config);
+
+        for (m, values) in self.arrays.iter().enumerate() {
+            let (in_indices, values) = chip.sort_indices(*values);
+            let (output_cells, _) = chip.assign_at(
+                layouter.namespace(|| format!("array {}", m)),
+                2 * N * m,
+                in_indices,
+                values,
+            )?;
+            for (i, output_cell) in output_cells.iter().enumerate() {
+                chip.expose_public(
+                    layouter.namespace(|| format!("out {}", m)),
+                    output_cell,
+                    2 * N * m + N + i,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SortBatchCircuit, SortNChip, SortNCircuit, SortNConfig, SortNParams, SortOrder};
+    use crate::{
+        cost::CostReport,
+        prover::{self, Backend, Ipa, KeyCache, Proof},
+        table::U8Table,
+        testing,
+        transcript::{PoseidonRead, PoseidonWrite},
+    };
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+        transcript::{Blake2bRead, Challenge255},
+    };
+    use rand_core::OsRng;
+    use std::marker::PhantomData;
+
+    fn try_sort<
+        const N: usize,
+        const N_BYTES: usize,
+        const ALLOW_DUPLICATES: bool,
+        const DESCENDING: bool,
+    >(
+        values: [u128; N],
+        sorted: [u128; N],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = SortNCircuit::<Fp, N, N_BYTES, ALLOW_DUPLICATES, DESCENDING> {
+            values: values.map(Fp::from_u128),
+            _marker: PhantomData,
+        };
+        let instance = values
+            .iter()
+            .chain(sorted.iter())
+            .map(|v| Fp::from_u128(*v));
+        testing::verify(&circuit, vec![instance.collect()])
+    }
+
+    #[test]
+    fn prove_and_verify() {
+        let values = [3, 1, 4, 2].map(Fp::from);
+        let instance: Vec<_> = [3, 1, 4, 2, 1, 2, 3, 4].into_iter().map(Fp::from).collect();
+        let circuit = SortNCircuit::<Fp, 4, 8, false, false> {
+            values,
+            _marker: PhantomData,
+        };
+
+        let (params, pk) = prover::setup(9, &circuit.without_witnesses()).unwrap();
+        let proof = prover::prove(&params, &pk, circuit, &[&instance]).unwrap();
+        assert!(prover::verify(&params, pk.get_vk(), &proof, &[&instance]).is_ok());
+
+        let mut wrong = instance.clone();
+        wrong[4] = Fp::from(2);
+        assert!(prover::verify(&params, pk.get_vk(), &proof, &[&wrong]).is_err());
+    }
+
+    #[test]
+    fn prove_with_another_keygen_witness() {
+        // the copy constraints of the keys are those of the keygen witness,
+        // so the proof of another permutation only verifies if the circuit
+        // does not tie its outputs to its inputs by witness-chosen copies
+        let keygen = SortNCircuit::<Fp, 4, 8, true, false, true> {
+            values: [10, 20, 30, 40].map(Fp::from),
+            _marker: PhantomData,
+        };
+        let (params, pk) = prover::setup(9, &keygen).unwrap();
+
+        for (values, instance) in [
+            ([3, 1, 4, 2], [3, 1, 4, 2, 1, 2, 3, 4, 1, 3, 0, 2]),
+            ([4, 3, 2, 1], [4, 3, 2, 1, 1, 2, 3, 4, 3, 2, 1, 0]),
+            ([2, 1, 2, 1], [2, 1, 2, 1, 1, 1, 2, 2, 1, 3, 0, 2]),
+        ] {
+            let instance: Vec<_> = instance.into_iter().map(Fp::from).collect();
+            let circuit = SortNCircuit::<Fp, 4, 8, true, false, true> {
+                values: values.map(Fp::from),
+                _marker: PhantomData,
+            };
+            let proof = prover::prove(&params, &pk, circuit, &[&instance]).unwrap();
+            assert!(prover::verify(&params, pk.get_vk(), &proof, &[&instance]).is_ok());
+        }
+    }
+
+    #[test]
+    fn prove_deterministic() {
+        let instance: Vec<_> = [3, 1, 4, 2, 1, 2, 3, 4].into_iter().map(Fp::from).collect();
+        let circuit = || SortNCircuit::<Fp, 4, 8, false, false> {
+            values: [3, 1, 4, 2].map(Fp::from),
+            _marker: PhantomData,
+        };
+
+        let (params, pk) = prover::setup(9, &circuit().without_witnesses()).unwrap();
+        let prove = |seed| {
+            prover::prove_deterministic(&params, &pk, circuit(), &[&instance], seed).unwrap()
+        };
+        let proof = prove(1);
+        assert!(prover::verify(&params, pk.get_vk(), &proof, &[&instance]).is_ok());
+        // the same seed, the same proof
+        assert_eq!(proof, prove(1));
+        assert_ne!(proof, prove(2));
+    }
+
+    #[test]
+    fn prove_with_poseidon() {
+        let instance: Vec<_> = [3, 1, 4, 2, 1, 2, 3, 4].into_iter().map(Fp::from).collect();
+        let circuit = SortNCircuit::<Fp, 4, 8, false, false> {
+            values: [3, 1, 4, 2].map(Fp::from),
+            _marker: PhantomData,
+        };
+
+        let (params, pk) = prover::setup(9, &circuit.without_witnesses()).unwrap();
+        let mut transcript = PoseidonWrite::init(vec![]);
+        prover::prove_with_transcript(&params, &pk, circuit, &[&instance], OsRng, &mut transcript)
+            .unwrap();
+        let proof = transcript.finalize();
+
+        // ok
+        let mut transcript = PoseidonRead::init(&proof[..]);
+        assert!(prover::verify_with_transcript(
+            &params,
+            pk.get_vk(),
+            &[&instance],
+            &mut transcript
+        )
+        .is_ok());
+
+        // error: the challenges of Blake2b are not those of the proof
+        let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+        assert!(prover::verify_with_transcript(
+            &params,
+            pk.get_vk(),
+            &[&instance],
+            &mut transcript
+        )
+        .is_err());
+
+        // error: a wrong instance
+        let mut wrong = instance.clone();
+        wrong[4] = Fp::from(2);
+        let mut transcript = PoseidonRead::init(&proof[..]);
+        assert!(
+            prover::verify_with_transcript(&params, pk.get_vk(), &[&wrong], &mut transcript)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn prove_with_backend() {
+        fn prove_and_verify<B: Backend<Scalar = Fp>>() -> Result<(), Error> {
+            let instance: Vec<_> = [2, 1, 1, 2].into_iter().map(Fp::from).collect();
+            let circuit = SortNCircuit::<Fp, 2, 8, false, false> {
+                values: [2, 1].map(Fp::from),
+                _marker: PhantomData,
+            };
+            let (params, pk) = B::setup(9, &circuit.without_witnesses())?;
+            let proof = B::prove(&params, &pk, circuit, &[&instance])?;
+            B::verify(&params, B::vk(&pk), &proof, &[&instance])
+        }
+
+        assert!(prove_and_verify::<Ipa>().is_ok());
+    }
+
+    #[test]
+    fn verify_batch() {
+        let instance: Vec<_> = [3, 1, 4, 2, 1, 2, 3, 4].into_iter().map(Fp::from).collect();
+        let circuit = || SortNCircuit::<Fp, 4, 8, false, false> {
+            values: [3, 1, 4, 2].map(Fp::from),
+            _marker: PhantomData,
+        };
+
+        let (params, pk) = prover::setup(9, &circuit().without_witnesses()).unwrap();
+        let mut proofs: Vec<_> = (0..2)
+            .map(|_| Proof::create(&params, &pk, circuit(), &[&instance]).unwrap())
+            .collect();
+        assert!(Proof::verify_batch(&params, pk.get_vk(), &proofs));
+
+        proofs[1].instances[0][4] = Fp::from(2);
+        assert!(!Proof::verify_batch(&params, pk.get_vk(), &proofs));
+    }
+
+    #[test]
+    fn vk_fingerprint() {
+        let fingerprint = |k| {
+            let circuit = SortNCircuit::<Fp, 4, 8, false, false> {
+                values: [0, 0, 0, 0].map(Fp::from),
+                _marker: PhantomData,
+            };
+            let (_, pk) = prover::setup(k, &circuit).unwrap();
+            prover::vk_fingerprint(pk.get_vk())
+        };
+        // stable across keygens
+        assert_eq!(fingerprint(9), fingerprint(9));
+        assert_eq!(fingerprint(9).len(), 66);
+        // another domain
+        assert_ne!(fingerprint(9), fingerprint(10));
+    }
+
+    #[test]
+    fn write_and_read_keys() {
+        let instance: Vec<_> = [3, 1, 4, 2, 1, 2, 3, 4].into_iter().map(Fp::from).collect();
+        let circuit = || SortNCircuit::<Fp, 4, 8, false, false> {
+            values: [3, 1, 4, 2].map(Fp::from),
+            _marker: PhantomData,
+        };
+        let other = SortNCircuit::<Fp, 4, 8, true, false> {
+            values: [0, 0, 0, 0].map(Fp::from),
+            _marker: PhantomData,
+        };
+        let (params, pk) = prover::setup(9, &circuit().without_witnesses()).unwrap();
+        let proof = prover::prove(&params, &pk, circuit(), &[&instance]).unwrap();
+
+        // ok
+        let mut vk = vec![];
+        prover::write_vk(pk.get_vk(), &mut vk).unwrap();
+        let read = prover::read_vk(&mut &vk[..], &params, &circuit()).unwrap();
+        assert!(prover::verify(&params, &read, &proof, &[&instance]).is_ok());
+        let mut written = vec![];
+        prover::write_pk(&pk, &mut written).unwrap();
+        let read = prover::read_pk(&mut &written[..], &params, &circuit()).unwrap();
+        let proof = prover::prove(&params, &read, circuit(), &[&instance]).unwrap();
+        assert!(prover::verify(&params, pk.get_vk(), &proof, &[&instance]).is_ok());
+        // error: written for another circuit
+        assert!(prover::read_vk(&mut &vk[..], &params, &other).is_err());
+        assert!(prover::read_pk(&mut &written[..], &params, &other).is_err());
+        // error: truncated
+        assert!(prover::read_vk(&mut &vk[..31], &params, &circuit()).is_err());
+    }
+
+    #[test]
+    fn key_cache() {
+        let instance: Vec<_> = [3, 1, 4, 2, 1, 2, 3, 4].into_iter().map(Fp::from).collect();
+        let circuit = || SortNCircuit::<Fp, 4, 8, false, false> {
+            values: [3, 1, 4, 2].map(Fp::from),
+            _marker: PhantomData,
+        };
+        let dir = std::env::temp_dir().join(format!("key-cache-{}", std::process::id()));
+        let cache = KeyCache::new(&dir);
+
+        // the first setup writes the entries, the second reads them
+        let (params, pk) = cache.setup("sort4", 9, &circuit()).unwrap();
+        let (read_params, read_pk) = cache.setup("sort4", 9, &circuit()).unwrap();
+        assert!(dir.join("params-k9.bin").exists());
+        let proof = prover::prove(&read_params, &read_pk, circuit(), &[&instance]).unwrap();
+        assert!(prover::verify(&params, pk.get_vk(), &proof, &[&instance]).is_ok());
+        // error: a stale entry, written for another circuit
+        let other = SortNCircuit::<Fp, 4, 8, true, false> {
+            values: [0, 0, 0, 0].map(Fp::from),
+            _marker: PhantomData,
+        };
+        assert!(cache.setup("sort4", 9, &other).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn min_k() {
+        let circuit = SortNCircuit::<Fp, 4, 8, false, false> {
+            values: [0, 0, 0, 0].map(Fp::from),
+            _marker: PhantomData,
+        };
+        // the 256 rows of the u8 table, and the blinding rows
+        assert_eq!(prover::rows_used(&circuit).unwrap(), 256);
+        assert_eq!(prover::min_k(&circuit).unwrap(), 9);
+    }
+
+    #[test]
+    fn cost_report() {
+        let circuit = SortNCircuit::<Fp, 4, 8, false, false> {
+            values: [0, 0, 0, 0].map(Fp::from),
+            _marker: PhantomData,
+        };
+        let report = CostReport::measure(&circuit).unwrap();
+        assert_eq!(report.advice_columns, 11);
+        assert_eq!(report.fixed_columns, 2);
+        assert_eq!(report.instance_columns, 1);
+        assert_eq!(report.selectors, 2);
+        assert_eq!(report.gates, 3);
+        // a lookup per byte of the differences
+        assert_eq!(report.lookups, 8);
+        assert_eq!(report.rows_used, 256);
+        assert_eq!(report.k, 9);
+        assert!(report.to_string().contains("rows used"));
+        assert!(report.to_json().unwrap().contains("\"proof_size\""));
+    }
+
+    #[test]
+    fn sort_n_elements() {
+        // ok
+        assert_eq!(
+            try_sort::<4, 8, false, false>([3, 1, 4, 2], [1, 2, 3, 4]),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort::<8, 8, false, false>([8, 6, 7, 5, 3, 0, 9, 2], [0, 2, 3, 5, 6, 7, 8, 9]),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort::<16, 8, false, false>(
+                [15, 3, 9, 12, 0, 7, 1, 14, 5, 11, 2, 8, 13, 4, 10, 6],
+                [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+            ),
+            Ok(())
+        );
+        // error
+        assert!(try_sort::<4, 8, false, false>([3, 1, 4, 2], [1, 2, 4, 3]).is_err());
+        assert!(try_sort::<4, 8, false, false>([3, 1, 4, 2], [4, 3, 2, 1]).is_err());
+    }
+
+    #[test]
+    fn sort_wide_values() {
+        let (small, large) = (u64::MAX as u128, u64::MAX as u128 + 1);
+
+        // ok
+        assert_eq!(
+            try_sort::<4, 16, false, false>(
+                [large, 0, u128::MAX, small],
+                [0, small, large, u128::MAX]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort::<4, 31, false, false>(
+                [large, 0, u128::MAX, small],
+                [0, small, large, u128::MAX]
+            ),
+            Ok(())
+        );
+        // error: values wider than 8 bytes wrap around the comparison range
+        assert!(try_sort::<2, 8, false, false>([large + 1, 0], [0, large + 1]).is_err());
+    }
+
+    #[test]
+    fn sort_with_duplicates() {
+        // ok
+        assert_eq!(
+            try_sort::<4, 8, true, false>([3, 1, 3, 1], [1, 1, 3, 3]),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort::<4, 8, true, false>([2, 2, 2, 2], [2, 2, 2, 2]),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort::<4, 8, true, false>([3, 1, 4, 2], [1, 2, 3, 4]),
+            Ok(())
+        );
+        // error
+        assert!(try_sort::<4, 8, false, false>([3, 1, 3, 1], [1, 1, 3, 3]).is_err());
+        assert!(try_sort::<4, 8, true, false>([3, 1, 3, 1], [1, 3, 1, 3]).is_err());
+    }
+
+    #[test]
+    fn sort_descending() {
+        // ok
+        assert_eq!(
+            try_sort::<4, 8, false, true>([3, 1, 4, 2], [4, 3, 2, 1]),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort::<4, 8, true, true>([3, 1, 3, 1], [3, 3, 1, 1]),
+            Ok(())
+        );
+        // error
+        assert!(try_sort::<4, 8, false, true>([3, 1, 4, 2], [1, 2, 3, 4]).is_err());
+        assert!(try_sort::<4, 8, false, true>([3, 1, 3, 1], [3, 3, 1, 1]).is_err());
+    }
+
+    fn try_argsort<const N: usize>(
+        values: [u64; N],
+        sorted: [u64; N],
+        indices: [u64; N],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        // 2N + 2 assigned rows, plus the blinding rows for the 2N-deep
+        // column queries, and at least the 256 rows of the u8 table
+        let k = (usize::BITS - (8 * N).leading_zeros()).max(9);
+        let circuit = SortNCircuit::<Fp, N, 8, false, false, true> {
+            values: values.map(Fp::from),
+            _marker: PhantomData,
+        };
+        let instance = values
+            .iter()
+            .chain(sorted.iter())
+            .chain(indices.iter())
+            .map(|v| Fp::from(*v));
+        let prover = MockProver::<Fp>::run(k, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn sort_with_argsort() {
+        // ok
+        assert_eq!(
+            try_argsort([30, 10, 40, 20], [10, 20, 30, 40], [1, 3, 0, 2]),
+            Ok(())
+        );
+        assert_eq!(
+            try_argsort([1, 2, 3, 4, 5], [1, 2, 3, 4, 5], [0, 1, 2, 3, 4]),
+            Ok(())
+        );
+        // error
+        assert!(try_argsort([30, 10, 40, 20], [10, 20, 30, 40], [0, 1, 2, 3]).is_err());
+        assert!(try_argsort([30, 10, 40, 20], [10, 20, 30, 40], [1, 3, 2, 0]).is_err());
+    }
+
+    #[test]
+    fn sort_private_inputs() {
+        let params = SortNParams {
+            n: 4,
+            n_bytes: 8,
+            private_inputs: true,
+        };
+        let try_sort_private = |values: [u128; 4], sorted: [u128; 4]| {
+            let circuit = SortNCircuit::<Fp, 4, 8, false, false, false, true>::new(
+                params,
+                &values.map(Fp::from_u128),
+            )
+            .unwrap();
+            let instance = sorted.iter().map(|v| Fp::from_u128(*v)).collect();
+            let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+            prover.verify()
+        };
+
+        // ok
+        assert_eq!(try_sort_private([3, 1, 4, 2], [1, 2, 3, 4]), Ok(()));
+        // error
+        assert!(try_sort_private([3, 1, 4, 2], [1, 2, 4, 3]).is_err());
+        assert!(try_sort_private([3, 1, 4, 2], [3, 1, 4, 2]).is_err());
+        // error: an input wider than 8 bytes
+        assert!(try_sort_private([3, 1, 4, 1 << 64], [1, 3, 4, 1 << 64]).is_err());
+    }
+
+    #[test]
+    fn sort_params() {
+        let params = SortNParams {
+            n: 4,
+            n_bytes: 8,
+            private_inputs: false,
+        };
+        let values = [3, 1, 4, 2].map(Fp::from);
+
+        // ok
+        assert!(SortNCircuit::<Fp, 4, 8>::new(params, &values).is_ok());
+        // error: wrong shape
+        assert!(SortNCircuit::<Fp, 5, 8>::new(params, &values).is_err());
+        assert!(SortNCircuit::<Fp, 4, 16>::new(params, &values).is_err());
+        assert!(SortNCircuit::<Fp, 4, 8, false, false, false, true>::new(params, &values).is_err());
+        // error: wrong number of values
+        assert!(SortNCircuit::<Fp, 4, 8>::new(params, &values[..3]).is_err());
+    }
+
+    // assigns whatever input indices it is given, sorted or not
+    #[derive(Default)]
+    struct PermutedCircuit<F> {
+        in_indices: [usize; 4],
+        values: [F; 4],
+    }
+
+    impl<F: FieldExt> Circuit<F> for PermutedCircuit<F> {
+        type Config = SortNConfig<F, 4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let u8_table = U8Table::configure(meta);
+            SortNChip::configure(
+                meta,
+                advice,
+                instance,
+                fixed,
+                SortOrder::Ascending,
+                true,
+                false,
+                false,
+                false,
+                u8_table,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = SortNChip::construct(config);
+            let (output_cells, _) =
+                chip.assign(layouter.namespace(|| "all"), self.in_indices, self.values)?;
+            for (i, output_cell) in output_cells.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), output_cell, i + 4)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sort_is_permutation() {
+        let try_permute = |inputs: [u64; 4], in_indices: [usize; 4]| {
+            let values = in_indices.map(|i| Fp::from(inputs[i]));
+            let circuit = PermutedCircuit { in_indices, values };
+            let instance = inputs.iter().map(|v| Fp::from(*v)).chain(values).collect();
+            let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+            prover.verify()
+        };
+
+        // ok
+        assert_eq!(try_permute([1, 5, 2, 3], [0, 2, 3, 1]), Ok(()));
+        assert_eq!(try_permute([2, 1, 2, 1], [1, 3, 0, 2]), Ok(()));
+        // error: 1 is copied twice and 5 is dropped, but the outputs are sorted
+        assert!(try_permute([1, 5, 2, 3], [0, 0, 2, 3]).is_err());
+        assert!(try_permute([2, 1, 2, 1], [1, 1, 3, 3]).is_err());
+    }
+
+    #[test]
+    fn sort_batch() {
+        let try_sort_batch = |arrays: Vec<[u64; 4]>, instance: Vec<u64>| {
+            let circuit = SortBatchCircuit::<Fp, 4, 8>::new(
+                arrays
+                    .into_iter()
+                    .map(|values| values.map(Fp::from))
+                    .collect(),
+            );
+            let instance = instance.into_iter().map(Fp::from).collect();
+            let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+            prover.verify()
+        };
+
+        // ok
+        let arrays = vec![[3, 1, 4, 2], [8, 6, 7, 5], [10, 30, 20, 40]];
+        let circuit =
+            SortBatchCircuit::<Fp, 4, 8>::new(arrays.iter().map(|a| a.map(Fp::from)).collect());
+        assert_eq!(
+            circuit.instance(),
+            [3, 1, 4, 2, 1, 2, 3, 4, 8, 6, 7, 5, 5, 6, 7, 8, 10, 30, 20, 40, 10, 20, 30, 40]
+                .map(Fp::from)
+        );
+        assert_eq!(
+            try_sort_batch(
+                arrays.clone(),
+                vec![
+                    3, 1, 4, 2, 1, 2, 3, 4, 8, 6, 7, 5, 5, 6, 7, 8, 10, 30, 20, 40, 10, 20, 30, 40
+                ]
+            ),
+            Ok(())
+        );
+        // error: the outputs of two arrays are swapped
+        assert!(try_sort_batch(
+            arrays,
+            vec![3, 1, 4, 2, 5, 6, 7, 8, 8, 6, 7, 5, 1, 2, 3, 4, 10, 30, 20, 40, 10, 20, 30, 40]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn sort_signed() {
+        let try_sort_signed = |values: [i64; 4], sorted: [i64; 4]| {
+            let to_field = |v: i64| {
+                let abs = Fp::from(v.unsigned_abs());
+                if v < 0 {
+                    -abs
+                } else {
+                    abs
+                }
+            };
+            let circuit = SortNCircuit::<Fp, 4, 8, false, false, false, false, true> {
+                values: values.map(to_field),
+                _marker: PhantomData,
+            };
+            let instance = values.iter().chain(sorted.iter()).map(|v| to_field(*v));
+            let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+            prover.verify()
+        };
+
+        // ok
+        assert_eq!(try_sort_signed([3, -1, 0, -7], [-7, -1, 0, 3]), Ok(()));
+        assert_eq!(
+            try_sort_signed([i64::MAX, i64::MIN, 1, -1], [i64::MIN, -1, 1, i64::MAX]),
+            Ok(())
+        );
+        // error: sorted as unsigned values
+        assert!(try_sort_signed([3, -1, 0, -7], [0, 3, -7, -1]).is_err());
+    }
+}
@@ -0,0 +1,450 @@
+use super::{SortInputs, SortOrder};
+use crate::{
+    gadgets::{
+        known_value,
+        less_than::{LtChip, LtConfig, LtInstruction},
+        Gadget, SharedColumns,
+    },
+    table::U8Table,
+    util::{is_zero, one_hot},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Config for stably sorting N elements, each compared as an N_BYTES-byte
+/// value.
+#[derive(Debug, Clone)]
+pub struct StableSortConfig<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    // N inputs, their N input indices and their sorted rows on the first four rows
+    pub advice: [Column<Advice>; N],
+    pub master_selector: Selector,
+    pub instance: Column<Instance>,
+    /// The order of the sorted outputs.
+    pub order: SortOrder,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // N - 1 adjacent comparisons of the outputs, of their input indices and
+    // the inverse of the output difference for the equality check
+    lt_selectors: Vec<Selector>,
+    lt_configs: Vec<LtConfig<F, N_BYTES>>,
+    index_lt_configs: Vec<LtConfig<F, N_BYTES>>,
+    diff_inv: Column<Advice>,
+}
+
+/// Chip that sorts N elements such that equal elements keep their input
+/// order. Every output carries its input index, and adjacent equal outputs
+/// are constrained to have increasing input indices.
+#[derive(Debug, Clone)]
+pub struct StableSortChip<F: FieldExt, const N: usize, const N_BYTES: usize> {
+    config: StableSortConfig<F, N, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> StableSortChip<F, N, N_BYTES> {
+    pub fn construct(config: StableSortConfig<F, N, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        order: SortOrder,
+        u8_table: U8Table,
+    ) -> StableSortConfig<F, N, N_BYTES> {
+        assert!(N > 1, "stableSort needs at least two elements");
+        assert!(N_BYTES < 32, "compared values must fit in the field");
+        assert!(
+            N_BYTES >= 8 || N < 1 << (8 * N_BYTES),
+            "input indices must fit in N_BYTES"
+        );
+
+        meta_cs.enable_equality(instance);
+        meta_cs.enable_constant(fixed);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let master_selector = meta_cs.selector();
+        let mut lt_selectors = Vec::with_capacity(N - 1);
+        for _i in 0..N - 1 {
+            lt_selectors.push(meta_cs.complex_selector());
+        }
+
+        // the lt rows need two lt columns, 2 * N_BYTES diff columns and the
+        // inverse column
+        let mut advice_vec = advice.to_vec();
+        for _i in N..2 * N_BYTES + 3 {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        let diff: Vec<_> = advice_vec[1..N_BYTES + 1].to_vec();
+        let index_diff: Vec<_> = advice_vec[N_BYTES + 2..2 * N_BYTES + 2].to_vec();
+        let diff_inv = advice_vec[2 * N_BYTES + 2];
+
+        let mut lt_configs = Vec::with_capacity(N - 1);
+        let mut index_lt_configs = Vec::with_capacity(N - 1);
+        for i in 0..N - 1 {
+            // the sorted outputs are on row 2, their input indices on row 3
+            // and the lt rows start on row 4
+            let (lhs, rhs) = order.lt_operands(false, advice_vec[i], advice_vec[i + 1]);
+            lt_configs.push(LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(lhs, Rotation(-2 - i as i32)),
+                |meta| meta.query_advice(rhs, Rotation(-2 - i as i32)),
+                advice_vec[0],
+                diff.clone().try_into().unwrap(),
+                u8_table,
+            ));
+            // input indices always increase between equal outputs
+            index_lt_configs.push(LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(advice_vec[i], Rotation(-1 - i as i32)),
+                |meta| meta.query_advice(advice_vec[i + 1], Rotation(-1 - i as i32)),
+                advice_vec[N_BYTES + 1],
+                index_diff.clone().try_into().unwrap(),
+                u8_table,
+            ));
+        }
+
+        let mut lt_constraints = Vec::with_capacity(2 * (N - 1));
+        meta_cs.create_gate("stableSort", |meta_vc| {
+            //  0  |  1  | ... | N-1    | selectors
+            // i0    i1    ...   i(N-1)  master
+            // 0     1     ...   N-1
+            // o0    o1    ...   o(N-1)
+            // idx0  idx1  ...   idx(N-1)
+            // lt0 diff0_0 .. idxlt0 idxdiff0_0 .. inv0   lt_selectors[0]
+            // ...
+            // lt(N-2) diff(N-2)_0 ..                     lt_selectors[N-2]
+            let s = meta_vc.query_selector(master_selector);
+            let one = Expression::Constant(F::one());
+
+            for (i, (lt, index_lt)) in lt_configs.iter().zip(index_lt_configs.iter()).enumerate() {
+                let rotation = Rotation(i as i32 + 4);
+                let lt = lt.is_lt(meta_vc, Some(rotation));
+                let index_lt = index_lt.is_lt(meta_vc, Some(rotation));
+
+                // eq == 1 iff the adjacent outputs are equal
+                let cur = meta_vc.query_advice(advice[i], Rotation(2));
+                let next = meta_vc.query_advice(advice[i + 1], Rotation(2));
+                let diff = next - cur;
+                let inv = meta_vc.query_advice(diff_inv, rotation);
+                let eq = one.clone() - diff.clone() * inv;
+
+                lt_constraints.push(s.clone() * diff * eq.clone());
+                lt_constraints.push(s.clone() * (lt + eq * index_lt - one.clone()));
+            }
+            lt_constraints
+        });
+
+        // The outputs and their input indices are selected by a permutation
+        // matrix rather than copied from the inputs, as copies are fixed at
+        // keygen.
+        meta_cs.create_gate("stableSort permutation", |meta_vc| {
+            // sel0_0    sel0_1 ...    sel0_(N-1)       row N + 3
+            // ...
+            // sel(N-1)_0 ...          sel(N-1)_(N-1)   row 2N + 2
+            // where sel_i selects the input of the output i
+            let s = meta_vc.query_selector(master_selector);
+            let query_row = |meta_vc: &mut VirtualCells<F>, row: usize| -> Vec<_> {
+                advice
+                    .iter()
+                    .map(|column| meta_vc.query_advice(*column, Rotation(row as i32)))
+                    .collect()
+            };
+            let inputs = query_row(meta_vc, 0);
+            let in_indices = query_row(meta_vc, 1);
+            let outputs = query_row(meta_vc, 2);
+            let indices = query_row(meta_vc, 3);
+            let matrix: Vec<_> = (0..N).map(|i| query_row(meta_vc, N + 3 + i)).collect();
+
+            let mut constraints = one_hot::permutation(&matrix);
+            for (i, sel) in matrix.iter().enumerate() {
+                constraints.push(outputs[i].clone() - one_hot::select(sel, &inputs));
+                constraints.push(indices[i].clone() - one_hot::select(sel, &in_indices));
+            }
+            constraints
+                .into_iter()
+                .map(|constraint| s.clone() * constraint)
+                .collect::<Vec<_>>()
+        });
+
+        StableSortConfig {
+            advice,
+            master_selector,
+            instance,
+            order,
+            u8_table,
+            lt_selectors,
+            lt_configs,
+            index_lt_configs,
+            diff_inv,
+        }
+    }
+
+    /// Assigns the inputs read from the instance column and returns the
+    /// sorted output cells along with the input index cells of the outputs.
+    #[allow(clippy::type_complexity)]
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        in_indices: [usize; N],
+        values: [F; N],
+    ) -> Result<([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]), Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, inputs, in_indices, values)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: SortInputs<F>,
+        in_indices: [usize; N],
+        values: [F; N],
+    ) -> Result<([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]), Error> {
+        layouter.assign_region(
+            || "stable sort",
+            |mut region| {
+                self.config.master_selector.enable(&mut region, 0)?;
+
+                // unsorted inputs and their fixed input indices
+                let mut in_cells = Vec::with_capacity(N);
+                let mut in_index_cells = Vec::with_capacity(N);
+                for (i, column) in self.config.advice.iter().enumerate() {
+                    in_cells.push(inputs.assign(&mut region, i, *column, 0)?);
+                    in_index_cells.push(region.assign_advice_from_constant(
+                        || format!("input index {}", i),
+                        *column,
+                        1,
+                        F::from(i as u64),
+                    )?);
+                }
+
+                // sorted outputs, their input indices and the permutation
+                // matrix selecting them
+                let mut output_cells = Vec::with_capacity(N);
+                let mut index_cells = Vec::with_capacity(N);
+                for (i, in_index) in in_indices.iter().enumerate() {
+                    let (input, index) = (&in_cells[*in_index], &in_index_cells[*in_index]);
+                    output_cells.push(region.assign_advice(
+                        || format!("sort out[{}]", i),
+                        self.config.advice[i],
+                        2,
+                        || input.value().copied(),
+                    )?);
+                    index_cells.push(region.assign_advice(
+                        || format!("sort index[{}]", i),
+                        self.config.advice[i],
+                        3,
+                        || index.value().copied(),
+                    )?);
+                    for (j, column) in self.config.advice.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("sort out[{}] one-hot[{}]", i, j),
+                            *column,
+                            N + 3 + i,
+                            || Value::known(F::from((j == *in_index) as u64)),
+                        )?;
+                    }
+                }
+
+                // lt chips and output equality
+                for (i, lt_selector) in self.config.lt_selectors.iter().enumerate() {
+                    lt_selector.enable(&mut region, i + 4)?;
+                }
+                for i in 0..N - 1 {
+                    let lt_chip = LtChip::construct(self.config.lt_configs[i]);
+                    let (lhs, rhs) = self
+                        .config
+                        .order
+                        .lt_operands(false, values[i], values[i + 1]);
+                    lt_chip.assign(&mut region, i + 4, lhs, rhs)?;
+
+                    let lt_chip = LtChip::construct(self.config.index_lt_configs[i]);
+                    lt_chip.assign(
+                        &mut region,
+                        i + 4,
+                        F::from(in_indices[i] as u64),
+                        F::from(in_indices[i + 1] as u64),
+                    )?;
+
+                    let diff_inv = is_zero::value_inv(values[i + 1] - values[i]);
+                    region.assign_advice(
+                        || format!("diff inv[{}]", i),
+                        self.config.diff_inv,
+                        i + 4,
+                        || Value::known(diff_inv),
+                    )?;
+                }
+                Ok((
+                    output_cells.try_into().unwrap(),
+                    index_cells.try_into().unwrap(),
+                ))
+            },
+        )
+    }
+
+    /// Stably sorts the values in the configured order, returning the input
+    /// index of every sorted output along with the sorted values.
+    pub fn sort_indices(&self, values: [F; N]) -> ([usize; N], [F; N]) {
+        // bubble sort only swaps strictly out of order values, so it's stable
+        self.config.order.sort_indices(values)
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const N_BYTES: usize> Gadget<F>
+    for StableSortChip<F, N, N_BYTES>
+{
+    type Config = StableSortConfig<F, N, N_BYTES>;
+    type Params = SortOrder;
+    type Input = [AssignedCell<F, F>; N];
+    /// The cells of the sorted outputs and of their input indices.
+    type Output = ([AssignedCell<F, F>; N], [AssignedCell<F, F>; N]);
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        order: SortOrder,
+    ) -> Self::Config {
+        StableSortChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            order,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let chip = StableSortChip::construct(config.clone());
+        let (in_indices, values) =
+            chip.sort_indices(std::array::from_fn(|i| known_value(&input[i])));
+        chip.assign_from(layouter, SortInputs::Cells(&input), in_indices, values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SortOrder, StableSortChip, StableSortConfig};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct StableSortCircuit<F, const DESCENDING: bool> {
+        values: [F; 4],
+    }
+
+    impl<F: FieldExt, const DESCENDING: bool> Circuit<F> for StableSortCircuit<F, DESCENDING> {
+        type Config = StableSortConfig<F, 4, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 4].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let order = if DESCENDING {
+                SortOrder::Descending
+            } else {
+                SortOrder::Ascending
+            };
+            let u8_table = U8Table::configure(meta);
+            StableSortChip::configure(meta, advice, instance, fixed, order, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = StableSortChip::construct(config);
+
+            let (in_indices, values) = chip.sort_indices(self.values);
+            let (output_cells, index_cells) =
+                chip.assign(layouter.namespace(|| "all"), in_indices, values)?;
+
+            for (i, cell) in output_cells.iter().chain(index_cells.iter()).enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), cell, i + 4)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_stable_sort<const DESCENDING: bool>(
+        values: [u64; 4],
+        sorted: [u64; 4],
+        indices: [u64; 4],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = StableSortCircuit::<Fp, DESCENDING> {
+            values: values.map(Fp::from),
+        };
+        let instance = values
+            .iter()
+            .chain(sorted.iter())
+            .chain(indices.iter())
+            .map(|v| Fp::from(*v));
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn stable_sort() {
+        // ok
+        assert_eq!(
+            try_stable_sort::<false>([3, 1, 3, 1], [1, 1, 3, 3], [1, 3, 0, 2]),
+            Ok(())
+        );
+        assert_eq!(
+            try_stable_sort::<true>([3, 1, 3, 1], [3, 3, 1, 1], [0, 2, 1, 3]),
+            Ok(())
+        );
+        assert_eq!(
+            try_stable_sort::<false>([4, 3, 2, 1], [1, 2, 3, 4], [3, 2, 1, 0]),
+            Ok(())
+        );
+        // error: equal outputs out of input order
+        assert!(try_stable_sort::<false>([3, 1, 3, 1], [1, 1, 3, 3], [3, 1, 2, 0]).is_err());
+        assert!(try_stable_sort::<false>([3, 1, 3, 1], [1, 3, 1, 3], [1, 0, 3, 2]).is_err());
+    }
+}
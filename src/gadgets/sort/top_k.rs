@@ -0,0 +1,191 @@
+use super::{SortNChip, SortNConfig, SortOrder};
+use crate::{
+    gadgets::{Gadget, SharedColumns},
+    table::U8Table,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance},
+};
+use std::marker::PhantomData;
+
+/// Config for selecting the K largest of N private elements, each compared
+/// as an N_BYTES-byte value.
+#[derive(Debug, Clone)]
+pub struct TopKConfig<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize> {
+    /// Config of the chip sorting all N elements.
+    pub sort_config: SortNConfig<F, N, N_BYTES>,
+}
+
+/// Chip that selects the K largest of N private elements. The elements are
+/// sorted in descending order, so that the K selected elements are sorted and
+/// every other element is at most the smallest selected one. This takes the
+/// same N - 1 comparisons as checking the two conditions separately. The
+/// sort chip range checks the private elements to N_BYTES bytes.
+#[derive(Debug, Clone)]
+pub struct TopKChip<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize> {
+    config: TopKConfig<F, N, K, N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize> TopKChip<F, N, K, N_BYTES> {
+    pub fn construct(config: TopKConfig<F, N, K, N_BYTES>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        fixed: Column<Fixed>,
+        u8_table: U8Table,
+    ) -> TopKConfig<F, N, K, N_BYTES> {
+        assert!(K > 0 && K <= N, "topK needs 0 < K <= N");
+
+        TopKConfig {
+            sort_config: SortNChip::configure(
+                meta_cs,
+                advice,
+                instance,
+                fixed,
+                SortOrder::Descending,
+                true,
+                false,
+                true,
+                false,
+                u8_table,
+            ),
+        }
+    }
+
+    /// Witnesses the private values and returns the cells of the K largest,
+    /// largest first.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        values: [F; N],
+    ) -> Result<[AssignedCell<F, F>; K], Error> {
+        let sort_chip = SortNChip::construct(self.config.sort_config.clone());
+        let (in_indices, sorted) = sort_chip.sort_indices(values);
+        let (output_cells, _) = sort_chip.assign(layouter, in_indices, sorted)?;
+        Ok(output_cells[..K].to_vec().try_into().unwrap())
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.sort_config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize, const K: usize, const N_BYTES: usize> Gadget<F>
+    for TopKChip<F, N, K, N_BYTES>
+{
+    type Config = TopKConfig<F, N, K, N_BYTES>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; N];
+    /// The cells of the K largest inputs, largest first.
+    type Output = [AssignedCell<F, F>; K];
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        TopKChip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            columns.constants,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        // the sort config has private inputs, as that of a SortNChip gadget
+        let cells =
+            <SortNChip<F, N, N_BYTES> as Gadget<F>>::assign(&config.sort_config, layouter, input)?;
+        Ok(cells.outputs[..K].to_vec().try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TopKChip, TopKConfig};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct TopKCircuit<F> {
+        values: [F; 5],
+    }
+
+    impl<F: FieldExt> Circuit<F> for TopKCircuit<F> {
+        type Config = TopKConfig<F, 5, 3, 8>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 5].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let fixed = meta.fixed_column();
+            let u8_table = U8Table::configure(meta);
+            TopKChip::configure(meta, advice, instance, fixed, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.sort_config.u8_table.load(&mut layouter)?;
+            let chip = TopKChip::construct(config);
+
+            let top_cells = chip.assign(layouter.namespace(|| "all"), self.values)?;
+
+            for (i, cell) in top_cells.iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), cell, i)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_top_k(values: [u128; 5], top: [u128; 3]) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TopKCircuit {
+            values: values.map(Fp::from_u128),
+        };
+        let instance = top.iter().map(|v| Fp::from_u128(*v)).collect();
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn top_k() {
+        // ok
+        assert_eq!(try_top_k([3, 9, 1, 7, 5], [9, 7, 5]), Ok(()));
+        assert_eq!(try_top_k([4, 8, 4, 1, 8], [8, 8, 4]), Ok(()));
+        // error: not sorted
+        assert!(try_top_k([3, 9, 1, 7, 5], [9, 5, 7]).is_err());
+        // error: not the largest
+        assert!(try_top_k([3, 9, 1, 7, 5], [9, 7, 3]).is_err());
+        // error: an element wider than 8 bytes
+        assert!(try_top_k([3, 9, 1, 7, 1 << 64], [1 << 64, 9, 7]).is_err());
+    }
+}
@@ -0,0 +1,471 @@
+use super::{SortInputs, SortOrder};
+use crate::{
+    constrain_if,
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::word::{Word, WORD_LIMBS},
+    gadgets::{known_value, Gadget, SharedColumns},
+    table::U8Table,
+    util::{is_zero, one_hot, Expr},
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector, VirtualCells,
+    },
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// The number of 64-bit limbs of a 256-bit word.
+pub const LIMBS: usize = WORD_LIMBS;
+
+/// Config for sorting N 256-bit words, each given as four 64-bit limbs.
+#[derive(Debug, Clone)]
+pub struct SortWord256Config<F: FieldExt, const N: usize> {
+    // the limbs of the N words, most significant first, on the first four
+    // rows and the limbs of the sorted words on the next four rows
+    pub advice: [Column<Advice>; N],
+    pub master_selector: Selector,
+    pub instance: Column<Instance>,
+    /// The order of the sorted words.
+    pub order: SortOrder,
+    /// If set, adjacent sorted words may be equal.
+    pub allow_duplicates: bool,
+    /// The u8 table range checking the diff bytes, which the circuit loads
+    /// once.
+    pub u8_table: U8Table,
+
+    // N - 1 adjacent comparisons of four limbs each, with the inverse of the
+    // limb difference, whether the limbs are equal and whether all limbs so
+    // far are equal
+    lt_selectors: Vec<Selector>,
+    lt_configs: Vec<LtConfig<F, 8>>,
+    diff_inv: Column<Advice>,
+    eq: Column<Advice>,
+    prefix_eq: Column<Advice>,
+}
+
+/// Chip that sorts 256-bit words, which do not fit in the field, by
+/// comparing their 64-bit limbs lexicographically. A word precedes the next
+/// one iff their first differing limbs are ordered.
+#[derive(Debug, Clone)]
+pub struct SortWord256Chip<F: FieldExt, const N: usize> {
+    config: SortWord256Config<F, N>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize> SortWord256Chip<F, N> {
+    pub fn construct(config: SortWord256Config<F, N>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta_cs: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+        instance: Column<Instance>,
+        order: SortOrder,
+        allow_duplicates: bool,
+        u8_table: U8Table,
+    ) -> SortWord256Config<F, N> {
+        assert!(N > 1, "sortWord256 needs at least two elements");
+
+        meta_cs.enable_equality(instance);
+        for column in &advice {
+            meta_cs.enable_equality(*column);
+        }
+        let master_selector = meta_cs.selector();
+        let mut lt_selectors = Vec::with_capacity(N - 1);
+        for _i in 0..N - 1 {
+            lt_selectors.push(meta_cs.complex_selector());
+        }
+
+        // the comparison rows need one lt column, 8 diff columns, the
+        // inverse column and the two equality columns
+        let mut advice_vec = advice.to_vec();
+        for _i in N..12 {
+            advice_vec.push(meta_cs.advice_column());
+        }
+        let diff: [Column<Advice>; 8] = advice_vec[1..9].try_into().unwrap();
+        let (diff_inv, eq, prefix_eq) = (advice_vec[9], advice_vec[10], advice_vec[11]);
+
+        let mut lt_configs = Vec::with_capacity(N - 1);
+        for i in 0..N - 1 {
+            // the sorted limbs are on rows 4..8, the comparison rows of
+            // adjacent words i and i + 1 are on rows 8 + 4i..12 + 4i
+            let (lhs, rhs) = order.lt_operands(false, advice_vec[i], advice_vec[i + 1]);
+            let lt_config = LtChip::configure(
+                meta_cs,
+                |meta| meta.query_selector(lt_selectors[i]),
+                |meta| meta.query_advice(lhs, Rotation(-4 - 4 * i as i32)),
+                |meta| meta.query_advice(rhs, Rotation(-4 - 4 * i as i32)),
+                advice_vec[0],
+                diff,
+                u8_table,
+            );
+            lt_configs.push(lt_config);
+        }
+
+        meta_cs.create_gate("sortWord256", |meta_vc| {
+            //  0  |  1  | ... | N-1        | selectors
+            // w0_0  w1_0  ...   w(N-1)_0     master
+            // ...
+            // w0_3  w1_3  ...   w(N-1)_3
+            // o0_0  o1_0  ...   o(N-1)_0
+            // ...
+            // o0_3  o1_3  ...   o(N-1)_3
+            // lt  diff .. | diff_inv | eq | prefix_eq   lt_selectors[0]
+            // ...                                        (4 rows per pair)
+            let s = meta_vc.query_selector(master_selector);
+
+            let mut constraints = Vec::with_capacity(4 * LIMBS * (N - 1));
+            for (i, lt_config) in lt_configs.iter().enumerate() {
+                let mut ordered = 0.expr();
+                let mut prev_prefix_eq: Option<Expression<F>> = None;
+                for j in 0..LIMBS {
+                    let row = (8 + 4 * i + j) as i32;
+                    let cur = meta_vc.query_advice(advice[i], Rotation(4 + j as i32));
+                    let next = meta_vc.query_advice(advice[i + 1], Rotation(4 + j as i32));
+                    let lt = lt_config.is_lt(meta_vc, Some(Rotation(row)));
+                    let diff_inv = meta_vc.query_advice(diff_inv, Rotation(row));
+                    let eq = meta_vc.query_advice(eq, Rotation(row));
+                    let prefix_eq = meta_vc.query_advice(prefix_eq, Rotation(row));
+                    let diff = next - cur;
+
+                    // eq is 1 iff the limbs are equal
+                    constraints.push(constrain_if!(s, diff => eq.clone()));
+                    constraints.push(s.clone() * (is_zero::expr(diff, diff_inv) - eq.clone()));
+
+                    // the limb decides the order iff all previous limbs are equal
+                    match prev_prefix_eq {
+                        None => {
+                            constraints.push(s.clone() * (prefix_eq.clone() - eq));
+                            ordered = lt;
+                        }
+                        Some(prev_prefix_eq) => {
+                            constraints.push(
+                                s.clone() * (prefix_eq.clone() - prev_prefix_eq.clone() * eq),
+                            );
+                            ordered = ordered + prev_prefix_eq * lt;
+                        }
+                    }
+                    prev_prefix_eq = Some(prefix_eq);
+                }
+                if allow_duplicates {
+                    ordered = ordered + prev_prefix_eq.unwrap();
+                }
+                constraints.push(s.clone() * (ordered - 1.expr()));
+            }
+            constraints
+        });
+
+        // The sorted words are selected by a permutation matrix rather than
+        // copied from the inputs, as copies are fixed at keygen.
+        meta_cs.create_gate("sortWord256 permutation", |meta_vc| {
+            // sel0_0    sel0_1 ...    sel0_(N-1)       row 4N + 4
+            // ...
+            // sel(N-1)_0 ...          sel(N-1)_(N-1)   row 5N + 3
+            // where sel_i selects the input word of the sorted word i
+            let s = meta_vc.query_selector(master_selector);
+            let query_row = |meta_vc: &mut VirtualCells<F>, row: usize| -> Vec<_> {
+                advice
+                    .iter()
+                    .map(|column| meta_vc.query_advice(*column, Rotation(row as i32)))
+                    .collect()
+            };
+            let matrix: Vec<_> = (0..N).map(|i| query_row(meta_vc, 4 * N + 4 + i)).collect();
+
+            let mut constraints = one_hot::permutation(&matrix);
+            for j in 0..LIMBS {
+                let limbs = query_row(meta_vc, j);
+                let sorted_limbs = query_row(meta_vc, LIMBS + j);
+                for (i, sel) in matrix.iter().enumerate() {
+                    constraints.push(sorted_limbs[i].clone() - one_hot::select(sel, &limbs));
+                }
+            }
+            constraints
+                .into_iter()
+                .map(|constraint| s.clone() * constraint)
+                .collect::<Vec<_>>()
+        });
+
+        SortWord256Config {
+            advice,
+            master_selector,
+            instance,
+            order,
+            allow_duplicates,
+            u8_table,
+            lt_selectors,
+            lt_configs,
+            diff_inv,
+            eq,
+            prefix_eq,
+        }
+    }
+
+    /// Splits a big-endian 256-bit word into its four 64-bit limbs, most
+    /// significant first.
+    pub fn limbs_from_be_bytes(bytes: [u8; 32]) -> [F; LIMBS] {
+        Word::from_be_bytes(bytes).0
+    }
+
+    /// Assigns the words read from the instance column (the limbs of word i
+    /// on rows 4i..4i + 4) and returns the limb cells of the sorted words.
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        in_indices: [usize; N],
+        words: [[F; LIMBS]; N],
+    ) -> Result<[[AssignedCell<F, F>; LIMBS]; N], Error> {
+        let inputs = SortInputs::Instance(self.config.instance);
+        self.assign_from(layouter, inputs, in_indices, words)
+    }
+
+    // Assigns the words read from the inputs, the limbs of word i being the
+    // inputs 4i..4i + 4.
+    fn assign_from(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: SortInputs<F>,
+        in_indices: [usize; N],
+        words: [[F; LIMBS]; N],
+    ) -> Result<[[AssignedCell<F, F>; LIMBS]; N], Error> {
+        layouter.assign_region(
+            || "sort word256",
+            |mut region| {
+                self.config.master_selector.enable(&mut region, 0)?;
+
+                // unsorted words
+                let mut in_cells = Vec::with_capacity(N);
+                for (i, column) in self.config.advice.iter().enumerate() {
+                    let mut limb_cells = Vec::with_capacity(LIMBS);
+                    for j in 0..LIMBS {
+                        limb_cells.push(inputs.assign(&mut region, LIMBS * i + j, *column, j)?);
+                    }
+                    in_cells.push(limb_cells);
+                }
+
+                // sorted words, every limb permuted by the word order, and
+                // the permutation matrix selecting them
+                let mut output_cells = Vec::with_capacity(N);
+                for (i, in_index) in in_indices.iter().enumerate() {
+                    let mut limb_cells = Vec::with_capacity(LIMBS);
+                    for (j, in_cell) in in_cells[*in_index].iter().enumerate() {
+                        limb_cells.push(region.assign_advice(
+                            || format!("sort out[{}][{}]", i, j),
+                            self.config.advice[i],
+                            LIMBS + j,
+                            || in_cell.value().copied(),
+                        )?);
+                    }
+                    output_cells.push(limb_cells.try_into().unwrap());
+                    for (j, column) in self.config.advice.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("sort out[{}] one-hot[{}]", i, j),
+                            *column,
+                            4 * N + 4 + i,
+                            || Value::known(F::from((j == *in_index) as u64)),
+                        )?;
+                    }
+                }
+
+                // limb comparisons
+                for (i, lt_config) in self.config.lt_configs.iter().enumerate() {
+                    let lt_chip = LtChip::construct(*lt_config);
+                    let mut prefix_eq = true;
+                    for (j, (cur, next)) in words[i].into_iter().zip(words[i + 1]).enumerate() {
+                        let row = 8 + 4 * i + j;
+                        self.config.lt_selectors[i].enable(&mut region, row)?;
+                        let (lhs, rhs) = self.config.order.lt_operands(false, cur, next);
+                        lt_chip.assign(&mut region, row, lhs, rhs)?;
+
+                        let diff_inv = is_zero::value_inv(next - cur);
+                        prefix_eq &= cur == next;
+                        for (column, value) in [
+                            (self.config.diff_inv, diff_inv),
+                            (self.config.eq, F::from(cur == next)),
+                            (self.config.prefix_eq, F::from(prefix_eq)),
+                        ] {
+                            region.assign_advice(
+                                || format!("cmp[{}][{}]", i, j),
+                                column,
+                                row,
+                                || Value::known(value),
+                            )?;
+                        }
+                    }
+                }
+                Ok(output_cells.try_into().unwrap())
+            },
+        )
+    }
+
+    /// Sorts the words in the configured order, returning the input index of
+    /// every sorted word along with the sorted words.
+    pub fn sort_indices(&self, words: [[F; LIMBS]; N]) -> ([usize; N], [[F; LIMBS]; N]) {
+        self.config.order.sort_indices(words)
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(cell.cell(), self.config.instance, row)
+    }
+}
+
+impl<F: FieldExt, const N: usize> Gadget<F> for SortWord256Chip<F, N> {
+    type Config = SortWord256Config<F, N>;
+    /// The order of the words and whether they may repeat.
+    type Params = (SortOrder, bool);
+    type Input = [Word<AssignedCell<F, F>>; N];
+    /// The limb cells of the sorted words.
+    type Output = [Word<AssignedCell<F, F>>; N];
+
+    fn configure(
+        meta: &mut ConstraintSystem<F>,
+        columns: &SharedColumns,
+        (order, allow_duplicates): (SortOrder, bool),
+    ) -> Self::Config {
+        SortWord256Chip::configure(
+            meta,
+            columns.advice(0),
+            columns.instance,
+            order,
+            allow_duplicates,
+            columns.u8_table,
+        )
+    }
+
+    fn assign(
+        config: &Self::Config,
+        layouter: impl Layouter<F>,
+        input: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        let chip = SortWord256Chip::construct(config.clone());
+        let words =
+            std::array::from_fn(|i| input[i].limbs().clone().map(|limb| known_value(&limb)));
+        let (in_indices, sorted) = chip.sort_indices(words);
+        let inputs: Vec<_> = input.into_iter().flat_map(|word| word.0).collect();
+        let outputs = chip.assign_from(layouter, SortInputs::Cells(&inputs), in_indices, sorted)?;
+        Ok(outputs.map(Word))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SortOrder, SortWord256Chip, SortWord256Config, LIMBS};
+    use crate::table::U8Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    #[derive(Default)]
+    struct SortWord256Circuit<F> {
+        words: [[F; LIMBS]; 3],
+    }
+
+    impl<F: FieldExt> Circuit<F> for SortWord256Circuit<F> {
+        type Config = SortWord256Config<F, 3>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            let u8_table = U8Table::configure(meta);
+            SortWord256Chip::configure(meta, advice, instance, SortOrder::Ascending, true, u8_table)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = SortWord256Chip::construct(config);
+
+            let (in_indices, words) = chip.sort_indices(self.words);
+            let output_cells = chip.assign(layouter.namespace(|| "all"), in_indices, words)?;
+
+            for (i, cell) in output_cells.iter().flatten().enumerate() {
+                chip.expose_public(layouter.namespace(|| "out"), cell, i + 3 * LIMBS)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    fn try_sort_word256(
+        words: [[u64; LIMBS]; 3],
+        sorted: [[u64; LIMBS]; 3],
+    ) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = SortWord256Circuit {
+            words: words.map(|word| word.map(Fp::from)),
+        };
+        let instance = words
+            .iter()
+            .chain(sorted.iter())
+            .flatten()
+            .map(|limb| Fp::from(*limb));
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![instance.collect()]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn sort_word256() {
+        let max = u64::MAX;
+
+        // ok
+        assert_eq!(
+            try_sort_word256(
+                [[1, 0, 0, 0], [0, max, max, max], [0, max, max, 7]],
+                [[0, max, max, 7], [0, max, max, max], [1, 0, 0, 0]]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            try_sort_word256(
+                [[max, 2, 0, 0], [0, 0, 0, 9], [max, 2, 0, 0]],
+                [[0, 0, 0, 9], [max, 2, 0, 0], [max, 2, 0, 0]]
+            ),
+            Ok(())
+        );
+        // error: sorted by a lower limb
+        assert!(try_sort_word256(
+            [[1, 0, 0, 0], [0, max, max, max], [0, max, max, 7]],
+            [[1, 0, 0, 0], [0, max, max, 7], [0, max, max, max]]
+        )
+        .is_err());
+        // error: limbs mixed between words
+        assert!(try_sort_word256(
+            [[1, 0, 0, 0], [0, max, max, max], [0, max, max, 7]],
+            [[0, max, max, 7], [0, max, max, max], [1, 0, 0, 7]]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn limbs_from_be_bytes() {
+        let mut bytes = [0u8; 32];
+        bytes[7] = 1;
+        bytes[31] = 2;
+        assert_eq!(
+            SortWord256Chip::<Fp, 2>::limbs_from_be_bytes(bytes),
+            [1, 0, 0, 2].map(Fp::from)
+        );
+    }
+}
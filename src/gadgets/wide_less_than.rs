@@ -0,0 +1,406 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Chip, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::{
+    gadgets::is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    gadgets::less_than::{LtChip, LtConfig, LtInstruction},
+    gadgets::{Gadget, Gated, SharedColumns},
+    table::U8Table,
+    util::expr_from_bytes,
+};
+
+/// Number of bytes of the low limb of the operands.
+const LO_BYTES: usize = 16;
+
+/// Instruction that the WideLt chip needs to implement.
+pub trait WideLtInstruction<F: FieldExt> {
+    /// Assign the lhs and rhs witnesses to the WideLt chip's region, returning
+    /// the cell of the lt outcome.
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<AssignedCell<F, F>, Error>;
+}
+
+/// Config for the WideLt chip.
+#[derive(Clone, Copy, Debug)]
+pub struct WideLtConfig<F, const N_BYTES: usize> {
+    /// Denotes the lt outcome. If lhs < rhs then lt == 1, otherwise lt == 0.
+    pub lt: Column<Advice>,
+    /// Denotes the little-endian bytes of lhs, each range checked by a lookup
+    /// into the shared u8 table.
+    pub lhs_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the little-endian bytes of rhs.
+    pub rhs_bytes: [Column<Advice>; N_BYTES],
+    /// Denotes the comparison of the high limbs, i.e. the bytes from
+    /// LO_BYTES on.
+    pub hi_lt: LtConfig<F, LO_BYTES>,
+    /// Denotes whether the high limbs are equal.
+    pub hi_eq: IsZeroConfig,
+    /// Denotes the comparison of the low limbs, i.e. the first LO_BYTES bytes.
+    pub lo_lt: LtConfig<F, LO_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> WideLtConfig<F, N_BYTES> {
+    /// Returns an expression that denotes whether lhs < rhs, or not.
+    pub fn is_lt(&self, meta: &mut VirtualCells<F>, rotation: Option<Rotation>) -> Expression<F> {
+        meta.query_advice(self.lt, rotation.unwrap_or_else(Rotation::cur))
+    }
+}
+
+/// Chip that compares lhs < rhs for values of up to 31 bytes, too wide for
+/// the diff of a single Lt chip. Both operands are split into a high and a
+/// low limb of at most 16 bytes each, and lhs < rhs iff the high limb of lhs
+/// is less, or both high limbs are equal and the low limb of lhs is less.
+#[derive(Clone, Debug)]
+pub struct WideLtChip<F, const N_BYTES: usize> {
+    config: WideLtConfig<F, N_BYTES>,
+}
+
+impl<F: FieldExt, const N_BYTES: usize> WideLtChip<F, N_BYTES> {
+    /// Configures the WideLt chip.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        lhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        rhs: impl FnOnce(&mut VirtualCells<F>) -> Expression<F>,
+        u8_table: U8Table,
+    ) -> WideLtConfig<F, N_BYTES> {
+        assert!(
+            N_BYTES > LO_BYTES && N_BYTES < 32,
+            "wide values have 17 to 31 bytes"
+        );
+
+        let lt = meta.advice_column();
+        meta.enable_equality(lt);
+        let lhs_bytes = [(); N_BYTES].map(|_| meta.advice_column());
+        let rhs_bytes = [(); N_BYTES].map(|_| meta.advice_column());
+        u8_table.range_check(meta, &q_enable, &lhs_bytes);
+        u8_table.range_check(meta, &q_enable, &rhs_bytes);
+        let [hi_lt_col, lo_lt_col, hi_eq_col, hi_diff_inv] = [(); 4].map(|_| meta.advice_column());
+        let hi_diff = [(); LO_BYTES].map(|_| meta.advice_column());
+        let lo_diff = [(); LO_BYTES].map(|_| meta.advice_column());
+
+        // the limbs of both operands, as (lo, hi)
+        let limbs = |meta: &mut VirtualCells<F>, bytes: &[Column<Advice>; N_BYTES]| {
+            let bytes: Vec<_> = bytes
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::cur()))
+                .collect();
+            (
+                expr_from_bytes(&bytes[..LO_BYTES]),
+                expr_from_bytes(&bytes[LO_BYTES..]),
+            )
+        };
+
+        let hi_lt = LtChip::configure(
+            meta,
+            &q_enable,
+            |meta| limbs(meta, &lhs_bytes).1,
+            |meta| limbs(meta, &rhs_bytes).1,
+            hi_lt_col,
+            hi_diff,
+            u8_table,
+        );
+        let hi_eq = IsZeroChip::configure(
+            meta,
+            &q_enable,
+            |meta| limbs(meta, &lhs_bytes).1 - limbs(meta, &rhs_bytes).1,
+            hi_eq_col,
+            hi_diff_inv,
+        );
+        let lo_lt = LtChip::configure(
+            meta,
+            &q_enable,
+            |meta| limbs(meta, &lhs_bytes).0,
+            |meta| limbs(meta, &rhs_bytes).0,
+            lo_lt_col,
+            lo_diff,
+            u8_table,
+        );
+
+        meta.create_gate("wide lt gate", |meta| {
+            let q_enable = q_enable(meta);
+            let lt = meta.query_advice(lt, Rotation::cur());
+
+            let lhs_bytes: Vec<_> = lhs_bytes
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::cur()))
+                .collect();
+            let rhs_bytes: Vec<_> = rhs_bytes
+                .iter()
+                .map(|c| meta.query_advice(*c, Rotation::cur()))
+                .collect();
+
+            // both operands fit in the field, so their bytes are unique
+            let check_lhs = lhs(meta) - expr_from_bytes(&lhs_bytes);
+            let check_rhs = rhs(meta) - expr_from_bytes(&rhs_bytes);
+            let check_lt =
+                lt - hi_lt.is_lt(meta, None) - hi_eq.is_zero(meta, None) * lo_lt.is_lt(meta, None);
+
+            [check_lhs, check_rhs, check_lt]
+                .into_iter()
+                .map(move |poly| q_enable.clone() * poly)
+        });
+
+        WideLtConfig {
+            lt,
+            lhs_bytes,
+            rhs_bytes,
+            hi_lt,
+            hi_eq,
+            lo_lt,
+        }
+    }
+
+    /// Constructs a WideLt chip given a config.
+    pub fn construct(config: WideLtConfig<F, N_BYTES>) -> WideLtChip<F, N_BYTES> {
+        WideLtChip { config }
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> WideLtInstruction<F> for WideLtChip<F, N_BYTES> {
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lhs: F,
+        rhs: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = self.config();
+
+        let mut limbs = vec![];
+        for (value, columns) in [(lhs, config.lhs_bytes), (rhs, config.rhs_bytes)] {
+            let bytes = value.to_repr();
+            let bytes = &bytes.as_ref()[..N_BYTES];
+            for (idx, (byte, column)) in bytes.iter().zip(columns).enumerate() {
+                region.assign_advice(
+                    || format!("wide lt chip: byte {}", idx),
+                    column,
+                    offset,
+                    || Value::known(F::from(*byte as u64)),
+                )?;
+            }
+
+            let limb = |bytes: &[u8]| {
+                let mut le_bytes = [0u8; 16];
+                le_bytes[..bytes.len()].copy_from_slice(bytes);
+                F::from_u128(u128::from_le_bytes(le_bytes))
+            };
+            limbs.push((limb(&bytes[..LO_BYTES]), limb(&bytes[LO_BYTES..])));
+        }
+        let [(lhs_lo, lhs_hi), (rhs_lo, rhs_hi)]: [(F, F); 2] = limbs.try_into().unwrap();
+
+        LtChip::construct(config.hi_lt).assign(region, offset, lhs_hi, rhs_hi)?;
+        IsZeroChip::construct(config.hi_eq).assign(region, offset, lhs_hi - rhs_hi)?;
+        LtChip::construct(config.lo_lt).assign(region, offset, lhs_lo, rhs_lo)?;
+
+        let lt = lhs_hi < rhs_hi || (lhs_hi == rhs_hi && lhs_lo < rhs_lo);
+        region.assign_advice(
+            || "wide lt chip: lt",
+            config.lt,
+            offset,
+            || Value::known(F::from(lt as u64)),
+        )
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Chip<F> for WideLtChip<F, N_BYTES> {
+    type Config = WideLtConfig<F, N_BYTES>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N_BYTES: usize> Gadget<F> for WideLtChip<F, N_BYTES> {
+    type Config = Gated<WideLtConfig<F, N_BYTES>, 2>;
+    type Params = ();
+    type Input = [AssignedCell<F, F>; 2];
+    type Output = AssignedCell<F, F>;
+
+    fn configure(meta: &mut ConstraintSystem<F>, columns: &SharedColumns, _: ()) -> Self::Config {
+        let selector = meta.complex_selector();
+        let inputs = columns.advice::<2>(0);
+        let chip = WideLtChip::configure(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(inputs[0], Rotation::cur()),
+            |meta| meta.query_advice(inputs[1], Rotation::cur()),
+            columns.u8_table,
+        );
+        Gated {
+            selector,
+            inputs,
+            chip,
+        }
+    }
+
+    fn assign(
+        config: &Self::Config,
+        mut layouter: impl Layouter<F>,
+        [lhs, rhs]: Self::Input,
+    ) -> Result<Self::Output, Error> {
+        layouter.assign_region(
+            || "wide lt gadget",
+            |mut region| {
+                let [lhs, rhs] = config.enable(&mut region, 0, [&lhs, &rhs])?;
+                WideLtChip::construct(config.chip).assign(&mut region, 0, lhs, rhs)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WideLtChip, WideLtConfig, WideLtInstruction};
+    use crate::{table::U8Table, util::pow_of_two};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig<F> {
+        q_enable: Selector,
+        value_a: Column<Advice>,
+        value_b: Column<Advice>,
+        check: Column<Advice>,
+        lt: WideLtConfig<F, 31>,
+        u8_table: U8Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        values: Vec<(F, F)>,
+        // checks[i] = lt(values[i].0, values[i].1)
+        checks: Vec<bool>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let (value_a, value_b) = (meta.advice_column(), meta.advice_column());
+            let check = meta.advice_column();
+            meta.enable_equality(check);
+            let u8_table = U8Table::configure(meta);
+
+            let lt = WideLtChip::configure(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| meta.query_advice(value_a, Rotation::cur()),
+                |meta| meta.query_advice(value_b, Rotation::cur()),
+                u8_table,
+            );
+
+            Self::Config {
+                q_enable,
+                value_a,
+                value_b,
+                check,
+                lt,
+                u8_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.u8_table.load(&mut layouter)?;
+            let chip = WideLtChip::construct(config.lt);
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (idx, ((value_a, value_b), check)) in
+                        self.values.iter().zip(&self.checks).enumerate()
+                    {
+                        config.q_enable.enable(&mut region, idx)?;
+                        region.assign_advice(
+                            || "value_a",
+                            config.value_a,
+                            idx,
+                            || Value::known(*value_a),
+                        )?;
+                        region.assign_advice(
+                            || "value_b",
+                            config.value_b,
+                            idx,
+                            || Value::known(*value_b),
+                        )?;
+                        let lt_cell = chip.assign(&mut region, idx, *value_a, *value_b)?;
+                        let check_cell = region.assign_advice(
+                            || "check",
+                            config.check,
+                            idx,
+                            || Value::known(F::from(*check as u64)),
+                        )?;
+                        region.constrain_equal(lt_cell.cell(), check_cell.cell())?;
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    // hi * 2^128 + lo
+    fn wide(hi: u128, lo: u128) -> Fp {
+        Fp::from_u128(hi) * pow_of_two::<Fp>(128) + Fp::from_u128(lo)
+    }
+
+    fn try_wide_lt(values: Vec<(Fp, Fp)>, checks: Vec<bool>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit { values, checks };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn wide_is_lt() {
+        let max_hi = (1 << 120) - 1;
+
+        // ok
+        assert_eq!(
+            try_wide_lt(
+                vec![
+                    (wide(1, u128::MAX), wide(2, 0)),
+                    (wide(2, 0), wide(1, u128::MAX)),
+                    (wide(max_hi, 5), wide(max_hi, 6)),
+                    (wide(max_hi, 6), wide(max_hi, 6)),
+                    (wide(0, 7), wide(max_hi, 0)),
+                ],
+                vec![true, false, true, false, true]
+            ),
+            Ok(())
+        );
+        // error
+        assert!(try_wide_lt(vec![(wide(2, 0), wide(1, u128::MAX))], vec![true]).is_err());
+        assert!(try_wide_lt(vec![(wide(max_hi, 5), wide(max_hi, 6))], vec![false]).is_err());
+        assert!(try_wide_lt(vec![(wide(3, 3), wide(3, 3))], vec![true]).is_err());
+    }
+}
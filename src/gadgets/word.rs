@@ -0,0 +1,143 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Region, Value},
+    plonk::{Advice, Column, Error, Expression, VirtualCells},
+    poly::Rotation,
+};
+
+use crate::util::expr_from_be_bytes;
+
+/// The number of 64-bit limbs of a 256-bit word.
+pub const WORD_LIMBS: usize = 4;
+
+/// An Ethereum uint256, which does not fit in the field, as its four 64-bit
+/// limbs, most significant first. A word is laid out down a column, limb j
+/// on the row offset + j, as the sortWord256 chip takes it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Word<T>(pub [T; WORD_LIMBS]);
+
+impl<T> Word<T> {
+    /// Returns the limbs, most significant first.
+    pub fn limbs(&self) -> &[T; WORD_LIMBS] {
+        &self.0
+    }
+
+    /// Returns the word of the limbs mapped by f.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Word<U> {
+        Word(self.0.map(f))
+    }
+}
+
+impl<F: FieldExt> Word<F> {
+    /// Returns the word of big-endian bytes.
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        Word(std::array::from_fn(|j| {
+            F::from(u64::from_be_bytes(
+                bytes[8 * j..8 * j + 8].try_into().unwrap(),
+            ))
+        }))
+    }
+
+    /// Returns the big-endian bytes of the word. Limbs wider than 64 bits
+    /// keep their low bytes.
+    pub fn to_be_bytes(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (j, limb) in self.0.iter().enumerate() {
+            let repr = limb.to_repr();
+            for (k, byte) in repr.as_ref()[..8].iter().enumerate() {
+                bytes[8 * j + 7 - k] = *byte;
+            }
+        }
+        bytes
+    }
+
+    /// Assigns the limbs to the rows from offset of a column.
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        offset: usize,
+    ) -> Result<Word<AssignedCell<F, F>>, Error> {
+        Word(self.0.map(Value::known)).assign_values(region, column, offset)
+    }
+}
+
+impl<F: FieldExt> Word<Value<F>> {
+    /// Assigns the limb witnesses to the rows from offset of a column.
+    pub fn assign_values(
+        &self,
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        offset: usize,
+    ) -> Result<Word<AssignedCell<F, F>>, Error> {
+        let mut cells = Vec::with_capacity(WORD_LIMBS);
+        for (j, limb) in self.0.iter().enumerate() {
+            cells.push(region.assign_advice(
+                || format!("word limb {}", j),
+                column,
+                offset + j,
+                || *limb,
+            )?);
+        }
+        Ok(Word(cells.try_into().unwrap()))
+    }
+}
+
+impl<F: FieldExt> Word<AssignedCell<F, F>> {
+    /// Returns the limb witnesses.
+    pub fn value(&self) -> Word<Value<F>> {
+        Word(self.0.each_ref().map(|cell| cell.value().copied()))
+    }
+
+    /// Copies the limbs to the rows from offset of a column.
+    pub fn copy_advice(
+        &self,
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        offset: usize,
+    ) -> Result<Word<AssignedCell<F, F>>, Error> {
+        let mut cells = Vec::with_capacity(WORD_LIMBS);
+        for (j, limb) in self.0.iter().enumerate() {
+            cells.push(limb.copy_advice(
+                || format!("word limb {}", j),
+                region,
+                column,
+                offset + j,
+            )?);
+        }
+        Ok(Word(cells.try_into().unwrap()))
+    }
+}
+
+impl<F: FieldExt> Word<Expression<F>> {
+    /// Returns the limbs of the word in a column, on the rows from rotation.
+    pub fn query(meta: &mut VirtualCells<F>, column: Column<Advice>, rotation: i32) -> Self {
+        Word(std::array::from_fn(|j| {
+            meta.query_advice(column, Rotation(rotation + j as i32))
+        }))
+    }
+
+    /// Returns the word of big-endian byte expressions.
+    pub fn from_be_bytes_expr(bytes: &[Expression<F>; 32]) -> Self {
+        Word(std::array::from_fn(|j| {
+            expr_from_be_bytes(&bytes[8 * j..8 * j + 8])
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Word;
+    use halo2_proofs::pasta::Fp;
+
+    #[test]
+    fn be_bytes() {
+        let mut bytes = [0u8; 32];
+        bytes[7] = 1;
+        bytes[8] = 0xab;
+        bytes[31] = 2;
+        let word = Word::<Fp>::from_be_bytes(bytes);
+        assert_eq!(word, Word([1, 0xab << 56, 0, 2].map(Fp::from)));
+        assert_eq!(word.to_be_bytes(), bytes);
+    }
+}
@@ -0,0 +1,22 @@
+use halo2_proofs::{arithmetic::FieldExt, dev::CircuitLayout, plonk::Circuit};
+use plotters::prelude::*;
+use std::{error::Error, path::Path};
+
+/// Renders the layout of the circuit on 2**k rows to a PNG, to see how its
+/// regions are packed in the columns and spot the unused area. Equality
+/// constrained cells are marked, and their constraints drawn.
+pub fn render_layout<F: FieldExt, C: Circuit<F>>(
+    path: impl AsRef<Path>,
+    k: u32,
+    circuit: &C,
+) -> Result<(), Box<dyn Error>> {
+    let root = BitMapBackend::new(path.as_ref(), (1024, 768)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let root = root.titled("Circuit layout", ("sans-serif", 40))?;
+    CircuitLayout::default()
+        .mark_equality_cells(true)
+        .show_equality_constraints(true)
+        .render(k, circuit, &root)?;
+    root.present()?;
+    Ok(())
+}
@@ -1,3 +1,26 @@
-mod less_than;
-mod sort;
-mod util;
\ No newline at end of file
+pub mod bindings;
+pub mod constraint_builder;
+pub mod cost;
+#[cfg(feature = "ffi")]
+mod ffi;
+pub mod gadgets;
+#[cfg(feature = "dev-graph")]
+pub mod layout;
+#[cfg(feature = "node")]
+// napi-derive only registers the exports outside of tests
+#[cfg_attr(test, allow(dead_code))]
+mod node;
+pub mod prover;
+pub mod ptau;
+pub mod table;
+pub mod testing;
+pub mod transcript;
+pub mod util;
+#[cfg(feature = "wasm")]
+mod wasm;
+pub mod witness;
+
+/// The version of the circuits of the crate, embedded in every Proof. It is
+/// bumped with any change to the constraints of a circuit, so that proofs of
+/// the previous circuits are rejected rather than failing to verify.
+pub const CIRCUIT_VERSION: u32 = 2;
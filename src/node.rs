@@ -0,0 +1,42 @@
+use napi::bindgen_prelude::{BigInt, Buffer, Error, Result};
+use napi_derive::napi;
+
+use crate::bindings;
+
+/// Returns the u64 values of JavaScript bigints.
+fn to_u64(values: &[BigInt]) -> Result<Vec<u64>> {
+    values
+        .iter()
+        .map(|value| match value.get_u64() {
+            (false, value, true) => Ok(value),
+            _ => Err(Error::from_reason("values must be u64")),
+        })
+        .collect()
+}
+
+fn to_napi(e: halo2_proofs::plonk::Error) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+/// Returns the verifying key of the circuit sorting n values, n being a
+/// power of two from 2 to 64.
+#[napi]
+pub fn keygen(n: u32) -> Result<Buffer> {
+    Ok(bindings::sort_vk(n as usize).map_err(to_napi)?.into())
+}
+
+/// Returns the proof that the values sort to their ascending order. The
+/// public inputs are the values followed by the sorted values.
+#[napi]
+pub fn prove(values: Vec<BigInt>) -> Result<Buffer> {
+    Ok(bindings::prove_sort(&to_u64(&values)?)
+        .map_err(to_napi)?
+        .into())
+}
+
+/// Returns whether the proof of prove is valid for the values, against the
+/// verifying key of keygen.
+#[napi]
+pub fn verify(values: Vec<BigInt>, proof: Buffer, vk: Buffer) -> Result<bool> {
+    Ok(bindings::verify_sort_with_vk(&to_u64(&values)?, &proof, &vk).is_ok())
+}
@@ -0,0 +1,962 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Value,
+    pasta::{group::ff::PrimeField, EqAffine, Fp},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Any, Assigned, Assignment,
+        BatchVerifier, Circuit, Column, ConstraintSystem, Error, Fixed, FloorPlanner, Instance,
+        ProvingKey, Selector, SingleVerifier, VerifyingKey,
+    },
+    poly::commitment::Params,
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge, Transcript, TranscriptRead,
+        TranscriptWrite,
+    },
+};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{OsRng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::CIRCUIT_VERSION;
+
+/// Returns the IPA params of 2**k rows and the proving key of the circuit,
+/// whose verifying key is `pk.get_vk()`. The keys only depend on the shape
+/// of the circuit, so it may be without witnesses. These are IPA keys over
+/// pasta, the only commitment scheme of halo2 0.2.
+pub fn setup<C: Circuit<Fp>>(
+    k: u32,
+    circuit: &C,
+) -> Result<(Params<EqAffine>, ProvingKey<EqAffine>), Error> {
+    let params = Params::new(k);
+    let vk = keygen_vk(&params, circuit)?;
+    let pk = keygen_pk(&params, vk, circuit)?;
+    Ok((params, pk))
+}
+
+/// Returns the number of rows the synthesis of the circuit assigns, from a
+/// pass that only records the rows, as keygen_vk does.
+pub fn rows_used<F: FieldExt, C: Circuit<F>>(circuit: &C) -> Result<usize, Error> {
+    let mut cs = ConstraintSystem::default();
+    let config = C::configure(&mut cs);
+    // the counter only records rows, so fresh fixed columns stand in for the
+    // constant columns of the circuit, which halo2 0.2 does not expose
+    let constants = (0..CsSizes::of(&cs).constants)
+        .map(|_| cs.fixed_column())
+        .collect();
+    let mut counter = RowCounter::default();
+    C::FloorPlanner::synthesize(&mut counter, circuit, config, constants)?;
+    Ok(counter.rows)
+}
+
+/// The sizes of a constraint system. halo2 0.2 keeps them pub(crate), so
+/// they are read back from its derived Debug output, whose layout is that
+/// of the exact halo2_proofs version Cargo.toml pins.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct CsSizes {
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    pub gates: usize,
+    pub lookups: usize,
+    pub constants: usize,
+}
+
+impl CsSizes {
+    pub fn of<F: FieldExt>(cs: &ConstraintSystem<F>) -> Self {
+        let debug = format!("{:#?}", cs);
+        // the fields of the ConstraintSystem are indented by 4 spaces and
+        // the items of its vectors by 8
+        let number = |field: &str| {
+            let prefix = format!("    {}: ", field);
+            debug
+                .lines()
+                .find_map(|line| line.strip_prefix(&prefix))
+                .and_then(|value| value.trim_end_matches(',').parse().ok())
+                .unwrap_or_default()
+        };
+        let items = |field: &str| {
+            let header = format!("    {}: [", field);
+            debug
+                .lines()
+                .skip_while(|line| *line != header)
+                .skip(1)
+                .take_while(|line| *line != "    ],")
+                .filter(|line| line.starts_with("        ") && !line[8..].starts_with([' ', '}']))
+                .count()
+        };
+        CsSizes {
+            advice_columns: number("num_advice_columns"),
+            fixed_columns: number("num_fixed_columns"),
+            instance_columns: number("num_instance_columns"),
+            selectors: number("num_selectors"),
+            gates: items("gates"),
+            lookups: items("lookups"),
+            constants: items("constants"),
+        }
+    }
+}
+
+/// Returns the smallest k for which the circuit fits in 2**k rows, along
+/// with the rows halo2 reserves for blinding, instead of guessing k until
+/// keygen stops failing with NotEnoughRowsAvailable. The values of the
+/// instance columns, only known when proving, must fit in those rows too,
+/// see min_k_with_instances.
+pub fn min_k<F: FieldExt, C: Circuit<F>>(circuit: &C) -> Result<u32, Error> {
+    min_k_with_instances(circuit, 0)
+}
+
+/// Like min_k, for instance columns of up to instance_rows values.
+pub fn min_k_with_instances<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    instance_rows: usize,
+) -> Result<u32, Error> {
+    let mut cs = ConstraintSystem::<F>::default();
+    C::configure(&mut cs);
+    // the last blinding_factors() + 1 rows are not usable
+    let rows = rows_used(circuit)?.max(instance_rows) + cs.blinding_factors() + 1;
+    Ok(rows
+        .max(cs.minimum_rows())
+        .next_power_of_two()
+        .trailing_zeros())
+}
+
+/// Returns the proof of the circuit, given the values of its instance
+/// columns, with a Blake2b transcript.
+pub fn prove<C: Circuit<Fp>>(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+) -> Result<Vec<u8>, Error> {
+    prove_with_rng(params, pk, circuit, instances, OsRng)
+}
+
+/// Returns the proof of the circuit as prove does, drawing its blinding
+/// factors from a ChaCha20 RNG seeded with seed, so that the same seed gives
+/// the same proof bytes.
+///
+/// Anyone knowing the seed can unblind the witness from the proof, so this
+/// is for tests and reproducibility audits only, never for proofs of
+/// private inputs.
+pub fn prove_deterministic<C: Circuit<Fp>>(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+    seed: u64,
+) -> Result<Vec<u8>, Error> {
+    let rng = ChaCha20Rng::seed_from_u64(seed);
+    prove_with_rng(params, pk, circuit, instances, rng)
+}
+
+/// Returns the proof of the circuit with a Blake2b transcript, drawing its
+/// blinding factors from rng.
+pub fn prove_with_rng<C: Circuit<Fp>>(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+    rng: impl RngCore,
+) -> Result<Vec<u8>, Error> {
+    let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+    prove_with_transcript(params, pk, circuit, instances, rng, &mut transcript)?;
+    Ok(transcript.finalize())
+}
+
+/// Writes the proof of the circuit to the transcript, given the values of
+/// its instance columns, drawing its blinding factors from rng.
+///
+/// Blake2b is cheap natively but not in a circuit, where the Poseidon
+/// transcript of [`crate::transcript::PoseidonWrite`] recomputes the
+/// challenges instead.
+pub fn prove_with_transcript<C, E, T>(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+    rng: impl RngCore,
+    transcript: &mut T,
+) -> Result<(), Error>
+where
+    C: Circuit<Fp>,
+    E: EncodedChallenge<EqAffine>,
+    T: TranscriptWrite<EqAffine, E>,
+{
+    create_proof(params, pk, &[circuit], &[instances], rng, transcript)
+}
+
+/// Verifies a proof with a Blake2b transcript against the values of the
+/// instance columns it was created with.
+pub fn verify(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &[u8],
+    instances: &[&[Fp]],
+) -> Result<(), Error> {
+    let mut transcript = Blake2bRead::<_, EqAffine, Challenge255<_>>::init(proof);
+    verify_with_transcript(params, vk, instances, &mut transcript)
+}
+
+/// Verifies the proof read from the transcript against the values of the
+/// instance columns it was created with.
+pub fn verify_with_transcript<E, T>(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    instances: &[&[Fp]],
+    transcript: &mut T,
+) -> Result<(), Error>
+where
+    E: EncodedChallenge<EqAffine>,
+    T: TranscriptRead<EqAffine, E>,
+{
+    let strategy = SingleVerifier::new(params);
+    verify_proof(params, vk, strategy, &[instances], transcript)
+}
+
+/// A commitment scheme of the prover, so that circuits and their callers
+/// can be written once against it. `Ipa` is the only one halo2 0.2 has, so
+/// there is no feature to select another (see BACKLOG.md).
+pub trait Backend {
+    type Scalar: FieldExt;
+    type Params;
+    type ProvingKey;
+    type VerifyingKey;
+
+    /// Returns the params of 2**k rows and the proving key of the circuit.
+    fn setup<C: Circuit<Self::Scalar>>(
+        k: u32,
+        circuit: &C,
+    ) -> Result<(Self::Params, Self::ProvingKey), Error>;
+
+    /// Returns the verifying key of a proving key.
+    fn vk(pk: &Self::ProvingKey) -> &Self::VerifyingKey;
+
+    /// Returns the proof of the circuit, given the values of its instance
+    /// columns.
+    fn prove<C: Circuit<Self::Scalar>>(
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        circuit: C,
+        instances: &[&[Self::Scalar]],
+    ) -> Result<Vec<u8>, Error>;
+
+    /// Verifies a proof against the values of the instance columns it was
+    /// created with.
+    fn verify(
+        params: &Self::Params,
+        vk: &Self::VerifyingKey,
+        proof: &[u8],
+        instances: &[&[Self::Scalar]],
+    ) -> Result<(), Error>;
+}
+
+/// IPA over the pasta curves, with a Blake2b transcript.
+#[derive(Clone, Copy, Debug)]
+pub struct Ipa;
+
+impl Backend for Ipa {
+    type Scalar = Fp;
+    type Params = Params<EqAffine>;
+    type ProvingKey = ProvingKey<EqAffine>;
+    type VerifyingKey = VerifyingKey<EqAffine>;
+
+    fn setup<C: Circuit<Fp>>(
+        k: u32,
+        circuit: &C,
+    ) -> Result<(Self::Params, Self::ProvingKey), Error> {
+        setup(k, circuit)
+    }
+
+    fn vk(pk: &Self::ProvingKey) -> &Self::VerifyingKey {
+        pk.get_vk()
+    }
+
+    fn prove<C: Circuit<Fp>>(
+        params: &Self::Params,
+        pk: &Self::ProvingKey,
+        circuit: C,
+        instances: &[&[Fp]],
+    ) -> Result<Vec<u8>, Error> {
+        prove(params, pk, circuit, instances)
+    }
+
+    fn verify(
+        params: &Self::Params,
+        vk: &Self::VerifyingKey,
+        proof: &[u8],
+        instances: &[&[Fp]],
+    ) -> Result<(), Error> {
+        verify(params, vk, proof, instances)
+    }
+}
+
+/// Returns a 0x-prefixed hex fingerprint of the verifying key, a Blake2b
+/// hash of its constraint system, fixed commitments and permutation, the
+/// same digest halo2 absorbs into the proof transcript. It changes with any
+/// change to the circuit, so verifiers can pin the circuits they accept.
+pub fn vk_fingerprint(vk: &VerifyingKey<EqAffine>) -> String {
+    field_to_hex(&vk_digest(vk))
+}
+
+fn vk_digest(vk: &VerifyingKey<EqAffine>) -> Fp {
+    let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+    vk.hash_into(&mut transcript)
+        .expect("hashing into a transcript does not fail");
+    transcript.squeeze_challenge().get_scalar()
+}
+
+/// Writes the verifying key. halo2 0.2 cannot serialize verifying keys, so
+/// only the 32 bytes of its digest, see vk_fingerprint, are written, and
+/// read_vk rebuilds the key with keygen_vk.
+pub fn write_vk<W: Write>(vk: &VerifyingKey<EqAffine>, writer: &mut W) -> io::Result<()> {
+    writer.write_all(vk_digest(vk).to_repr().as_ref())
+}
+
+/// Reads a verifying key of the circuit written by write_vk: runs keygen_vk
+/// on the circuit, which may be without witnesses, and checks that the key
+/// is the one written, so a key written for another circuit is rejected.
+pub fn read_vk<C: Circuit<Fp>, R: Read>(
+    reader: &mut R,
+    params: &Params<EqAffine>,
+    circuit: &C,
+) -> Result<VerifyingKey<EqAffine>, Error> {
+    let mut digest = [0; 32];
+    reader.read_exact(&mut digest).map_err(Error::Transcript)?;
+    let vk = keygen_vk(params, circuit)?;
+    if vk_digest(&vk).to_repr() != digest {
+        return Err(Error::Transcript(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the verifying key was written for another circuit or params",
+        )));
+    }
+    Ok(vk)
+}
+
+/// Writes the proving key. halo2 0.2 cannot serialize proving keys either,
+/// so this writes its verifying key with write_vk, and read_pk reruns both
+/// keygen_vk and keygen_pk: the file only pins the circuit the key is of.
+pub fn write_pk<W: Write>(pk: &ProvingKey<EqAffine>, writer: &mut W) -> io::Result<()> {
+    write_vk(pk.get_vk(), writer)
+}
+
+/// Reads a proving key of the circuit written by write_pk, rejecting a key
+/// written for another circuit, see read_vk.
+pub fn read_pk<C: Circuit<Fp>, R: Read>(
+    reader: &mut R,
+    params: &Params<EqAffine>,
+    circuit: &C,
+) -> Result<ProvingKey<EqAffine>, Error> {
+    let vk = read_vk(reader, params, circuit)?;
+    keygen_pk(params, vk, circuit)
+}
+
+/// On-disk cache of the params and keys of circuits, so that proving the
+/// same circuit shape again skips the params generation. The keys
+/// themselves are rebuilt on every read, see write_pk, and only checked
+/// against the cached entry.
+///
+/// Entries are keyed by a name given by the caller, which must change with
+/// anything changing the shape of the circuit (its configuration, N_BYTES,
+/// the number of rows it lays out...), and by k. A stale entry is rejected
+/// by setup, so delete the directory when the circuits change.
+#[derive(Clone, Debug)]
+pub struct KeyCache {
+    dir: PathBuf,
+}
+
+impl KeyCache {
+    /// Creates a cache in the directory, which is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        KeyCache { dir: dir.into() }
+    }
+
+    /// Returns the params of 2**k rows and the proving key of the circuit
+    /// named key, as setup does, reading them from the cache if present and
+    /// writing them to it otherwise.
+    pub fn setup<C: Circuit<Fp>>(
+        &self,
+        key: &str,
+        k: u32,
+        circuit: &C,
+    ) -> Result<(Params<EqAffine>, ProvingKey<EqAffine>), Error> {
+        let params_path = self.dir.join(format!("params-k{}.bin", k));
+        let params = match File::open(&params_path) {
+            Ok(file) => Params::read(&mut BufReader::new(file)).map_err(Error::Transcript)?,
+            Err(_) => {
+                let params = Params::new(k);
+                self.write(&params_path, |writer| params.write(writer))?;
+                params
+            }
+        };
+
+        let pk_path = self.dir.join(format!("{}-k{}.pk", key, k));
+        let pk = match File::open(&pk_path) {
+            Ok(file) => read_pk(&mut BufReader::new(file), &params, circuit)?,
+            Err(_) => {
+                let vk = keygen_vk(&params, circuit)?;
+                let pk = keygen_pk(&params, vk, circuit)?;
+                self.write(&pk_path, |writer| write_pk(&pk, writer))?;
+                pk
+            }
+        };
+        Ok((params, pk))
+    }
+
+    /// Writes a cache file through a temporary file, so that an interrupted
+    /// write never leaves a truncated entry behind.
+    fn write(
+        &self,
+        path: &Path,
+        write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    ) -> Result<(), Error> {
+        let tmp = path.with_extension("tmp");
+        (|| {
+            fs::create_dir_all(&self.dir)?;
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            write(&mut writer)?;
+            writer.flush()?;
+            drop(writer);
+            fs::rename(&tmp, path)
+        })()
+        .map_err(Error::Transcript)
+    }
+}
+
+/// A proof along with the values of the instance columns it was created
+/// with, one vector per column, and the CIRCUIT_VERSION of the crate that
+/// created it. In JSON, the proof is a 0x-prefixed hex string and the
+/// instances are 0x-prefixed big-endian hex field elements.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Proof {
+    /// The CIRCUIT_VERSION the proof was created with.
+    pub circuit_version: u32,
+    /// The proof bytes.
+    #[serde(with = "hex_bytes")]
+    pub proof: Vec<u8>,
+    /// The values of the instance columns.
+    #[serde(with = "hex_fields")]
+    pub instances: Vec<Vec<Fp>>,
+}
+
+impl Proof {
+    /// Creates the proof of the circuit, given the values of its instance
+    /// columns.
+    pub fn create<C: Circuit<Fp>>(
+        params: &Params<EqAffine>,
+        pk: &ProvingKey<EqAffine>,
+        circuit: C,
+        instances: &[&[Fp]],
+    ) -> Result<Self, Error> {
+        Ok(Proof {
+            circuit_version: CIRCUIT_VERSION,
+            proof: prove(params, pk, circuit, instances)?,
+            instances: instances.iter().map(|column| column.to_vec()).collect(),
+        })
+    }
+
+    /// Verifies the proof against its instances, rejecting proofs of
+    /// another CIRCUIT_VERSION.
+    pub fn verify(
+        &self,
+        params: &Params<EqAffine>,
+        vk: &VerifyingKey<EqAffine>,
+    ) -> Result<(), Error> {
+        self.check_version()?;
+        let instances: Vec<_> = self.instances.iter().map(Vec::as_slice).collect();
+        verify(params, vk, &self.proof, &instances)
+    }
+
+    /// Verifies proofs of the same circuit at once, sharing the final MSM
+    /// of their openings, which is far cheaper than verifying each. Only
+    /// tells whether all the proofs are valid, not which is not.
+    pub fn verify_batch(
+        params: &Params<EqAffine>,
+        vk: &VerifyingKey<EqAffine>,
+        proofs: &[Proof],
+    ) -> bool {
+        if proofs.iter().any(|proof| proof.check_version().is_err()) {
+            return false;
+        }
+        let mut batch = BatchVerifier::new();
+        for proof in proofs {
+            batch.add_proof(vec![proof.instances.clone()], proof.proof.clone());
+        }
+        batch.finalize(params, vk)
+    }
+
+    /// Checks that the proof was created with the CIRCUIT_VERSION of this
+    /// crate.
+    pub fn check_version(&self) -> Result<(), Error> {
+        if self.circuit_version != CIRCUIT_VERSION {
+            return Err(Error::Transcript(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the proof is of circuit version {}, expected {}",
+                    self.circuit_version, CIRCUIT_VERSION
+                ),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the proof bytes.
+    pub fn to_bytes(&self) -> &[u8] {
+        &self.proof
+    }
+
+    /// Returns the proof bytes as a 0x-prefixed hex string.
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.proof))
+    }
+
+    /// Returns the proof and its instances in the calldata layout of
+    /// encode_instances_for_evm. No on-chain verifier accepts it yet.
+    pub fn to_evm_calldata(&self) -> Vec<u8> {
+        encode_instances_for_evm(&self.instances, &self.proof)
+    }
+
+    /// Returns the proof and its instances as JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Parses a proof and its instances from JSON.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// A proof and its instances along with the metadata relayers need to route
+/// and sanity-check it without halo2: the fingerprint of the verifying key
+/// and the name of the circuit it is a proof of, the k of the params and
+/// when it was created, as seconds since the Unix epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofBundle {
+    /// The CIRCUIT_VERSION the proof was created with.
+    pub circuit_version: u32,
+    /// The proof bytes.
+    #[serde(with = "hex_bytes")]
+    pub proof: Vec<u8>,
+    /// The values of the instance columns.
+    #[serde(with = "hex_fields")]
+    pub instances: Vec<Vec<Fp>>,
+    /// The vk_fingerprint of the verifying key.
+    pub vk_hash: String,
+    /// The name of the circuit, chosen by the prover.
+    pub circuit_id: String,
+    /// The k of the params, the circuit having 2**k rows.
+    pub params_k: u32,
+    /// When the proof was created, in seconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+/// Why a ProofBundle is invalid.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BundleError {
+    /// The proof is of another CIRCUIT_VERSION.
+    CircuitVersion { expected: u32, actual: u32 },
+    /// The proof has no bytes.
+    EmptyProof,
+    /// The vk_hash is not a 0x-prefixed 32-byte hex string.
+    InvalidVkHash,
+    /// The vk_hash is not the fingerprint of the verifying key.
+    VkMismatch,
+    /// The circuit_id is empty.
+    EmptyCircuitId,
+    /// params_k is 0 or above the 2**32 rows the pasta curves support.
+    ParamsK(u32),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::CircuitVersion { expected, actual } => write!(
+                f,
+                "the proof is of circuit version {}, expected {}",
+                actual, expected
+            ),
+            BundleError::EmptyProof => write!(f, "the proof is empty"),
+            BundleError::InvalidVkHash => write!(f, "vk_hash is not a 32-byte hex string"),
+            BundleError::VkMismatch => write!(f, "vk_hash is not the hash of the verifying key"),
+            BundleError::EmptyCircuitId => write!(f, "circuit_id is empty"),
+            BundleError::ParamsK(k) => write!(f, "unsupported params_k {}", k),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl ProofBundle {
+    /// Bundles the proof with the fingerprint of the verifying key it was
+    /// created with, the name of its circuit and the k of its params.
+    pub fn new(
+        proof: Proof,
+        vk: &VerifyingKey<EqAffine>,
+        circuit_id: impl Into<String>,
+        params_k: u32,
+    ) -> Self {
+        ProofBundle {
+            circuit_version: proof.circuit_version,
+            proof: proof.proof,
+            instances: proof.instances,
+            vk_hash: vk_fingerprint(vk),
+            circuit_id: circuit_id.into(),
+            params_k,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        }
+    }
+
+    /// Checks the metadata of the bundle, but not the proof itself, which
+    /// needs the verifying key, see check_vk.
+    pub fn validate(&self) -> Result<(), BundleError> {
+        if self.circuit_version != CIRCUIT_VERSION {
+            return Err(BundleError::CircuitVersion {
+                expected: CIRCUIT_VERSION,
+                actual: self.circuit_version,
+            });
+        }
+        if self.proof.is_empty() {
+            return Err(BundleError::EmptyProof);
+        }
+        let vk_hash = self.vk_hash.strip_prefix("0x").map(hex::decode);
+        if !matches!(vk_hash, Some(Ok(hash)) if hash.len() == 32) {
+            return Err(BundleError::InvalidVkHash);
+        }
+        if self.circuit_id.is_empty() {
+            return Err(BundleError::EmptyCircuitId);
+        }
+        if self.params_k == 0 || self.params_k > 32 {
+            return Err(BundleError::ParamsK(self.params_k));
+        }
+        Ok(())
+    }
+
+    /// Validates the bundle and checks that it is of the verifying key.
+    pub fn check_vk(&self, vk: &VerifyingKey<EqAffine>) -> Result<(), BundleError> {
+        self.validate()?;
+        if self.vk_hash != vk_fingerprint(vk) {
+            return Err(BundleError::VkMismatch);
+        }
+        Ok(())
+    }
+
+    /// Returns the proof and its instances, to be verified.
+    pub fn to_proof(&self) -> Proof {
+        Proof {
+            circuit_version: self.circuit_version,
+            proof: self.proof.clone(),
+            instances: self.instances.clone(),
+        }
+    }
+
+    /// Returns the bundle as JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Parses a bundle from JSON.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Returns the instances as 32-byte big-endian words, column by column,
+/// followed by the proof bytes. This is only a byte layout, the one of the
+/// verifiers snark-verifier generates for KZG proofs over bn254: there is no
+/// on-chain verifier of these IPA proofs over pasta to submit it to (see
+/// BACKLOG.md).
+pub fn encode_instances_for_evm<F: FieldExt>(instances: &[Vec<F>], proof: &[u8]) -> Vec<u8> {
+    let words: usize = instances.iter().map(Vec::len).sum();
+    let mut calldata = Vec::with_capacity(32 * words + proof.len());
+    for value in instances.iter().flatten() {
+        let repr = value.to_repr();
+        let mut word = [0u8; 32];
+        for (byte, repr) in word.iter_mut().rev().zip(repr.as_ref()) {
+            *byte = *repr;
+        }
+        calldata.extend(word);
+    }
+    calldata.extend(proof);
+    calldata
+}
+
+/// Returns the bytes of a hex string, with or without a 0x prefix.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex))
+}
+
+/// Returns the 0x-prefixed big-endian hex string of a field element.
+fn field_to_hex<F: FieldExt>(value: &F) -> String {
+    let mut bytes = value.to_repr();
+    bytes.as_mut().reverse();
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Returns the field element of a big-endian hex string, if it is canonical.
+fn field_from_hex<F: FieldExt>(hex: &str) -> Option<F> {
+    let mut bytes = decode_hex(hex).ok()?;
+    let mut repr = F::Repr::default();
+    if bytes.len() != repr.as_ref().len() {
+        return None;
+    }
+    bytes.reverse();
+    repr.as_mut().copy_from_slice(&bytes);
+    Option::from(F::from_repr(repr))
+}
+
+/// Assignment recording the highest row a synthesis assigns, ignoring the
+/// values.
+#[derive(Debug, Default)]
+struct RowCounter {
+    rows: usize,
+}
+
+impl RowCounter {
+    fn assign(&mut self, row: usize) {
+        self.rows = self.rows.max(row + 1);
+    }
+}
+
+impl<F: FieldExt> Assignment<F> for RowCounter {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.assign(row);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.assign(row);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.assign(row);
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+mod hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        super::decode_hex(&hex).map_err(D::Error::custom)
+    }
+}
+
+mod hex_fields {
+    use halo2_proofs::pasta::Fp;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        instances: &[Vec<Fp>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let hex: Vec<Vec<String>> = instances
+            .iter()
+            .map(|column| column.iter().map(super::field_to_hex).collect())
+            .collect();
+        hex.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<Fp>>, D::Error> {
+        let hex = Vec::<Vec<String>>::deserialize(deserializer)?;
+        hex.iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|value| {
+                        super::field_from_hex(value).ok_or_else(|| {
+                            D::Error::custom(format!("invalid field element {}", value))
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BundleError, Proof, ProofBundle};
+    use crate::CIRCUIT_VERSION;
+    use halo2_proofs::pasta::Fp;
+
+    #[test]
+    fn proof_json() {
+        let proof = Proof {
+            circuit_version: CIRCUIT_VERSION,
+            proof: vec![0xde, 0xad, 0xbe, 0xef],
+            instances: vec![vec![Fp::from(1), Fp::from(0x1234)], vec![]],
+        };
+        assert_eq!(proof.to_hex(), "0xdeadbeef");
+
+        let json = proof.to_json().unwrap();
+        assert!(
+            json.contains("\"0x0000000000000000000000000000000000000000000000000000000000001234\"")
+        );
+        assert_eq!(Proof::from_json(&json).unwrap(), proof);
+        // error: not a field element
+        assert!(Proof::from_json(&json.replace("0x00000000", "0xffffffff")).is_err());
+
+        assert!(proof.check_version().is_ok());
+        // error: another circuit version
+        let proof = Proof {
+            circuit_version: CIRCUIT_VERSION + 1,
+            ..proof
+        };
+        assert!(proof.check_version().is_err());
+    }
+
+    #[test]
+    fn evm_calldata() {
+        let proof = Proof {
+            circuit_version: CIRCUIT_VERSION,
+            proof: vec![0xde, 0xad],
+            instances: vec![vec![Fp::from(1)], vec![Fp::from(0x1234)]],
+        };
+        let calldata = proof.to_evm_calldata();
+        assert_eq!(calldata.len(), 2 * 32 + 2);
+        assert_eq!(calldata[31], 1);
+        assert_eq!(calldata[62..64], [0x12, 0x34]);
+        assert_eq!(calldata[64..], [0xde, 0xad]);
+    }
+
+    #[test]
+    fn proof_bundle() {
+        let bundle = ProofBundle {
+            circuit_version: CIRCUIT_VERSION,
+            proof: vec![0xde, 0xad],
+            instances: vec![vec![Fp::from(1)]],
+            vk_hash: format!("0x{}", "ab".repeat(32)),
+            circuit_id: "sort-4".to_string(),
+            params_k: 9,
+            created_at: 1_700_000_000,
+        };
+        let json = bundle.to_json().unwrap();
+        assert!(json.contains("\"circuit_id\":\"sort-4\""));
+        assert_eq!(ProofBundle::from_json(&json).unwrap(), bundle);
+        assert_eq!(bundle.to_proof().proof, bundle.proof);
+
+        // ok
+        assert_eq!(bundle.validate(), Ok(()));
+        // error
+        let invalid = |bundle: ProofBundle| bundle.validate().unwrap_err();
+        assert_eq!(
+            invalid(ProofBundle {
+                circuit_version: CIRCUIT_VERSION + 1,
+                ..bundle.clone()
+            }),
+            BundleError::CircuitVersion {
+                expected: CIRCUIT_VERSION,
+                actual: CIRCUIT_VERSION + 1
+            }
+        );
+        assert_eq!(
+            invalid(ProofBundle {
+                proof: vec![],
+                ..bundle.clone()
+            }),
+            BundleError::EmptyProof
+        );
+        assert_eq!(
+            invalid(ProofBundle {
+                vk_hash: "0xabcd".to_string(),
+                ..bundle.clone()
+            }),
+            BundleError::InvalidVkHash
+        );
+        assert_eq!(
+            invalid(ProofBundle {
+                circuit_id: String::new(),
+                ..bundle.clone()
+            }),
+            BundleError::EmptyCircuitId
+        );
+        assert_eq!(
+            invalid(ProofBundle {
+                params_k: 33,
+                ..bundle
+            }),
+            BundleError::ParamsK(33)
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use halo2_proofs::{pasta::EqAffine, poly::commitment::Params};
+use std::{
+    fmt,
+    io::{self, Read},
+};
+
+/// The base field modulus of bn254, little-endian, as .ptau files store it.
+const BN254_Q: [u8; 32] = [
+    0x47, 0xfd, 0x7c, 0xd8, 0x16, 0x8c, 0x20, 0x3c, 0x8d, 0xca, 0x71, 0x68, 0x91, 0x6a, 0x81, 0x97,
+    0x5d, 0x58, 0x81, 0x81, 0xb6, 0x45, 0x50, 0xb8, 0x29, 0xa0, 0x31, 0xe1, 0x72, 0x4e, 0x64, 0x30,
+];
+
+/// The largest byte length of a base field element the reader accepts, well
+/// above the 32 bytes of bn254, so that a corrupt n8 can't make it allocate
+/// gigabytes.
+const MAX_N8: u32 = 64;
+
+/// Why a .ptau file is rejected.
+#[derive(Debug)]
+pub enum PtauError {
+    /// Reading the file failed, or it is truncated.
+    Io(io::Error),
+    /// The file is not a .ptau file over bn254.
+    Invalid(&'static str),
+    /// The SRS has 2**power rows, fewer than the 2**k of the circuit.
+    TooFewRows { power: u32, k: u32 },
+    /// The SRS is for KZG over bn254, while the params of halo2 0.2 are IPA
+    /// over pasta, so its points can't be converted.
+    UnsupportedScheme,
+}
+
+impl fmt::Display for PtauError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PtauError::Io(e) => write!(f, "{}", e),
+            PtauError::Invalid(message) => write!(f, "{}", message),
+            PtauError::TooFewRows { power, k } => {
+                write!(f, "the SRS has 2^{} rows, the circuit needs 2^{}", power, k)
+            }
+            PtauError::UnsupportedScheme => write!(
+                f,
+                "a .ptau SRS is for KZG over bn254, the params are IPA over pasta"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PtauError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PtauError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PtauError {
+    fn from(e: io::Error) -> Self {
+        PtauError::Io(e)
+    }
+}
+
+/// Reads the params of a circuit of 2**k rows from a .ptau file, checking
+/// its header and size first. The points are never converted: halo2 0.2
+/// has no KZG params to convert them to, so a valid file is rejected with
+/// PtauError::UnsupportedScheme (see BACKLOG.md).
+pub fn read_params<R: Read>(reader: &mut R, k: u32) -> Result<Params<EqAffine>, PtauError> {
+    PtauHeader::read(reader)?.check_k(k)?;
+    Err(PtauError::UnsupportedScheme)
+}
+
+/// The header of a Perpetual Powers of Tau (.ptau) file, as written by
+/// snarkjs. The file holds the SRS of 2**power rows over bn254.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PtauHeader {
+    /// The byte length of a base field element.
+    pub n8: u32,
+    /// The base field modulus, little-endian.
+    pub q: Vec<u8>,
+    /// The log2 of the number of rows of the SRS.
+    pub power: u32,
+    /// The power of the ceremony the SRS was taken from.
+    pub ceremony_power: u32,
+}
+
+impl PtauHeader {
+    /// Reads the header of a .ptau file, checking that it is over bn254.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, PtauError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != b"ptau" {
+            return Err(PtauError::Invalid("not a .ptau file"));
+        }
+        let _version = read_u32(reader)?;
+        let sections = read_u32(reader)?;
+
+        // The header section is the one of type 1, usually but not
+        // necessarily the first.
+        for _ in 0..sections {
+            let section = read_u32(reader)?;
+            let size = read_u64(reader)?;
+            if section != 1 {
+                if io::copy(&mut reader.take(size), &mut io::sink())? < size {
+                    return Err(PtauError::Io(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "truncated section",
+                    )));
+                }
+                continue;
+            }
+
+            let n8 = read_u32(reader)?;
+            if n8 > MAX_N8 {
+                return Err(PtauError::Invalid("the field element length is too large"));
+            }
+            let mut q = vec![0u8; n8 as usize];
+            reader.read_exact(&mut q)?;
+            if q != BN254_Q {
+                return Err(PtauError::Invalid("the SRS is not over bn254"));
+            }
+            let power = read_u32(reader)?;
+            let ceremony_power = read_u32(reader)?;
+            return Ok(PtauHeader {
+                n8,
+                q,
+                power,
+                ceremony_power,
+            });
+        }
+        Err(PtauError::Invalid("no header section"))
+    }
+
+    /// Checks that the SRS has enough rows for a circuit of 2**k rows.
+    pub fn check_k(&self, k: u32) -> Result<(), PtauError> {
+        if k > self.power {
+            return Err(PtauError::TooFewRows {
+                power: self.power,
+                k,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_params, PtauError, PtauHeader, BN254_Q};
+
+    fn ptau(q: &[u8]) -> Vec<u8> {
+        let mut file = b"ptau".to_vec();
+        file.extend(1u32.to_le_bytes());
+        file.extend(2u32.to_le_bytes());
+        // a section to skip
+        file.extend(2u32.to_le_bytes());
+        file.extend(3u64.to_le_bytes());
+        file.extend([0xff; 3]);
+        // the header
+        file.extend(1u32.to_le_bytes());
+        file.extend(44u64.to_le_bytes());
+        file.extend(32u32.to_le_bytes());
+        file.extend(q);
+        file.extend(10u32.to_le_bytes());
+        file.extend(28u32.to_le_bytes());
+        file
+    }
+
+    #[test]
+    fn ptau_header() {
+        let header = PtauHeader::read(&mut ptau(&BN254_Q).as_slice()).unwrap();
+        assert_eq!(header.power, 10);
+        assert_eq!(header.ceremony_power, 28);
+        // ok
+        assert!(header.check_k(10).is_ok());
+        // error: too few rows
+        assert!(matches!(
+            header.check_k(11),
+            Err(PtauError::TooFewRows { power: 10, k: 11 })
+        ));
+        // error: not bn254
+        assert!(matches!(
+            PtauHeader::read(&mut ptau(&[0; 32]).as_slice()),
+            Err(PtauError::Invalid(_))
+        ));
+        // error: n8 too large
+        let mut file = ptau(&BN254_Q);
+        file[39..43].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(PtauHeader::read(&mut file.as_slice()).is_err());
+        // error: the skipped section is truncated
+        let file = ptau(&BN254_Q);
+        match PtauHeader::read(&mut &file[..26]) {
+            Err(PtauError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+            other => panic!("expected a truncated file, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ptau_params() {
+        // error: a valid SRS can't be converted to IPA params
+        assert!(matches!(
+            read_params(&mut ptau(&BN254_Q).as_slice(), 10),
+            Err(PtauError::UnsupportedScheme)
+        ));
+        // error: the size is checked first
+        assert!(matches!(
+            read_params(&mut ptau(&BN254_Q).as_slice(), 11),
+            Err(PtauError::TooFewRows { .. })
+        ));
+    }
+}
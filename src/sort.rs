@@ -1 +0,0 @@
-mod main;
\ No newline at end of file
@@ -0,0 +1,298 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, TableColumn, VirtualCells},
+    poly::Rotation,
+};
+
+/// Fixed table of the 256 byte values. It is loaded once per circuit and
+/// shared by every chip that range checks bytes.
+#[derive(Clone, Copy, Debug)]
+pub struct U8Table {
+    /// Denotes the byte values 0..256.
+    pub byte: TableColumn,
+}
+
+impl U8Table {
+    /// Configures the u8 table.
+    pub fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            byte: meta.lookup_table_column(),
+        }
+    }
+
+    /// Restricts every byte column to 0 <= byte < 256 at the current row,
+    /// wherever q_enable is set.
+    pub fn range_check<F: FieldExt>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        bytes: &[Column<Advice>],
+    ) {
+        for byte in bytes {
+            meta.lookup(|meta| {
+                let q_enable = q_enable(meta);
+                let byte = meta.query_advice(*byte, Rotation::cur());
+                vec![(q_enable * byte, self.byte)]
+            });
+        }
+    }
+
+    /// Assigns the 256 byte values to the table.
+    pub fn load<F: FieldExt>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "u8 table",
+            |mut table| {
+                for byte in 0..256 {
+                    table.assign_cell(
+                        || "u8 table: byte",
+                        self.byte,
+                        byte,
+                        || Value::known(F::from(byte as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Bitwise operation on a pair of bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl BitwiseOp {
+    /// Applies the operation to lhs and rhs.
+    pub fn apply(&self, lhs: u8, rhs: u8) -> u8 {
+        match self {
+            BitwiseOp::And => lhs & rhs,
+            BitwiseOp::Or => lhs | rhs,
+            BitwiseOp::Xor => lhs ^ rhs,
+        }
+    }
+}
+
+/// Fixed table of (lhs, rhs, lhs op rhs) for every pair of bytes, 65536 rows
+/// in total. As 0 op 0 == 0 for every op, a disabled lookup of (0, 0, 0)
+/// always passes.
+#[derive(Clone, Copy, Debug)]
+pub struct BitwiseTable {
+    /// The operation of the table.
+    pub op: BitwiseOp,
+    /// Denotes the lhs bytes.
+    pub lhs: TableColumn,
+    /// Denotes the rhs bytes.
+    pub rhs: TableColumn,
+    /// Denotes lhs op rhs.
+    pub out: TableColumn,
+}
+
+impl BitwiseTable {
+    /// Configures the bitwise table of op.
+    pub fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>, op: BitwiseOp) -> Self {
+        Self {
+            op,
+            lhs: meta.lookup_table_column(),
+            rhs: meta.lookup_table_column(),
+            out: meta.lookup_table_column(),
+        }
+    }
+
+    /// Assigns every pair of bytes and the result of op to the table.
+    pub fn load<F: FieldExt>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || format!("{:?} table", self.op),
+            |mut table| {
+                for lhs in 0..=u8::MAX {
+                    for rhs in 0..=u8::MAX {
+                        let row = ((lhs as usize) << 8) + rhs as usize;
+                        for (column, value) in [
+                            (self.lhs, lhs),
+                            (self.rhs, rhs),
+                            (self.out, self.op.apply(lhs, rhs)),
+                        ] {
+                            table.assign_cell(
+                                || "bitwise table: cell",
+                                column,
+                                row,
+                                || Value::known(F::from(value as u64)),
+                            )?;
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Fixed table of (i, 2**i) for 0 <= i < 256, so that shifts and bit lengths
+/// look powers of two up instead of computing them with high-degree
+/// expressions. Its first row (0, 1) is what a disabled lookup reads.
+#[derive(Clone, Copy, Debug)]
+pub struct Pow2Table {
+    /// Denotes the exponents 0..256.
+    pub exponent: TableColumn,
+    /// Denotes 2**exponent.
+    pub power: TableColumn,
+}
+
+impl Pow2Table {
+    /// Configures the pow2 table.
+    pub fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            exponent: meta.lookup_table_column(),
+            power: meta.lookup_table_column(),
+        }
+    }
+
+    /// Restricts power == 2**exponent with 0 <= exponent < 256 at the
+    /// current row, wherever q_enable is set.
+    pub fn lookup<F: FieldExt>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        exponent: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        power: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+    ) {
+        meta.lookup(|meta| {
+            let q_enable = q_enable(meta);
+            let exponent = exponent(meta);
+            let power = power(meta);
+            let one = Expression::Constant(F::one());
+            vec![
+                (q_enable.clone() * exponent, self.exponent),
+                (q_enable * (power - one.clone()) + one, self.power),
+            ]
+        });
+    }
+
+    /// Assigns the 256 exponents and their powers of two to the table.
+    pub fn load<F: FieldExt>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "pow2 table",
+            |mut table| {
+                let mut power = F::one();
+                for exponent in 0..256 {
+                    table.assign_cell(
+                        || "pow2 table: exponent",
+                        self.exponent,
+                        exponent,
+                        || Value::known(F::from(exponent as u64)),
+                    )?;
+                    table.assign_cell(
+                        || "pow2 table: power",
+                        self.power,
+                        exponent,
+                        || Value::known(power),
+                    )?;
+                    power = power.double();
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Pow2Table;
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::{MockProver, VerifyFailure},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+        poly::Rotation,
+    };
+
+    #[derive(Clone, Debug)]
+    struct TestCircuitConfig {
+        q_enable: Selector,
+        advice: [Column<Advice>; 2],
+        pow2_table: Pow2Table,
+    }
+
+    #[derive(Default)]
+    struct TestCircuit<F> {
+        pairs: Vec<(F, F)>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for TestCircuit<F> {
+        type Config = TestCircuitConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.complex_selector();
+            let advice = [(); 2].map(|_| meta.advice_column());
+            let pow2_table = Pow2Table::configure(meta);
+            pow2_table.lookup(
+                meta,
+                |meta| meta.query_selector(q_enable),
+                |meta| meta.query_advice(advice[0], Rotation::cur()),
+                |meta| meta.query_advice(advice[1], Rotation::cur()),
+            );
+
+            Self::Config {
+                q_enable,
+                advice,
+                pow2_table,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.pow2_table.load(&mut layouter)?;
+
+            layouter.assign_region(
+                || "witness",
+                |mut region| {
+                    for (idx, (exponent, power)) in self.pairs.iter().enumerate() {
+                        config.q_enable.enable(&mut region, idx)?;
+                        for (column, value) in config.advice.iter().zip([exponent, power]) {
+                            region.assign_advice(
+                                || "pair",
+                                *column,
+                                idx,
+                                || Value::known(*value),
+                            )?;
+                        }
+                    }
+
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn try_pow2(pairs: Vec<(u64, u128)>) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = TestCircuit {
+            pairs: pairs
+                .into_iter()
+                .map(|(exponent, power)| (Fp::from(exponent), Fp::from_u128(power)))
+                .collect(),
+        };
+        let prover = MockProver::<Fp>::run(9, &circuit, vec![]).unwrap();
+        prover.verify()
+    }
+
+    #[test]
+    fn pow2_lookup() {
+        // ok
+        assert_eq!(try_pow2(vec![(0, 1), (3, 8), (127, 1 << 127)]), Ok(()));
+        // error
+        assert!(try_pow2(vec![(3, 9)]).is_err());
+        assert!(try_pow2(vec![(0, 0)]).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    dev::{MockProver, VerifyFailure},
+    plonk::Circuit,
+};
+
+use crate::prover::min_k_with_instances;
+
+/// Runs the circuit in the MockProver, at the smallest k it and the
+/// instances fit in, returning its failures.
+pub fn verify<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+) -> Result<(), Vec<VerifyFailure>> {
+    let instance_rows = instances.iter().map(Vec::len).max().unwrap_or(0);
+    let k = min_k_with_instances(circuit, instance_rows).unwrap();
+    MockProver::run(k, circuit, instances).unwrap().verify()
+}
+
+/// Asserts that the circuit is satisfied by the instances, printing the
+/// failures otherwise.
+#[track_caller]
+pub fn assert_satisfied<F: FieldExt, C: Circuit<F>>(circuit: &C, instances: Vec<Vec<F>>) {
+    if let Err(failures) = verify(circuit, instances) {
+        panic!("{}", describe(&failures));
+    }
+}
+
+/// Asserts that the circuit fails a constraint of the gate, or the
+/// constraint, named name with the instances.
+#[track_caller]
+pub fn assert_fails_at<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    instances: Vec<Vec<F>>,
+    name: &str,
+) {
+    let failures = match verify(circuit, instances) {
+        Ok(()) => panic!("the circuit is satisfied, expected a failure of '{}'", name),
+        Err(failures) => failures,
+    };
+    let name = format!("('{}')", name);
+    if !failures.iter().any(|failure| match failure {
+        VerifyFailure::ConstraintNotSatisfied { .. } => failure.to_string().contains(&name),
+        _ => false,
+    }) {
+        panic!(
+            "expected a failure of {}, got:\n{}",
+            name,
+            describe(&failures)
+        );
+    }
+}
+
+fn describe(failures: &[VerifyFailure]) -> String {
+    failures
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{assert_fails_at, assert_satisfied};
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        pasta::Fp,
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
+        poly::Rotation,
+    };
+
+    #[derive(Default)]
+    struct OneCircuit<F> {
+        value: F,
+    }
+
+    impl<F: FieldExt> Circuit<F> for OneCircuit<F> {
+        type Config = (Column<Advice>, Selector);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = meta.advice_column();
+            let q_enable = meta.selector();
+            meta.create_gate("is one", |meta| {
+                let q_enable = meta.query_selector(q_enable);
+                let value = meta.query_advice(advice, Rotation::cur());
+                vec![q_enable * (value - Expression::Constant(F::one()))]
+            });
+            (advice, q_enable)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, q_enable): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "one",
+                |mut region| {
+                    q_enable.enable(&mut region, 0)?;
+                    region.assign_advice(|| "value", advice, 0, || Value::known(self.value))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn assertions() {
+        // ok
+        assert_satisfied(&OneCircuit { value: Fp::from(1) }, vec![]);
+        // error
+        assert_fails_at(&OneCircuit { value: Fp::from(0) }, vec![], "is one");
+    }
+}
@@ -0,0 +1,164 @@
+use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength, P128Pow5T3};
+use halo2_proofs::{
+    arithmetic::{Coordinates, CurveAffine},
+    pasta::{
+        group::{ff::PrimeField, GroupEncoding},
+        EqAffine, Fp, Fq,
+    },
+    transcript::{Challenge255, EncodedChallenge, Transcript, TranscriptRead, TranscriptWrite},
+};
+use std::io::{self, Read, Write};
+
+// The tags of what the state absorbs, as the prefixes of the Blake2b
+// transcript of halo2.
+const TAG_CHALLENGE: u64 = 0;
+const TAG_POINT: u64 = 1;
+const TAG_SCALAR: u64 = 2;
+
+/// The state of a Poseidon transcript over the base field of EqAffine, so
+/// that an outer circuit over Fq recomputes it natively with the Pow5Chip
+/// of halo2_gadgets. halo2_gadgets 0.2 only exposes fixed-length hashes, so
+/// the state is a chain of them: absorbing x with tag t sets the state to
+/// H(state, x, t), and a challenge is the state after absorbing 0 with
+/// TAG_CHALLENGE.
+#[derive(Clone, Debug)]
+struct PoseidonState(Fq);
+
+impl PoseidonState {
+    fn new() -> Self {
+        PoseidonState(Fq::zero())
+    }
+
+    fn absorb(&mut self, value: Fq, tag: u64) {
+        self.0 = poseidon::Hash::<_, P128Pow5T3, ConstantLength<3>, 3, 2>::init().hash([
+            self.0,
+            value,
+            Fq::from(tag),
+        ]);
+    }
+
+    fn squeeze(&mut self) -> Challenge255<EqAffine> {
+        self.absorb(Fq::zero(), TAG_CHALLENGE);
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(self.0.to_repr().as_ref());
+        Challenge255::new(&bytes)
+    }
+
+    fn absorb_point(&mut self, point: EqAffine) -> io::Result<()> {
+        let coordinates: Coordinates<_> = Option::from(point.coordinates())
+            .ok_or_else(|| io::Error::other("cannot write points at infinity to the transcript"))?;
+        self.absorb(*coordinates.x(), TAG_POINT);
+        self.absorb(*coordinates.y(), TAG_POINT);
+        Ok(())
+    }
+
+    fn absorb_scalar(&mut self, scalar: Fp) {
+        // the modulus of Fp is below the one of Fq
+        let scalar = Fq::from_repr(scalar.to_repr()).unwrap();
+        self.absorb(scalar, TAG_SCALAR);
+    }
+}
+
+/// A Poseidon transcript writing a proof, to pass to prove_with_transcript
+/// in place of Blake2bWrite. The proof bytes are laid out as with Blake2b,
+/// only the challenges differ.
+#[derive(Clone, Debug)]
+pub struct PoseidonWrite<W: Write> {
+    state: PoseidonState,
+    writer: W,
+}
+
+impl<W: Write> PoseidonWrite<W> {
+    /// Initializes a transcript writing to writer.
+    pub fn init(writer: W) -> Self {
+        PoseidonWrite {
+            state: PoseidonState::new(),
+            writer,
+        }
+    }
+
+    /// Returns the writer, holding the proof.
+    pub fn finalize(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Transcript<EqAffine, Challenge255<EqAffine>> for PoseidonWrite<W> {
+    fn squeeze_challenge(&mut self) -> Challenge255<EqAffine> {
+        self.state.squeeze()
+    }
+
+    fn common_point(&mut self, point: EqAffine) -> io::Result<()> {
+        self.state.absorb_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fp) -> io::Result<()> {
+        self.state.absorb_scalar(scalar);
+        Ok(())
+    }
+}
+
+impl<W: Write> TranscriptWrite<EqAffine, Challenge255<EqAffine>> for PoseidonWrite<W> {
+    fn write_point(&mut self, point: EqAffine) -> io::Result<()> {
+        self.common_point(point)?;
+        self.writer.write_all(point.to_bytes().as_ref())
+    }
+
+    fn write_scalar(&mut self, scalar: Fp) -> io::Result<()> {
+        self.common_scalar(scalar)?;
+        self.writer.write_all(scalar.to_repr().as_ref())
+    }
+}
+
+/// A Poseidon transcript reading a proof of PoseidonWrite, to pass to
+/// verify_with_transcript in place of Blake2bRead.
+#[derive(Clone, Debug)]
+pub struct PoseidonRead<R: Read> {
+    state: PoseidonState,
+    reader: R,
+}
+
+impl<R: Read> PoseidonRead<R> {
+    /// Initializes a transcript reading the proof from reader.
+    pub fn init(reader: R) -> Self {
+        PoseidonRead {
+            state: PoseidonState::new(),
+            reader,
+        }
+    }
+}
+
+impl<R: Read> Transcript<EqAffine, Challenge255<EqAffine>> for PoseidonRead<R> {
+    fn squeeze_challenge(&mut self) -> Challenge255<EqAffine> {
+        self.state.squeeze()
+    }
+
+    fn common_point(&mut self, point: EqAffine) -> io::Result<()> {
+        self.state.absorb_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fp) -> io::Result<()> {
+        self.state.absorb_scalar(scalar);
+        Ok(())
+    }
+}
+
+impl<R: Read> TranscriptRead<EqAffine, Challenge255<EqAffine>> for PoseidonRead<R> {
+    fn read_point(&mut self) -> io::Result<EqAffine> {
+        let mut bytes = <EqAffine as GroupEncoding>::Repr::default();
+        self.reader.read_exact(bytes.as_mut())?;
+        let point = Option::from(EqAffine::from_bytes(&bytes))
+            .ok_or_else(|| io::Error::other("invalid point encoding in proof"))?;
+        self.common_point(point)?;
+        Ok(point)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<Fp> {
+        let mut bytes = <Fp as PrimeField>::Repr::default();
+        self.reader.read_exact(bytes.as_mut())?;
+        let scalar = Option::from(Fp::from_repr(bytes))
+            .ok_or_else(|| io::Error::other("invalid field element encoding in proof"))?;
+        self.common_scalar(scalar)?;
+        Ok(scalar)
+    }
+}
@@ -1,12 +1,33 @@
 use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
 
-/// Restrict an expression such that 0 <= word < range.
+/// Restrict an expression such that 0 <= word < range. The degree grows with
+/// range, so only use it for tiny ranges and the RangeCheck chip otherwise.
 pub fn range_check<F: FieldExt>(word: Expression<F>, range: usize) -> Expression<F> {
     (1..range).fold(word.clone(), |acc, i| {
         acc * (Expression::Constant(F::from(i as u64)) - word.clone())
     })
 }
 
+/// Restrict an expression such that 0 <= word < 2**bits.len(), given the
+/// witnessed little-endian bits of word. Unlike range_check, the constraints
+/// are at most degree 2 whatever the range: one recomposition of word and a
+/// bool_check per bit.
+pub fn range_check_bits<F: FieldExt>(
+    word: Expression<F>,
+    bits: &[Expression<F>],
+) -> Vec<Expression<F>> {
+    let recomposed = bits
+        .iter()
+        .enumerate()
+        .fold(Expression::Constant(F::zero()), |acc, (i, bit)| {
+            acc + bit.clone() * pow_of_two::<F>(i)
+        });
+
+    std::iter::once(word - recomposed)
+        .chain(bits.iter().cloned().map(bool_check))
+        .collect()
+}
+
 /// Restrict an expression to be a boolean.
 pub fn bool_check<F: FieldExt>(value: Expression<F>) -> Expression<F> {
     range_check(value, 2)
@@ -25,16 +46,16 @@ macro_rules! impl_expr {
     ($type:ty) => {
         impl<F: halo2_proofs::arithmetic::FieldExt> $crate::util::Expr<F> for $type {
             #[inline]
-            fn expr(&self) -> Expression<F> {
-                Expression::Constant(F::from(*self as u64))
+            fn expr(&self) -> halo2_proofs::plonk::Expression<F> {
+                halo2_proofs::plonk::Expression::Constant(F::from(*self as u64))
             }
         }
     };
     ($type:ty, $method:path) => {
         impl<F: halo2_proofs::arithmetic::FieldExt> $crate::util::Expr<F> for $type {
             #[inline]
-            fn expr(&self) -> Expression<F> {
-                Expression::Constant(F::from($method(self) as u64))
+            fn expr(&self) -> halo2_proofs::plonk::Expression<F> {
+                halo2_proofs::plonk::Expression::Constant(F::from($method(self) as u64))
             }
         }
     };
@@ -42,9 +63,36 @@ macro_rules! impl_expr {
 
 impl_expr!(bool);
 impl_expr!(u8);
+impl_expr!(u16);
+impl_expr!(u32);
 impl_expr!(u64);
 impl_expr!(usize);
 
+impl<F: FieldExt> Expr<F> for u128 {
+    #[inline]
+    fn expr(&self) -> Expression<F> {
+        Expression::Constant(F::from_u128(*self))
+    }
+}
+
+/// Implementation trait `Expr` for a concrete field element type. A blanket
+/// impl over FieldExt would overlap the integer impls, so each field the
+/// circuits run over gets its own.
+#[macro_export]
+macro_rules! impl_expr_field {
+    ($type:ty) => {
+        impl $crate::util::Expr<$type> for $type {
+            #[inline]
+            fn expr(&self) -> halo2_proofs::plonk::Expression<$type> {
+                halo2_proofs::plonk::Expression::Constant(*self)
+            }
+        }
+    };
+}
+
+impl_expr_field!(halo2_proofs::pasta::Fp);
+impl_expr_field!(halo2_proofs::pasta::Fq);
+
 impl<F: FieldExt> Expr<F> for Expression<F> {
     #[inline]
     fn expr(&self) -> Expression<F> {
@@ -73,6 +121,63 @@ impl<F: FieldExt> Expr<F> for i32 {
     }
 }
 
+impl<F: FieldExt> Expr<F> for i64 {
+    #[inline]
+    fn expr(&self) -> Expression<F> {
+        Expression::Constant(
+            F::from(self.unsigned_abs())
+                * if self.is_negative() {
+                    -F::one()
+                } else {
+                    F::one()
+                },
+        )
+    }
+}
+
+/// Names the constraints returned from create_gate, so MockProver failures
+/// point to the failing constraint by name instead of by its index in the
+/// gate. A name is anything Display, e.g.
+/// `named_constraints![format!("sorted[{}] <= sorted[{}]", i, i + 1) => poly]`.
+#[macro_export]
+macro_rules! named_constraints {
+    ($($name:expr => $constraint:expr),* $(,)?) => {
+        vec![$(($crate::util::constraint_name(&$name), $constraint)),*]
+    };
+}
+
+/// Returns the name as the &'static str halo2 0.2 takes for constraint
+/// names. Each distinct name is leaked once, so configuring a circuit again
+/// does not leak more.
+#[doc(hidden)]
+pub fn constraint_name(name: &impl std::fmt::Display) -> &'static str {
+    static NAMES: std::sync::Mutex<std::collections::BTreeSet<&'static str>> =
+        std::sync::Mutex::new(std::collections::BTreeSet::new());
+    let name = name.to_string();
+    let mut names = NAMES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match names.get(name.as_str()) {
+        Some(name) => name,
+        None => {
+            let name = Box::leak(name.into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+/// Returns a constraint that only applies under conditions, as the product
+/// of the gate selector, the conditions and the constraint, e.g.
+/// `constrain_if!(s, keep => is_zero::expr(diff, diff_inv))`. The selector
+/// and the conditions are cloned, so they can be reused afterwards.
+#[macro_export]
+macro_rules! constrain_if {
+    ($selector:expr, $($condition:expr),+ => $constraint:expr) => {
+        $selector.clone() $(* $condition.clone())+ * ($constraint)
+    };
+}
+
 /// Given a bytes-representation of an expression, it computes and returns the
 /// single expression.
 pub fn expr_from_bytes<F: FieldExt, E: Expr<F>>(bytes: &[E]) -> Expression<F> {
@@ -85,7 +190,752 @@ pub fn expr_from_bytes<F: FieldExt, E: Expr<F>>(bytes: &[E]) -> Expression<F> {
     value
 }
 
+/// Given a big-endian bytes-representation of an expression, as for
+/// Ethereum words, it computes and returns the single expression.
+pub fn expr_from_be_bytes<F: FieldExt, E: Expr<F>>(bytes: &[E]) -> Expression<F> {
+    let mut value = 0.expr();
+    for byte in bytes.iter() {
+        value = value * F::from(256) + byte.expr();
+    }
+    value
+}
+
+/// Returns the N_BYTES low bytes of a value in little-endian order, the
+/// witness of the bytes whose expr_from_bytes is the value. Wider values are
+/// truncated, which the gate recomposing them then rejects.
+pub fn value_to_bytes<F: FieldExt, const N_BYTES: usize>(value: &F) -> [u8; N_BYTES] {
+    let mut bytes = [0u8; N_BYTES];
+    bytes.copy_from_slice(&value.to_repr().as_ref()[..N_BYTES]);
+    bytes
+}
+
+/// Returns the N_BYTES low bytes of a value in big-endian order, the witness
+/// of the bytes whose expr_from_be_bytes is the value.
+pub fn value_to_be_bytes<F: FieldExt, const N_BYTES: usize>(value: &F) -> [u8; N_BYTES] {
+    let mut bytes = value_to_bytes(value);
+    bytes.reverse();
+    bytes
+}
+
 /// Returns 2**by as FieldExt
 pub fn pow_of_two<F: FieldExt>(by: usize) -> F {
-    F::from(2).pow(&[by as u64, 0, 0, 0])
-}
\ No newline at end of file
+    pow_const(F::from(2), &[by as u64])
+}
+
+/// Returns 10**by as FieldExt
+pub fn pow_of_ten<F: FieldExt>(by: usize) -> F {
+    pow_const(F::from(10), &[by as u64])
+}
+
+/// Returns base**exp as FieldExt, where exp is given as little-endian u64
+/// limbs of any length.
+pub fn pow_const<F: FieldExt>(base: F, exp: &[u64]) -> F {
+    let mut result = F::one();
+    for limb in exp.iter().rev() {
+        for i in (0..64).rev() {
+            result = result.square();
+            if (limb >> i) & 1 == 1 {
+                result *= base;
+            }
+        }
+    }
+    result
+}
+
+/// Returns an expression for base**exp, built by square and multiply. Its
+/// degree is exp times the degree of base.
+pub fn pow_expr<F: FieldExt>(base: Expression<F>, exp: usize) -> Expression<F> {
+    if exp == 0 {
+        return 1.expr();
+    }
+    let mut result = base.clone();
+    for i in (0..usize::BITS - 1 - exp.leading_zeros()).rev() {
+        result = result.clone() * result;
+        if (exp >> i) & 1 == 1 {
+            result = result * base.clone();
+        }
+    }
+    result
+}
+
+/// Returns the product of boolean expressions, which is one iff all of them
+/// are one.
+pub mod and {
+    use super::{product, Expr};
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression that is one iff all the inputs are one.
+    pub fn expr<F: FieldExt, E: Expr<F>, I: IntoIterator<Item = E>>(inputs: I) -> Expression<F> {
+        product::expr(inputs)
+    }
+}
+
+/// Returns the disjunction of boolean expressions, as 1 - and(not(inputs)).
+pub mod or {
+    use super::{and, not, Expr};
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression that is one iff any of the inputs is one.
+    pub fn expr<F: FieldExt, E: Expr<F>, I: IntoIterator<Item = E>>(inputs: I) -> Expression<F> {
+        not::expr(and::expr(inputs.into_iter().map(not::expr)))
+    }
+}
+
+/// Returns the negation of a boolean expression.
+pub mod not {
+    use super::Expr;
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression that is one iff the input is zero.
+    pub fn expr<F: FieldExt, E: Expr<F>>(input: E) -> Expression<F> {
+        1.expr() - input.expr()
+    }
+}
+
+/// Returns the parity of boolean expressions.
+pub mod xor {
+    use super::Expr;
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression that is one iff an odd number of the inputs are
+    /// one, folding a + b - 2ab over them.
+    pub fn expr<F: FieldExt, E: Expr<F>, I: IntoIterator<Item = E>>(inputs: I) -> Expression<F> {
+        inputs.into_iter().fold(0.expr(), |acc, input| {
+            let input = input.expr();
+            acc.clone() + input.clone() - 2.expr() * acc * input
+        })
+    }
+}
+
+/// Returns when_true if the boolean cond is one, and when_false otherwise.
+pub mod select {
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression for cond * when_true + (1 - cond) * when_false.
+    pub fn expr<F: FieldExt>(
+        cond: Expression<F>,
+        when_true: Expression<F>,
+        when_false: Expression<F>,
+    ) -> Expression<F> {
+        cond * (when_true - when_false.clone()) + when_false
+    }
+
+    /// Returns the witness of expr, given a cond which is zero or one.
+    pub fn value<F: FieldExt>(cond: F, when_true: F, when_false: F) -> F {
+        cond * (when_true - when_false) + when_false
+    }
+}
+
+/// Returns the sum of expressions.
+pub mod sum {
+    use super::Expr;
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression for the sum of the inputs, zero if there are
+    /// none.
+    pub fn expr<F: FieldExt, E: Expr<F>, I: IntoIterator<Item = E>>(inputs: I) -> Expression<F> {
+        inputs
+            .into_iter()
+            .fold(0.expr(), |acc, input| acc + input.expr())
+    }
+}
+
+/// Returns the product of expressions.
+pub mod product {
+    use super::Expr;
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression for the product of the inputs, one if there are
+    /// none.
+    pub fn expr<F: FieldExt, E: Expr<F>, I: IntoIterator<Item = E>>(inputs: I) -> Expression<F> {
+        inputs
+            .into_iter()
+            .fold(1.expr(), |acc, input| acc * input.expr())
+    }
+}
+
+/// Returns the random linear combination of bytes under a randomness, which
+/// commits to a whole byte array in a single field element.
+///
+/// The randomness has to come from the verifier to make the combination
+/// binding, but halo2_proofs 0.2 has no Challenge API nor second phase
+/// advice columns to query it from. Until the crate moves to a halo2 with
+/// phases, the randomness is whatever expression the caller passes, e.g. an
+/// instance cell derived from a hash of the other public inputs.
+pub mod rlc {
+    use super::{eval_poly, Expr};
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression for bytes_0 + bytes_1 * randomness + ... +
+    /// bytes_(n-1) * randomness**(n-1).
+    pub fn expr<F: FieldExt, E: Expr<F>>(bytes: &[E], randomness: Expression<F>) -> Expression<F> {
+        eval_poly::expr(bytes, randomness)
+    }
+
+    /// Returns the witness of expr.
+    pub fn value<'a, F: FieldExt, I: IntoIterator<Item = &'a u8>>(bytes: I, randomness: F) -> F
+    where
+        I::IntoIter: DoubleEndedIterator,
+    {
+        bytes.into_iter().rev().fold(F::zero(), |acc, byte| {
+            acc * randomness + F::from(*byte as u64)
+        })
+    }
+}
+
+/// Returns the expressions of a one-hot encoding, where the boolean cells
+/// bits_0, ..., bits_(n-1) select the index i for which bits_i == 1.
+pub mod one_hot {
+    use super::{bool_check, sum, Expr};
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns the constraints that the bits are booleans summing to one.
+    pub fn constraints<F: FieldExt>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+        bits.iter()
+            .cloned()
+            .map(bool_check)
+            .chain(std::iter::once(sum::expr(bits) - 1.expr()))
+            .collect()
+    }
+
+    /// Returns an expression for the selected index.
+    pub fn index<F: FieldExt>(bits: &[Expression<F>]) -> Expression<F> {
+        sum::expr(
+            bits.iter()
+                .enumerate()
+                .map(|(i, bit)| bit.clone() * i.expr()),
+        )
+    }
+
+    /// Returns an expression for the value at the selected index.
+    pub fn select<F: FieldExt>(bits: &[Expression<F>], values: &[Expression<F>]) -> Expression<F> {
+        sum::expr(
+            bits.iter()
+                .zip(values.iter())
+                .map(|(bit, value)| bit.clone() * value.clone()),
+        )
+    }
+
+    /// Returns the constraints that the n rows of n bits are one-hot and
+    /// select every index exactly once, i.e. form a permutation matrix.
+    pub fn permutation<F: FieldExt>(rows: &[Vec<Expression<F>>]) -> Vec<Expression<F>> {
+        let selected =
+            (0..rows.len()).map(|j| sum::expr(rows.iter().map(|row| row[j].clone())) - 1.expr());
+        rows.iter()
+            .flat_map(|row| constraints(row))
+            .chain(selected)
+            .collect()
+    }
+}
+
+/// Returns the Lagrange selector of i out of 0..n, an expression which is one
+/// if index == i and zero for any other index in 0..n. Its degree is n - 1,
+/// so prefer one_hot but for a small n.
+pub fn lagrange_selector<F: FieldExt>(index: Expression<F>, i: usize, n: usize) -> Expression<F> {
+    let (numerator, denominator) =
+        (0..n)
+            .filter(|j| *j != i)
+            .fold((1.expr(), F::one()), |(numerator, denominator), j| {
+                (
+                    numerator * (index.clone() - j.expr()),
+                    denominator * (F::from(i as u64) - F::from(j as u64)),
+                )
+            });
+    numerator * denominator.invert().unwrap()
+}
+
+/// Returns the zero test of a value given its witnessed inverse value_inv,
+/// which is the inverse of value, or zero if value is zero.
+pub mod is_zero {
+    use super::Expr;
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression for 1 - value * value_inv, which is one iff
+    /// value == 0. It is only sound along with the constraint
+    /// value * is_zero == 0, since a value_inv of zero otherwise makes any
+    /// value pass as zero.
+    pub fn expr<F: FieldExt>(value: Expression<F>, value_inv: Expression<F>) -> Expression<F> {
+        1.expr() - value * value_inv
+    }
+
+    /// Returns the witness of value_inv.
+    pub fn value_inv<F: FieldExt>(value: F) -> F {
+        value.invert().unwrap_or(F::zero())
+    }
+}
+
+/// Returns the evaluation of a polynomial by Horner's rule, given its
+/// coefficients from the constant term up.
+pub mod eval_poly {
+    use super::Expr;
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression for coeffs_0 + coeffs_1 * x + ... +
+    /// coeffs_(n-1) * x**(n-1), of degree n - 1 times the degree of x.
+    pub fn expr<F: FieldExt, E: Expr<F>>(coeffs: &[E], x: Expression<F>) -> Expression<F> {
+        coeffs
+            .iter()
+            .rev()
+            .fold(0.expr(), |acc, coeff| acc * x.clone() + coeff.expr())
+    }
+
+    /// Returns the witness of expr.
+    pub fn value<F: FieldExt>(coeffs: &[F], x: F) -> F {
+        coeffs
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, coeff| acc * x + coeff)
+    }
+}
+
+/// Returns witnesses computed under Value, so that assignments don't have to
+/// unwrap the witnesses or redo the byte math.
+pub mod value {
+    use halo2_proofs::{arithmetic::FieldExt, circuit::Value, plonk::Error};
+
+    /// Returns the field element of a u64.
+    pub fn from_u64<F: FieldExt>(value: Value<u64>) -> Value<F> {
+        value.map(F::from)
+    }
+
+    /// Returns the field element of a u128.
+    pub fn from_u128<F: FieldExt>(value: Value<u128>) -> Value<F> {
+        value.map(F::from_u128)
+    }
+
+    /// Returns the field element of little-endian bytes.
+    pub fn from_le_bytes<F: FieldExt, B: AsRef<[u8]>>(bytes: Value<B>) -> Value<F> {
+        bytes.map(|bytes| {
+            bytes.as_ref().iter().rev().fold(F::zero(), |acc, byte| {
+                acc * F::from(256) + F::from(*byte as u64)
+            })
+        })
+    }
+
+    /// Returns the n_limbs little-endian limbs of limb_bytes bytes of a
+    /// value. The bytes above the limbs are dropped.
+    pub fn to_limbs<F: FieldExt>(
+        value: Value<F>,
+        limb_bytes: usize,
+        n_limbs: usize,
+    ) -> Vec<Value<F>> {
+        (0..n_limbs)
+            .map(|i| {
+                from_le_bytes(value.map(|value| {
+                    value.to_repr().as_ref()[i * limb_bytes..(i + 1) * limb_bytes].to_vec()
+                }))
+            })
+            .collect()
+    }
+
+    /// Returns whether a value is below 2**(8 * n_bytes).
+    pub fn fits_in_bytes<F: FieldExt>(value: &F, n_bytes: usize) -> bool {
+        value.to_repr().as_ref()[n_bytes..]
+            .iter()
+            .all(|byte| *byte == 0)
+    }
+
+    /// Returns a + b, or Error::Synthesis if a known operand or sum doesn't
+    /// fit in n_bytes bytes.
+    pub fn checked_add<F: FieldExt>(
+        a: Value<F>,
+        b: Value<F>,
+        n_bytes: usize,
+    ) -> Result<Value<F>, Error> {
+        checked(a, b, a + b, n_bytes)
+    }
+
+    /// Returns a * b, or Error::Synthesis if a known operand or product
+    /// doesn't fit in n_bytes bytes.
+    pub fn checked_mul<F: FieldExt>(
+        a: Value<F>,
+        b: Value<F>,
+        n_bytes: usize,
+    ) -> Result<Value<F>, Error> {
+        checked(a, b, a * b, n_bytes)
+    }
+
+    fn checked<F: FieldExt>(
+        a: Value<F>,
+        b: Value<F>,
+        out: Value<F>,
+        n_bytes: usize,
+    ) -> Result<Value<F>, Error> {
+        let mut fits = true;
+        a.zip(b).zip(out).map(|((a, b), out)| {
+            fits = [a, b, out]
+                .iter()
+                .all(|value| fits_in_bytes(value, n_bytes))
+        });
+        if fits {
+            Ok(out)
+        } else {
+            Err(Error::Synthesis)
+        }
+    }
+}
+
+/// Returns the helpers of binary Qm.n fixed-point values, which denote
+/// value / 2**n for n fractional bits, unlike the decimal FixedPoint.
+pub mod fixed {
+    use super::{pow_of_two, Expr};
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns 2**frac_bits, the fixed-point encoding of one.
+    pub fn scale<F: FieldExt>(frac_bits: usize) -> F {
+        pow_of_two(frac_bits)
+    }
+
+    /// Returns the fixed-point encoding of numerator / denominator, rounded
+    /// to the nearest, e.g. constant(1, 2, 16) for 0.5 in Q.16.
+    pub fn constant<F: FieldExt>(numerator: u64, denominator: u64, frac_bits: usize) -> F {
+        assert!(frac_bits < 64, "the constant is computed on u128");
+        let scaled = ((numerator as u128) << frac_bits) + denominator as u128 / 2;
+        F::from_u128(scaled / denominator as u128)
+    }
+
+    /// Returns the constraint of the rescaled product out of a * b, where
+    /// a * b (+ 2**(frac_bits - 1) if rounded to the nearest) ==
+    /// out * 2**frac_bits + remainder.
+    /// The caller has to range check 0 <= remainder < 2**frac_bits, and out
+    /// so that the product can't wrap around the field modulus.
+    pub fn mul_rescale_expr<F: FieldExt>(
+        a: Expression<F>,
+        b: Expression<F>,
+        out: Expression<F>,
+        remainder: Expression<F>,
+        frac_bits: usize,
+        round: bool,
+    ) -> Expression<F> {
+        let half = if round && frac_bits > 0 {
+            Expression::Constant(pow_of_two::<F>(frac_bits - 1))
+        } else {
+            0.expr()
+        };
+        a * b + half - out * scale::<F>(frac_bits) - remainder
+    }
+
+    /// Returns the witnesses (out, remainder) of mul_rescale_expr, given a
+    /// product that doesn't wrap around the field modulus.
+    pub fn mul_rescale_value<F: FieldExt>(a: F, b: F, frac_bits: usize, round: bool) -> (F, F) {
+        let mut product = a * b;
+        if round && frac_bits > 0 {
+            product += pow_of_two::<F>(frac_bits - 1);
+        }
+        let repr = product.to_repr();
+        let remainder = (0..frac_bits)
+            .filter(|i| (repr.as_ref()[i / 8] >> (i % 8)) & 1 == 1)
+            .fold(F::zero(), |acc, i| acc + pow_of_two::<F>(i));
+        let out = (product - remainder) * scale::<F>(frac_bits).invert().unwrap();
+        (out, remainder)
+    }
+}
+
+/// Returns the sign of an n_bits-bit signed value, encoded in the field as
+/// -x for a negative x, as the signed comparisons take it. Offsetting the
+/// value by 2**(n_bits - 1) maps it to the unsigned range 0..2**n_bits,
+/// whose top bit is set iff the value is non-negative.
+pub mod sign {
+    use super::{not, pow_of_two};
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns 2**(n_bits - 1), the offset of the signed values.
+    pub fn offset<F: FieldExt>(n_bits: usize) -> F {
+        pow_of_two(n_bits - 1)
+    }
+
+    /// Returns an expression that is one iff the value is negative, given
+    /// the little-endian bits of value + offset, which the caller constrains
+    /// with range_check_bits.
+    pub fn expr<F: FieldExt>(offset_bits: &[Expression<F>]) -> Expression<F> {
+        not::expr(offset_bits[offset_bits.len() - 1].clone())
+    }
+
+    /// Returns the witness of the bits of value + offset.
+    pub fn offset_bits<F: FieldExt>(value: F, n_bits: usize) -> Vec<F> {
+        let repr = (value + offset::<F>(n_bits)).to_repr();
+        (0..n_bits)
+            .map(|i| F::from(((repr.as_ref()[i / 8] >> (i % 8)) & 1) as u64))
+            .collect()
+    }
+
+    /// Returns whether the n_bits-bit value is negative.
+    pub fn value<F: FieldExt>(value: F, n_bits: usize) -> bool {
+        offset_bits(value, n_bits)[n_bits - 1] == F::zero()
+    }
+}
+
+/// Returns the magnitude of a signed value given its sign.
+pub mod abs {
+    use super::{sign, Expr};
+    use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
+
+    /// Returns an expression for (1 - 2 * sign) * value, which is -value
+    /// for a negative value and value otherwise.
+    pub fn expr<F: FieldExt>(value: Expression<F>, sign: Expression<F>) -> Expression<F> {
+        (1.expr() - 2.expr() * sign) * value
+    }
+
+    /// Returns the magnitude of the n_bits-bit value.
+    pub fn value<F: FieldExt>(value: F, n_bits: usize) -> F {
+        if sign::value(value, n_bits) {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        abs, eval_poly, expr_from_be_bytes, fixed, lagrange_selector, one_hot, or, pow_const,
+        pow_expr, pow_of_two, range_check_bits, rlc, select, sign, value, value_to_be_bytes,
+        value_to_bytes, xor, Expr,
+    };
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::Value,
+        pasta::{Fp, Fq},
+        plonk::Expression,
+    };
+
+    /// Returns the value of an expression of constants.
+    fn eval<F: FieldExt>(expr: Expression<F>) -> F {
+        expr.evaluate(
+            &|constant| constant,
+            &|_| unreachable!("a constant expression has no selector"),
+            &|_, _, _| unreachable!("a constant expression has no fixed cell"),
+            &|_, _, _| unreachable!("a constant expression has no advice cell"),
+            &|_, _, _| unreachable!("a constant expression has no instance cell"),
+            &|a| -a,
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|a, scalar| a * scalar,
+        )
+    }
+
+    #[test]
+    fn or_xor() {
+        for bits in [[0u64, 0, 0], [1, 0, 0], [0, 1, 1], [1, 1, 1]] {
+            let inputs = bits.map(|bit| Expr::<Fp>::expr(&bit));
+            let ones = bits.iter().sum::<u64>();
+            assert_eq!(eval(or::expr(inputs.clone())), Fp::from((ones > 0) as u64));
+            assert_eq!(eval(xor::expr(inputs)), Fp::from(ones % 2));
+        }
+        // no inputs
+        assert_eq!(eval(or::expr(Vec::<Expression<Fp>>::new())), Fp::zero());
+        assert_eq!(eval(xor::expr(Vec::<Expression<Fp>>::new())), Fp::zero());
+    }
+
+    #[test]
+    fn select_value() {
+        let (a, b) = (Fp::from(3), Fp::from(5));
+        assert_eq!(select::value(Fp::one(), a, b), a);
+        assert_eq!(select::value(Fp::zero(), a, b), b);
+        // the witness of the expression
+        for cond in [0u64, 1] {
+            let expr = select::expr(cond.expr(), a.expr(), b.expr());
+            assert_eq!(eval(expr), select::value(Fp::from(cond), a, b));
+        }
+    }
+
+    #[test]
+    fn rlc() {
+        let bytes = [1u8, 2, 3];
+        let randomness = Fp::from(7);
+        // 1 + 2 * 7 + 3 * 49
+        assert_eq!(rlc::value(&bytes, randomness), Fp::from(162));
+        assert_eq!(eval(rlc::expr(&bytes, randomness.expr())), Fp::from(162));
+        assert_eq!(rlc::value(&[], randomness), Fp::zero());
+        // another order, another combination
+        assert_ne!(rlc::value(&[3, 2, 1], randomness), Fp::from(162));
+    }
+
+    #[test]
+    fn be_bytes() {
+        let value = Fp::from(0x0102_0304);
+        let bytes = value_to_be_bytes::<Fp, 4>(&value);
+        assert_eq!(bytes, [1, 2, 3, 4]);
+        assert_eq!(eval::<Fp>(expr_from_be_bytes(&bytes)), value);
+        assert_eq!(value_to_bytes::<Fp, 4>(&value), [4, 3, 2, 1]);
+        // wider values keep their low bytes
+        assert_eq!(value_to_be_bytes::<Fp, 2>(&value), [3, 4]);
+    }
+
+    #[test]
+    fn expr_impls() {
+        assert_eq!(eval(Expr::<Fp>::expr(&u16::MAX)), Fp::from(u16::MAX as u64));
+        assert_eq!(eval(Expr::<Fp>::expr(&u32::MAX)), Fp::from(u32::MAX as u64));
+        // above 64 bits
+        assert_eq!(eval(Expr::<Fp>::expr(&u128::MAX)), Fp::from_u128(u128::MAX));
+        assert_eq!(eval(Expr::<Fp>::expr(&-3i64)), -Fp::from(3));
+        assert_eq!(eval(Expr::<Fp>::expr(&i64::MIN)), -Fp::from(1 << 63));
+        // field elements
+        assert_eq!(eval(Fp::from(7).expr()), Fp::from(7));
+        assert_eq!(eval(Fq::from(7).expr()), Fq::from(7));
+    }
+
+    #[test]
+    fn pow() {
+        let base = Fp::from(3);
+        for exp in [0, 1, 2, 5, 13] {
+            let expected = Fp::from(3u64.pow(exp as u32));
+            assert_eq!(pow_const(base, &[exp as u64]), expected);
+            assert_eq!(eval(pow_expr(base.expr(), exp)), expected);
+        }
+        // exponents beyond the 4 limbs of a field element: base**(2**256)
+        // is base squared 256 times
+        let squared = (0..256).fold(base, |acc, _| acc.square());
+        assert_eq!(pow_const(base, &[0, 0, 0, 0, 1]), squared);
+        assert_eq!(pow_of_two::<Fp>(300), pow_of_two::<Fp>(150).square());
+    }
+
+    #[test]
+    fn selectors() {
+        let n = 4;
+        for index in 0..n {
+            for i in 0..n {
+                let selector = lagrange_selector(index.expr(), i, n);
+                assert_eq!(eval::<Fp>(selector), Fp::from((index == i) as u64));
+            }
+        }
+
+        let bits: Vec<Expression<Fp>> = [0u64, 0, 1, 0].iter().map(Expr::expr).collect();
+        let values: Vec<_> = [10u64, 20, 30, 40].iter().map(Expr::expr).collect();
+        assert!(one_hot::constraints(&bits)
+            .into_iter()
+            .all(|poly| eval(poly) == Fp::zero()));
+        assert_eq!(eval(one_hot::index(&bits)), Fp::from(2));
+        assert_eq!(eval(one_hot::select(&bits, &values)), Fp::from(30));
+        // error: two bits set
+        let bits: Vec<Expression<Fp>> = [0u64, 1, 1, 0].iter().map(Expr::expr).collect();
+        assert!(one_hot::constraints(&bits)
+            .into_iter()
+            .any(|poly| eval(poly) != Fp::zero()));
+    }
+
+    #[test]
+    fn horner() {
+        // 2 + 3x + x**3 at x = 5
+        let coeffs = [2u64, 3, 0, 1].map(Fp::from);
+        let x = Fp::from(5);
+        assert_eq!(eval_poly::value(&coeffs, x), Fp::from(142));
+        assert_eq!(eval(eval_poly::expr(&coeffs, x.expr())), Fp::from(142));
+        // the zero polynomial
+        assert_eq!(eval_poly::value(&[], x), Fp::zero());
+    }
+
+    #[test]
+    fn values() {
+        let known = |value: Value<Fp>, expected: Fp| value.assert_if_known(|v| *v == expected);
+        known(value::from_u64(Value::known(7)), Fp::from(7));
+        known(
+            value::from_u128(Value::known(u128::MAX)),
+            Fp::from_u128(u128::MAX),
+        );
+        known(
+            value::from_le_bytes(Value::known([4u8, 3, 2, 1])),
+            Fp::from(0x0102_0304),
+        );
+
+        let limbs = value::to_limbs(Value::known(Fp::from(0x0102_0304)), 2, 3);
+        for (limb, expected) in limbs.into_iter().zip([0x0304, 0x0102, 0]) {
+            known(limb, Fp::from(expected));
+        }
+
+        assert!(value::fits_in_bytes(&Fp::from(0xffff), 2));
+        assert!(!value::fits_in_bytes(&Fp::from(0x10000), 2));
+        let (a, b) = (Value::known(Fp::from(200)), Value::known(Fp::from(100)));
+        // ok
+        known(value::checked_add(a, b, 2).unwrap(), Fp::from(300));
+        known(value::checked_mul(a, b, 2).unwrap(), Fp::from(20000));
+        assert!(value::checked_add(Value::unknown(), b, 1).is_ok());
+        // error: the sum or product overflows
+        assert!(value::checked_add(a, b, 1).is_err());
+        assert!(value::checked_mul(a, b, 1).is_err());
+        // error: an operand overflows
+        assert!(
+            value::checked_add(Value::known(Fp::from(256)), Value::known(Fp::zero()), 1).is_err()
+        );
+    }
+
+    #[test]
+    fn fixed_point() {
+        // Q.16
+        let one = fixed::scale::<Fp>(16);
+        assert_eq!(one, Fp::from(1 << 16));
+        assert_eq!(fixed::constant::<Fp>(1, 2, 16), Fp::from(1 << 15));
+        // 1/3 rounds down, 2/3 up
+        assert_eq!(fixed::constant::<Fp>(1, 3, 16), Fp::from(21845));
+        assert_eq!(fixed::constant::<Fp>(2, 3, 16), Fp::from(43691));
+
+        // 1.5 * 2.25 == 3.375
+        let (a, b) = (Fp::from(3 << 15), Fp::from(9 << 14));
+        for round in [false, true] {
+            let (out, remainder) = fixed::mul_rescale_value(a, b, 16, round);
+            assert_eq!(out, Fp::from(27 << 13));
+            let poly = fixed::mul_rescale_expr(
+                a.expr(),
+                b.expr(),
+                out.expr(),
+                remainder.expr(),
+                16,
+                round,
+            );
+            assert_eq!(eval(poly), Fp::zero());
+        }
+        // 1/3 * 1/3 rounds to the nearest, or down
+        let third = fixed::constant::<Fp>(1, 3, 16);
+        assert_eq!(
+            fixed::mul_rescale_value(third, third, 16, true).0,
+            Fp::from(7282)
+        );
+        assert_eq!(
+            fixed::mul_rescale_value(third, third, 16, false).0,
+            Fp::from(7281)
+        );
+        // error: another out
+        let (out, remainder) = fixed::mul_rescale_value(a, b, 16, false);
+        let poly = fixed::mul_rescale_expr(
+            a.expr(),
+            b.expr(),
+            (out + Fp::one()).expr(),
+            remainder.expr(),
+            16,
+            false,
+        );
+        assert_ne!(eval(poly), Fp::zero());
+    }
+
+    #[test]
+    fn sign_abs() {
+        for (value, negative) in [
+            (5i64, false),
+            (0, false),
+            (-5, true),
+            (-128, true),
+            (127, false),
+        ] {
+            let field = eval::<Fp>(value.expr());
+            assert_eq!(sign::value(field, 8), negative);
+            assert_eq!(abs::value(field, 8), Fp::from(value.unsigned_abs()));
+
+            let bits: Vec<_> = sign::offset_bits(field, 8)
+                .into_iter()
+                .map(|bit| bit.expr())
+                .collect();
+            let sign = sign::expr(&bits);
+            assert_eq!(eval(sign.clone()), Fp::from(negative as u64));
+            assert_eq!(
+                eval(abs::expr(field.expr(), sign)),
+                Fp::from(value.unsigned_abs())
+            );
+            // the bits are those of value + 128
+            let offset = field + sign::offset::<Fp>(8);
+            assert!(range_check_bits(offset.expr(), &bits)
+                .into_iter()
+                .all(|poly| eval(poly) == Fp::zero()));
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use wasm_bindgen::prelude::*;
+
+use crate::bindings;
+
+/// Returns the proof that the values, of a power of two count from 2 to 64,
+/// sort to their ascending order. The public inputs are the values followed
+/// by the sorted values.
+#[wasm_bindgen]
+pub fn prove_sort(values: &[u64]) -> Result<Vec<u8>, JsError> {
+    bindings::prove_sort(values).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Verifies a proof of prove_sort for the values.
+#[wasm_bindgen]
+pub fn verify_sort(values: &[u64], proof: &[u8]) -> bool {
+    bindings::verify_sort(values, proof).is_ok()
+}
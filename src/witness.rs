@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::gadgets::sort::SortCircuitBuilder;
+
+/// The JSON input of a sort proof, shared by the provers and the CLI:
+///
+/// ```json
+/// {"values": ["3", "0x01", "4"], "count": 3, "options": {"n_bytes": 8}}
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WitnessInput {
+    /// The values, as decimal or 0x-prefixed hex strings.
+    pub values: Vec<String>,
+    /// The number of values, checked against values if given.
+    #[serde(default)]
+    pub count: Option<usize>,
+    #[serde(default)]
+    pub options: WitnessOptions,
+}
+
+/// The options of a WitnessInput.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct WitnessOptions {
+    /// The number of bytes every value must fit in, at most 16.
+    pub n_bytes: usize,
+    /// Whether values may repeat.
+    pub allow_duplicates: bool,
+}
+
+impl Default for WitnessOptions {
+    fn default() -> Self {
+        WitnessOptions {
+            n_bytes: 8,
+            allow_duplicates: true,
+        }
+    }
+}
+
+/// Why a WitnessInput is invalid.
+#[derive(Debug)]
+pub enum WitnessError {
+    Json(serde_json::Error),
+    /// The value at the index is not a decimal or hex integer.
+    InvalidValue(usize),
+    /// The value at the index does not fit in n_bytes bytes.
+    OutOfRange(usize),
+    /// The value at the index repeats a previous one.
+    Duplicate(usize),
+    /// The count does not match the number of values.
+    Count {
+        expected: usize,
+        actual: usize,
+    },
+    /// n_bytes is 0 or above what the caller supports.
+    NBytes(usize),
+    /// The options call for another circuit than the one of the caller.
+    Options,
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WitnessError::Json(e) => write!(f, "invalid witness: {}", e),
+            WitnessError::InvalidValue(i) => write!(f, "value {} is not an integer", i),
+            WitnessError::OutOfRange(i) => write!(f, "value {} does not fit in n_bytes", i),
+            WitnessError::Duplicate(i) => write!(f, "value {} is a duplicate", i),
+            WitnessError::Count { expected, actual } => {
+                write!(f, "expected {} values, got {}", expected, actual)
+            }
+            WitnessError::NBytes(n_bytes) => write!(f, "unsupported n_bytes {}", n_bytes),
+            WitnessError::Options => write!(f, "the options call for another circuit"),
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+impl WitnessInput {
+    /// Parses a witness from JSON.
+    pub fn from_json(json: &str) -> Result<Self, WitnessError> {
+        serde_json::from_str(json).map_err(WitnessError::Json)
+    }
+
+    /// Returns the values, checking them against the count and options
+    /// before they are assigned.
+    pub fn values(&self) -> Result<Vec<u128>, WitnessError> {
+        let n_bytes = self.options.n_bytes;
+        if n_bytes == 0 || n_bytes > 16 {
+            return Err(WitnessError::NBytes(n_bytes));
+        }
+        if let Some(count) = self.count {
+            if count != self.values.len() {
+                return Err(WitnessError::Count {
+                    expected: count,
+                    actual: self.values.len(),
+                });
+            }
+        }
+
+        let mut values = Vec::with_capacity(self.values.len());
+        for (i, value) in self.values.iter().enumerate() {
+            let value = match value.strip_prefix("0x") {
+                Some(hex) => u128::from_str_radix(hex, 16),
+                None => value.parse(),
+            }
+            .map_err(|_| WitnessError::InvalidValue(i))?;
+            if n_bytes < 16 && value >> (8 * n_bytes) != 0 {
+                return Err(WitnessError::OutOfRange(i));
+            }
+            if !self.options.allow_duplicates && values.contains(&value) {
+                return Err(WitnessError::Duplicate(i));
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Returns the builder of the circuit the values and options call for.
+    pub fn builder(&self) -> SortCircuitBuilder {
+        let builder = SortCircuitBuilder::new()
+            .elements(self.values.len())
+            .bytes(self.options.n_bytes);
+        if self.options.allow_duplicates {
+            builder.allow_duplicates()
+        } else {
+            builder
+        }
+    }
+
+    /// Returns the values, checking them as values does, for circuits of u64
+    /// values, which fails if n_bytes is above 8.
+    pub fn values_u64(&self) -> Result<Vec<u64>, WitnessError> {
+        if self.options.n_bytes > 8 {
+            return Err(WitnessError::NBytes(self.options.n_bytes));
+        }
+        Ok(self.values()?.into_iter().map(|v| v as u64).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WitnessError, WitnessInput};
+    use crate::gadgets::sort::SortCircuitBuilder;
+
+    #[test]
+    fn witness_values() {
+        // ok
+        let witness = WitnessInput::from_json(r#"{"values": ["3", "0x01", "4", "1"]}"#).unwrap();
+        assert_eq!(witness.values_u64().unwrap(), vec![3, 1, 4, 1]);
+        let witness = WitnessInput::from_json(
+            r#"{"values": ["65535"], "count": 1, "options": {"n_bytes": 2}}"#,
+        )
+        .unwrap();
+        assert_eq!(witness.values().unwrap(), vec![65535]);
+        assert_eq!(
+            witness.builder(),
+            SortCircuitBuilder::new()
+                .elements(1)
+                .bytes(2)
+                .allow_duplicates()
+        );
+
+        // error
+        let values = |json| WitnessInput::from_json(json).and_then(|w| w.values());
+        assert!(matches!(
+            values(r#"{"values": ["1"], "options": {"n_bytes": 1, "extra": 0}}"#),
+            Err(WitnessError::Json(_))
+        ));
+        assert!(matches!(
+            values(r#"{"values": ["1", "x"]}"#),
+            Err(WitnessError::InvalidValue(1))
+        ));
+        assert!(matches!(
+            values(r#"{"values": ["256"], "options": {"n_bytes": 1}}"#),
+            Err(WitnessError::OutOfRange(0))
+        ));
+        assert!(matches!(
+            values(r#"{"values": ["1", "1"], "options": {"allow_duplicates": false}}"#),
+            Err(WitnessError::Duplicate(1))
+        ));
+        assert!(matches!(
+            values(r#"{"values": ["1"], "count": 2}"#),
+            Err(WitnessError::Count {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+}